@@ -501,29 +501,16 @@ fn main() {
             let mut deleted_node_ids = remove_selected_items(&nodes, |n| n.id, &sm);
             deleted_node_ids.extend(remove_selected_items(&filter_nodes, |n| n.id, &sm));
 
-            let cache = ctrl.cache();
-            let cache = cache.borrow();
-            let mut link_indices_to_remove: Vec<usize> = Vec::new();
-
-            for i in 0..links.row_count() {
-                if let Some(link) = links.row_data(i) {
-                    let start_node = cache
-                        .pin_positions
-                        .get(&link.start_pin_id)
-                        .map(|p| p.node_id);
-                    let end_node = cache.pin_positions.get(&link.end_pin_id).map(|p| p.node_id);
-                    if start_node.map_or(false, |id| deleted_node_ids.contains(&id))
-                        || end_node.map_or(false, |id| deleted_node_ids.contains(&id))
-                    {
-                        link_indices_to_remove.push(i);
-                    }
+            let orphaned = GraphLogic::links_orphaned_by_nodes(
+                &deleted_node_ids,
+                links.iter(),
+                &ctrl.cache().borrow(),
+            );
+            for i in (0..links.row_count()).rev() {
+                if links.row_data(i).is_some_and(|link| orphaned.contains(&link.id)) {
+                    links.remove(i);
                 }
             }
-            drop(cache);
-
-            for &i in link_indices_to_remove.iter().rev() {
-                links.remove(i);
-            }
         }
     });
 
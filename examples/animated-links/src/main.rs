@@ -90,32 +90,13 @@ fn main() {
             let cache = ctrl.cache();
             let cache = cache.borrow();
 
-            // Get pin positions from cache
-            let start_pos = match cache.pin_positions.get(&start_pin) {
-                Some(p) => p,
-                None => return SharedString::default(),
-            };
-            let end_pos = match cache.pin_positions.get(&end_pin) {
-                Some(p) => p,
-                None => return SharedString::default(),
-            };
-
-            // Get node rects to calculate absolute positions
-            let start_rect = match cache.node_rects.get(&start_pos.node_id) {
-                Some(r) => r,
-                None => return SharedString::default(),
-            };
-            let end_rect = match cache.node_rects.get(&end_pos.node_id) {
-                Some(r) => r,
-                None => return SharedString::default(),
+            // Absolute pin positions from the cache
+            let Some(((start_x, start_y), (end_x, end_y))) = cache.absolute_link(start_pin, end_pin)
+            else {
+                return SharedString::default();
             };
 
-            // Calculate absolute screen positions
             let zoom = ctrl.zoom();
-            let start_x = start_rect.x + start_pos.rel_x;
-            let start_y = start_rect.y + start_pos.rel_y;
-            let end_x = end_rect.x + end_pos.rel_x;
-            let end_y = end_rect.y + end_pos.rel_y;
 
             slint_node_editor::generate_partial_bezier_path(
                 start_x, start_y, end_x, end_y, zoom, 50.0, progress,
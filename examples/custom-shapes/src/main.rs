@@ -1,26 +1,9 @@
 use slint::{Color, Model, ModelRc, SharedString, VecModel};
-use slint_node_editor::{wire_node_editor, NodeEditorSetup, NodeGeometry};
+use slint_node_editor::{wire_node_editor, NodeEditorSetup};
 use std::rc::Rc;
 
 slint::include_modules!();
 
-/// Generate an orthogonal (Manhattan) path: Horizontal -> Vertical -> Horizontal
-/// Uses pure world coordinates - zoom is handled by the container's transform-scale
-fn generate_manhattan_path(start_x: f32, start_y: f32, end_x: f32, end_y: f32) -> String {
-    // Calculate midpoint for the vertical segment
-    let mid_x = (start_x + end_x) / 2.0;
-
-    // Construct SVG path command
-    // M sx sy -> Move to start
-    // L mid_x sy -> Line to first corner
-    // L mid_x ey -> Line to second corner
-    // L ex ey -> Line to end
-    format!(
-        "M {} {} L {} {} L {} {} L {} {}",
-        start_x, start_y, mid_x, start_y, mid_x, end_y, end_x, end_y
-    )
-}
-
 fn main() {
     let window = MainWindow::new().unwrap();
     let w = window.as_weak();
@@ -105,39 +88,13 @@ fn main() {
                 let cache = ctrl.cache();
                 let cache = cache.borrow();
 
-                let start_pos = cache.pin_positions.get(&start_pin);
-                let end_pos = cache.pin_positions.get(&end_pin);
-
-                if let (Some(start), Some(end)) = (start_pos, end_pos) {
-                    if let (Some(start_rect), Some(end_rect)) = (
-                        cache.node_rects.get(&start.node_id).map(|n| n.rect()),
-                        cache.node_rects.get(&end.node_id).map(|n| n.rect()),
-                    ) {
-                        let sx = start_rect.0 + start.rel_x;
-                        let sy = start_rect.1 + start.rel_y;
-                        let ex = end_rect.0 + end.rel_x;
-                        let ey = end_rect.1 + end.rel_y;
-
-                        if style == "orthogonal" {
-                            generate_manhattan_path(sx, sy, ex, ey).into()
-                        } else {
-                            // Use zoom=1.0 since transform-scale handles zoom
-                            slint_node_editor::generate_bezier_path(
-                                sx,
-                                sy,
-                                ex,
-                                ey,
-                                1.0,
-                                bezier_offset,
-                            )
-                            .into()
-                        }
-                    } else {
-                        SharedString::default()
-                    }
+                // Use zoom=1.0 since transform-scale handles zoom
+                let path = if style == "orthogonal" {
+                    cache.compute_link_path_orthogonal(start_pin, end_pin, 1.0, 0.0)
                 } else {
-                    SharedString::default()
-                }
+                    cache.compute_link_path_world(start_pin, end_pin, bezier_offset)
+                };
+                path.unwrap_or_default().into()
             }
         });
 
@@ -15,8 +15,9 @@
 
 use slint::{Color, Model, ModelRc, SharedString, VecModel};
 use slint_node_editor::{
-    wire_node_editor, BasicLinkValidator, CompositeValidator, GeometryCache, LinkModel,
-    LinkValidator, NodeEditorSetup, SimpleNodeGeometry, ValidationError, ValidationResult,
+    wire_node_editor, BasicLinkValidator, CompositeValidator, LinkModel, LinkValidator,
+    NodeEditorSetup, SimpleNodeGeometry, TypeValidator, ValidationCache, ValidationError,
+    ValidationResult,
 };
 use std::rc::Rc;
 
@@ -85,48 +86,6 @@ fn get_link_color(output_pin_id: i32) -> Color {
     }
 }
 
-/// Custom validator that checks type compatibility between pins.
-#[derive(Clone, Copy, Debug)]
-pub struct TypeCompatibilityValidator;
-
-impl<N, L> LinkValidator<N, L> for TypeCompatibilityValidator {
-    fn validate(
-        &self,
-        start_pin: i32,
-        end_pin: i32,
-        cache: &GeometryCache<N>,
-        _links: &[L],
-    ) -> ValidationResult {
-        // Get pin info from cache to determine which is output and which is input
-        let start_info = cache.pin_positions.get(&start_pin);
-        let end_info = cache.pin_positions.get(&end_pin);
-
-        let (start_info, _end_info) = match (start_info, end_info) {
-            (Some(s), Some(e)) => (s, e),
-            _ => return ValidationResult::Invalid(ValidationError::PinNotFound(start_pin)),
-        };
-
-        // Determine which pin is output (type 2) and which is input (type 1)
-        let (output_pin, input_pin) = if start_info.pin_type == 2 {
-            (start_pin, end_pin)
-        } else {
-            (end_pin, start_pin)
-        };
-
-        let source_type = get_data_type(output_pin);
-        let target_type = get_data_type(input_pin);
-
-        if types_compatible(source_type, target_type) {
-            ValidationResult::Valid
-        } else {
-            ValidationResult::Invalid(ValidationError::TypeMismatch {
-                expected: target_type,
-                found: source_type,
-            })
-        }
-    }
-}
-
 /// Get type name for display
 fn type_name(data_type: i32) -> &'static str {
     use data_types::*;
@@ -208,25 +167,32 @@ fn main() {
     // Wire all standard callbacks with one macro call
     wire_node_editor!(window, setup);
 
+    // Hover verdicts are memoized until the links change
+    let validation_cache = Rc::new(ValidationCache::new());
+
     // Link validation callback for hover feedback
     window.on_validate_link({
         let ctrl = setup.controller().clone();
         let links = links.clone();
+        let validation_cache = validation_cache.clone();
+
+        // Create composite validator with basic checks + type compatibility
+        let validator: CompositeValidator<SimpleNodeGeometry, LinkData> =
+            CompositeValidator::new()
+                .with(BasicLinkValidator::new(2)) // 2 = output pin type
+                .with(TypeValidator::new(2, get_data_type, types_compatible));
+
         move |start_pin, end_pin| {
+            if let Some(result) = validation_cache.get(start_pin, end_pin) {
+                return result.is_valid();
+            }
+
             let cache = ctrl.cache();
             let cache = cache.borrow();
-
-            // Create composite validator with basic checks + type compatibility
-            let validator: CompositeValidator<SimpleNodeGeometry, LinkData> =
-                CompositeValidator::new()
-                    .with(BasicLinkValidator::new(2)) // 2 = output pin type
-                    .with(TypeCompatibilityValidator);
-
             let links_vec: Vec<LinkData> = links.iter().collect();
-            matches!(
-                validator.validate(start_pin, end_pin, &cache, &links_vec),
-                ValidationResult::Valid
-            )
+            validation_cache
+                .cached_validate(start_pin, end_pin, &cache, &links_vec, &validator)
+                .is_valid()
         }
     });
 
@@ -235,6 +201,7 @@ fn main() {
         let ctrl = setup.controller().clone();
         let links = links.clone();
         let next_link_id = next_link_id.clone();
+        let validation_cache = validation_cache.clone();
         move |start_pin, end_pin| {
             println!("link_requested: {} -> {}", start_pin, end_pin);
             let cache = ctrl.cache();
@@ -244,7 +211,7 @@ fn main() {
             let validator: CompositeValidator<SimpleNodeGeometry, LinkData> =
                 CompositeValidator::new()
                     .with(BasicLinkValidator::new(2)) // 2 = output pin type
-                    .with(TypeCompatibilityValidator);
+                    .with(TypeValidator::new(2, get_data_type, types_compatible));
 
             let links_vec: Vec<LinkData> = links.iter().collect();
             match validator.validate(start_pin, end_pin, &cache, &links_vec) {
@@ -275,6 +242,7 @@ fn main() {
 
                     links.push(link);
                     next_link_id.set(next_link_id.get() + 1);
+                    validation_cache.invalidate();
                 }
                 ValidationResult::Invalid(err) => match &err {
                     ValidationError::TypeMismatch { expected, found } => {
@@ -7,7 +7,8 @@ use std::rc::Rc;
 
 use slint::{Color, Model, ModelRc, SharedString, VecModel};
 use slint_node_editor::{
-    sugiyama_layout, wire_node_editor, Direction, NodeEditorSetup, SugiyamaConfig,
+    normalize_positions, sugiyama_layout, translate_positions, wire_node_editor, Direction,
+    NodeEditorSetup, SugiyamaConfig,
 };
 
 slint::include_modules!();
@@ -91,16 +92,16 @@ fn main() {
             config.vertex_spacing = 60.0;
             config.direction = Direction::LeftToRight;
 
-            let positions = sugiyama_layout(&dag_edges, &node_sizes, &config);
+            let mut positions = sugiyama_layout(&dag_edges, &node_sizes, &config);
+            normalize_positions(&mut positions);
+            translate_positions(&mut positions, 80.0, 100.0);
 
             let index = build_node_index(&nodes);
-            let offset_x = 80.0_f32;
-            let offset_y = 100.0_f32;
             for pos in &positions {
                 if let Some(&row) = index.get(&pos.id) {
                     if let Some(mut node) = nodes.row_data(row) {
-                        node.x = pos.x as f32 + offset_x;
-                        node.y = pos.y as f32 + offset_y;
+                        node.x = pos.x as f32;
+                        node.y = pos.y as f32;
                         nodes.set_row_data(row, node);
                     }
                 }
@@ -4,11 +4,15 @@ use std::rc::Rc;
 
 use slint::{Color, Model, ModelRc, SharedString, VecModel};
 use slint_node_editor::{
-    sugiyama_layout, wire_node_editor, Direction, NodeEditorSetup, SugiyamaConfig,
+    normalize_positions, sugiyama_layout, translate_positions, wire_node_editor, BezierBias,
+    Direction, NodeEditorSetup, SugiyamaConfig,
 };
 
 slint::include_modules!();
 
+/// Flow direction used by the layout button.
+const LAYOUT_DIRECTION: Direction = Direction::LeftToRight;
+
 /// Deterministic LCG — produces the same sequence on first click after each
 /// restart, but varies across subsequent clicks within a session.
 fn random_f32() -> f32 {
@@ -134,18 +138,18 @@ fn main() {
 
             let mut config = SugiyamaConfig::default();
             config.vertex_spacing = 60.0;
-            config.direction = Direction::LeftToRight;
+            config.direction = LAYOUT_DIRECTION;
 
-            let positions = sugiyama_layout(&dag_edges, &node_sizes, &config);
+            let mut positions = sugiyama_layout(&dag_edges, &node_sizes, &config);
+            normalize_positions(&mut positions);
+            translate_positions(&mut positions, 80.0, 100.0);
 
             let index = build_node_index(&nodes);
-            let offset_x = 80.0_f32;
-            let offset_y = 100.0_f32;
             for pos in &positions {
                 if let Some(&row) = index.get(&pos.id) {
                     if let Some(mut node) = nodes.row_data(row) {
-                        node.x = pos.x as f32 + offset_x;
-                        node.y = pos.y as f32 + offset_y;
+                        node.x = pos.x as f32;
+                        node.y = pos.y as f32;
                         nodes.set_row_data(row, node);
                     }
                 }
@@ -221,6 +225,13 @@ fn main() {
         }
     });
 
+    // Links leave pins along the flow direction
+    setup.controller().set_bezier_bias(match LAYOUT_DIRECTION {
+        Direction::TopToBottom | Direction::BottomToTop => BezierBias::Vertical,
+        // `Direction` is non-exhaustive; anything else flows sideways
+        _ => BezierBias::Horizontal,
+    });
+
     // Wire all callbacks with one macro call
     wire_node_editor!(window, setup);
 
@@ -66,13 +66,51 @@
 //! }
 //! ```
 
-use crate::state::GeometryCache;
-use crate::hit_test::{find_link_at, NodeGeometry, SimpleLinkGeometry};
-use slint::SharedString;
+use crate::graph::{
+    AsyncLinkValidator, GraphLogic, LinkModel, MovableNode, SimpleLink, ValidationResult,
+};
+use crate::state::{visible_link_ids, GeometryCache, GraphSnapshot, StoredPin};
+use crate::hit_test::{
+    find_link_at, links_in_polygon, LinkGeometry, LinkSelectionMode, NodeGeometry,
+    ShapedLinkGeometry, SimpleNodeGeometry,
+};
+use crate::path::{BezierBias, LinkShape, DEFAULT_BEZIER_MIN_OFFSET};
+use crate::selection::SelectionManager;
+use crate::undo::{EditCommand, UndoStack};
+use slint::{Model, SharedString, VecModel};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+/// Application callback that applies an [`EditCommand`] to its models.
+type CommandApplier = Rc<dyn Fn(&EditCommand)>;
+
+/// Smallest zoom returned by the fit computations (matches `NodeEditor.min-zoom`).
+const FIT_MIN_ZOOM: f32 = 0.1;
+/// Largest zoom returned by the fit computations (matches `NodeEditor.max-zoom`).
+const FIT_MAX_ZOOM: f32 = 3.0;
+
+/// Identifies a copied item whose ID must be replaced on paste.
+///
+/// Passed to the ID-remapping closure of [`NodeEditorController::paste`]
+/// together with the item's original ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipboardId {
+    Node(i32),
+    Pin(i32),
+    Link(i32),
+}
+
+/// Subgraph produced by [`NodeEditorController::paste`].
+#[derive(Clone, Debug, Default)]
+pub struct PasteResult {
+    /// Pasted nodes, pins and links with fresh IDs; node rects are shifted by
+    /// the paste offset and links connect the fresh pin IDs.
+    pub graph: GraphSnapshot,
+    /// Original node ID to fresh node ID
+    pub node_ids: HashMap<i32, i32>,
+}
+
 /// Viewport and configuration state, behind a single `Rc<RefCell<_>>`.
 ///
 /// The geometry cache is kept separate (its own `Rc<RefCell<_>>`) because
@@ -82,10 +120,40 @@ struct ViewportState {
     pan_x: f32,
     pan_y: f32,
     bezier_offset: f32,
+    bezier_bias: BezierBias,
+    /// `(min, max)` zoom accepted by `set_viewport`; `None` accepts any zoom
+    zoom_limits: Option<(f32, f32)>,
     dragged_node_id: i32,
     grid_spacing: f32,
     /// Links registered for hit testing, keyed by link ID.
     links: HashMap<i32, (i32, i32)>,
+    /// World-space waypoints of registered links routed through any.
+    link_waypoints: HashMap<i32, Vec<(f32, f32)>>,
+    /// Multi-node drag in progress, if any.
+    drag: Option<DragPreview>,
+    /// Outstanding deferred validations per `(start_pin, end_pin)`.
+    pending_validations: HashMap<(i32, i32), usize>,
+}
+
+/// A registered link's geometry together with its waypoints.
+struct RoutedLink<'a> {
+    link: ShapedLinkGeometry,
+    waypoints: &'a [(f32, f32)],
+}
+
+impl LinkGeometry for RoutedLink<'_> {
+    fn id(&self) -> i32 { self.link.id }
+    fn start(&self) -> (f32, f32) { self.link.start() }
+    fn end(&self) -> (f32, f32) { self.link.end() }
+    fn waypoints(&self) -> &[(f32, f32)] { self.waypoints }
+    fn shape(&self) -> LinkShape { self.link.shape }
+}
+
+/// Nodes being dragged and the world-space delta accumulated so far.
+struct DragPreview {
+    node_ids: HashSet<i32>,
+    delta_x: f32,
+    delta_y: f32,
 }
 
 impl ViewportState {
@@ -94,17 +162,43 @@ impl ViewportState {
             zoom: 1.0,
             pan_x: 0.0,
             pan_y: 0.0,
-            bezier_offset: 50.0,
+            bezier_offset: DEFAULT_BEZIER_MIN_OFFSET,
+            bezier_bias: BezierBias::Horizontal,
+            zoom_limits: None,
             dragged_node_id: 0,
             grid_spacing: 24.0,
             links: HashMap::new(),
+            link_waypoints: HashMap::new(),
+            drag: None,
+            pending_validations: HashMap::new(),
         }
     }
 
+    /// World-space geometry of the registered links, shaped and routed as
+    /// drawn; links with unknown pins are skipped.
+    fn routed_links<'a>(
+        &'a self,
+        cache: &'a GeometryCache,
+    ) -> impl Iterator<Item = RoutedLink<'a>> + 'a {
+        let links = self.links.iter().map(|(&id, &(start_pin, end_pin))| (id, start_pin, end_pin));
+        cache.get_shaped_links(links, self.bezier_bias).map(move |link| RoutedLink {
+            link,
+            waypoints: self.link_waypoints.get(&link.id).map_or(&[], Vec::as_slice),
+        })
+    }
+
     /// Clamp zoom to a safe positive value.
     fn safe_zoom(&self) -> f32 {
         if self.zoom > 0.0 { self.zoom } else { 1.0 }
     }
+
+    /// Clamp a requested zoom to the configured limits, if any.
+    fn limit_zoom(&self, zoom: f32) -> f32 {
+        match self.zoom_limits {
+            Some((min, max)) => zoom.clamp(min, max),
+            None => zoom,
+        }
+    }
 }
 
 /// Controller that manages node editor state and provides callback implementations.
@@ -115,6 +209,7 @@ impl ViewportState {
 /// - Hit testing facades (screen-space input, world-space internals)
 /// - Viewport/zoom/pan tracking
 /// - Drag tracking
+/// - Undo/redo history of [`EditCommand`]s
 ///
 /// The cache stores node rects in world coordinates, which are invariant to
 /// zoom/pan changes. This eliminates the O(N) viewport resync loop that was
@@ -125,6 +220,9 @@ impl ViewportState {
 pub struct NodeEditorController {
     cache: Rc<RefCell<GeometryCache>>,
     state: Rc<RefCell<ViewportState>>,
+    history: Rc<RefCell<UndoStack>>,
+    command_applier: Rc<RefCell<Option<CommandApplier>>>,
+    clipboard: Rc<RefCell<Option<GraphSnapshot>>>,
 }
 
 impl Default for NodeEditorController {
@@ -139,6 +237,9 @@ impl NodeEditorController {
         Self {
             cache: Rc::new(RefCell::new(GeometryCache::new())),
             state: Rc::new(RefCell::new(ViewportState::new())),
+            history: Rc::new(RefCell::new(UndoStack::new())),
+            command_applier: Rc::new(RefCell::new(None)),
+            clipboard: Rc::new(RefCell::new(None)),
         }
     }
 
@@ -147,6 +248,20 @@ impl NodeEditorController {
         self.state.borrow_mut().bezier_offset = offset;
     }
 
+    /// Set the axis link control points extend along (default: [`BezierBias::Horizontal`]).
+    ///
+    /// Use [`BezierBias::Vertical`] when pins sit on the top and bottom of
+    /// nodes, e.g. for a top-to-bottom layered layout. Pins with an explicit
+    /// [`PinSide`](crate::PinSide) still leave along their side.
+    pub fn set_bezier_bias(&self, bias: BezierBias) {
+        self.state.borrow_mut().bezier_bias = bias;
+    }
+
+    /// Get the current bezier bias.
+    pub fn bezier_bias(&self) -> BezierBias {
+        self.state.borrow().bezier_bias
+    }
+
     /// Set the grid spacing (default: 24.0).
     pub fn set_grid_spacing(&self, spacing: f32) {
         self.state.borrow_mut().grid_spacing = spacing;
@@ -180,10 +295,11 @@ impl NodeEditorController {
             let s = state.borrow();
             cache
                 .borrow()
-                .compute_link_path_world(
+                .compute_link_path_world_biased(
                     start_pin,
                     end_pin,
                     s.bezier_offset,
+                    s.bezier_bias,
                 )
                 .unwrap_or_default()
                 .into()
@@ -217,6 +333,22 @@ impl NodeEditorController {
         self.cache.borrow_mut().handle_pin_report(pid, nid, ptype, x, y);
     }
 
+    /// Remove pins of `node_id` that are not in `keep`, for nodes whose pin
+    /// count changes at runtime.
+    ///
+    /// Returns the removed pin IDs in ascending order. See
+    /// [`GeometryCache::prune_pins_for_node`].
+    pub fn prune_pins_for_node(&self, node_id: i32, keep: &[i32]) -> Vec<i32> {
+        self.cache.borrow_mut().prune_pins_for_node(node_id, keep)
+    }
+
+    /// Apply many node rect and pin reports with a single cache borrow.
+    ///
+    /// See [`GeometryCache::apply_reports`] for the tuple layouts.
+    pub fn apply_reports(&self, nodes: &[(i32, f32, f32, f32, f32)], pins: &[(i32, i32, i32, f32, f32)]) {
+        self.cache.borrow_mut().apply_reports(nodes, pins);
+    }
+
     /// Seed a node's world-space rect directly, bypassing screen→world conversion.
     ///
     /// Use this to pre-populate the geometry cache for nodes that haven't been
@@ -242,12 +374,72 @@ impl NodeEditorController {
     /// Set viewport state: zoom, pan_x, pan_y.
     ///
     /// Since the cache stores world-space coordinates, changing zoom/pan
-    /// requires no per-node updates.
-    pub fn set_viewport(&self, zoom: f32, pan_x: f32, pan_y: f32) {
+    /// requires no per-node updates. The zoom is clamped to the range set by
+    /// [`set_zoom_limits`](Self::set_zoom_limits); the stored zoom is returned
+    /// so the app can sync the `NodeEditor`'s `zoom` when it was clamped.
+    pub fn set_viewport(&self, zoom: f32, pan_x: f32, pan_y: f32) -> f32 {
+        let mut s = self.state.borrow_mut();
+        s.zoom = s.limit_zoom(zoom);
+        s.pan_x = pan_x;
+        s.pan_y = pan_y;
+        s.zoom
+    }
+
+    /// Restrict the zoom accepted by [`set_viewport`](Self::set_viewport) and
+    /// [`zoom_at_cursor`](Self::zoom_at_cursor) to `min..=max`.
+    ///
+    /// By default any zoom is stored. The limits are swapped if `min > max`.
+    /// The current zoom is clamped right away and returned.
+    pub fn set_zoom_limits(&self, min: f32, max: f32) -> f32 {
+        let mut s = self.state.borrow_mut();
+        s.zoom_limits = Some((min.min(max), min.max(max)));
+        s.zoom = s.limit_zoom(s.zoom);
+        s.zoom
+    }
+
+    /// The `(min, max)` zoom limits, if set.
+    pub fn zoom_limits(&self) -> Option<(f32, f32)> {
+        self.state.borrow().zoom_limits
+    }
+
+    /// Like [`set_viewport`](Self::set_viewport), but limits the pan with
+    /// [`clamp_pan`] so the cached graph cannot be panned out of view.
+    ///
+    /// Returns the pan that was stored; write it back to the `NodeEditor`'s
+    /// `pan-x`/`pan-y` when it differs from the requested one. With an empty
+    /// cache the pan is stored unchanged.
+    pub fn set_viewport_clamped(
+        &self,
+        zoom: f32,
+        pan_x: f32,
+        pan_y: f32,
+        viewport_w: f32,
+        viewport_h: f32,
+        margin: f32,
+    ) -> (f32, f32) {
+        let zoom = self.state.borrow().limit_zoom(zoom);
+        let (pan_x, pan_y) = match self.cache.borrow().graph_bounds() {
+            Some(bounds) => clamp_pan(pan_x, pan_y, zoom, bounds, viewport_w, viewport_h, margin),
+            None => (pan_x, pan_y),
+        };
+        self.set_viewport(zoom, pan_x, pan_y);
+        (pan_x, pan_y)
+    }
+
+    /// Zoom to `new_zoom` around a screen point, e.g. the cursor on scroll.
+    ///
+    /// The stored pan is adjusted with [`zoom_at_point`] so the world point
+    /// under `(cursor_x, cursor_y)` stays put. `new_zoom` is clamped to the
+    /// [zoom limits](Self::set_zoom_limits) first. Returns the new
+    /// `(pan_x, pan_y)` to write back to the `NodeEditor`.
+    pub fn zoom_at_cursor(&self, new_zoom: f32, cursor_x: f32, cursor_y: f32) -> (f32, f32) {
         let mut s = self.state.borrow_mut();
-        s.zoom = zoom;
+        let new_zoom = s.limit_zoom(new_zoom);
+        let (pan_x, pan_y) = zoom_at_point(s.zoom, new_zoom, cursor_x, cursor_y, s.pan_x, s.pan_y);
+        s.zoom = new_zoom;
         s.pan_x = pan_x;
         s.pan_y = pan_y;
+        (pan_x, pan_y)
     }
 
     /// Register a link for hit testing. Idempotent: re-registering the same ID
@@ -256,14 +448,40 @@ impl NodeEditorController {
         self.state.borrow_mut().links.insert(id, (start_pin, end_pin));
     }
 
+    /// Register a link for hit testing from a [`LinkModel`], including its
+    /// [`waypoints`](LinkModel::waypoints). Idempotent like
+    /// [`register_link`](Self::register_link).
+    pub fn register_link_model<L: LinkModel>(&self, link: &L) {
+        self.register_link(link.id(), link.start_pin_id(), link.end_pin_id());
+        self.set_link_waypoints(link.id(), link.waypoints());
+    }
+
+    /// Set the world-space points a registered link is routed through.
+    ///
+    /// Hit tests then follow the route drawn by
+    /// [`generate_waypoint_path`](crate::path::generate_waypoint_path). An
+    /// empty slice routes the link as a plain bezier again.
+    pub fn set_link_waypoints(&self, id: i32, waypoints: &[(f32, f32)]) {
+        let mut s = self.state.borrow_mut();
+        if waypoints.is_empty() {
+            s.link_waypoints.remove(&id);
+        } else {
+            s.link_waypoints.insert(id, waypoints.to_vec());
+        }
+    }
+
     /// Unregister a link by ID.
     pub fn unregister_link(&self, id: i32) {
-        self.state.borrow_mut().links.remove(&id);
+        let mut s = self.state.borrow_mut();
+        s.links.remove(&id);
+        s.link_waypoints.remove(&id);
     }
 
     /// Clear all registered links.
     pub fn clear_links(&self) {
-        self.state.borrow_mut().links.clear();
+        let mut s = self.state.borrow_mut();
+        s.links.clear();
+        s.link_waypoints.clear();
     }
 
     /// Clear the geometry cache (node rects and pin positions).
@@ -271,9 +489,7 @@ impl NodeEditorController {
     /// Call this when navigating between subgraphs to prevent stale
     /// pin-to-node associations from producing incorrect link paths.
     pub fn clear_geometry(&self) {
-        let mut cache = self.cache.borrow_mut();
-        cache.node_rects.clear();
-        cache.pin_positions.clear();
+        self.cache.borrow_mut().clear();
     }
 
     /// Compute link path for given pins (screen-space output from world-space cache).
@@ -281,13 +497,14 @@ impl NodeEditorController {
         let s = self.state.borrow();
         self.cache
             .borrow()
-            .compute_link_path_screen(
+            .compute_link_path_screen_biased(
                 start_pin,
                 end_pin,
                 s.zoom,
                 s.pan_x,
                 s.pan_y,
                 s.bezier_offset,
+                s.bezier_bias,
             )
             .unwrap_or_default()
             .into()
@@ -305,6 +522,441 @@ impl NodeEditorController {
         crate::generate_grid_commands(width, height, 1.0, 0.0, 0.0, spacing).into()
     }
 
+    // === Undo/redo ===
+
+    /// Set the callback that applies commands to the application's models.
+    ///
+    /// [`undo`](Self::undo) calls it with the inverse of the last command and
+    /// [`redo`](Self::redo) with the original. Link commands also update the
+    /// controller's registered links before the applier runs.
+    pub fn set_command_applier(&self, applier: impl Fn(&EditCommand) + 'static) {
+        *self.command_applier.borrow_mut() = Some(Rc::new(applier));
+    }
+
+    /// Record an edit that has already been applied.
+    ///
+    /// Clears the redo history.
+    pub fn push_command(&self, command: EditCommand) {
+        self.history.borrow_mut().push(command);
+    }
+
+    /// Revert the last recorded edit. Returns `false` if there was nothing to undo.
+    pub fn undo(&self) -> bool {
+        let command = self.history.borrow_mut().undo();
+        match command {
+            Some(command) => {
+                self.apply_command(&command);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-apply the last undone edit. Returns `false` if there was nothing to redo.
+    pub fn redo(&self) -> bool {
+        let command = self.history.borrow_mut().redo();
+        match command {
+            Some(command) => {
+                self.apply_command(&command);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether [`undo`](Self::undo) would do anything.
+    pub fn can_undo(&self) -> bool {
+        self.history.borrow().can_undo()
+    }
+
+    /// Whether [`redo`](Self::redo) would do anything.
+    pub fn can_redo(&self) -> bool {
+        self.history.borrow().can_redo()
+    }
+
+    /// Drop the undo/redo history.
+    pub fn clear_history(&self) {
+        self.history.borrow_mut().clear();
+    }
+
+    fn apply_command(&self, command: &EditCommand) {
+        self.sync_links_for_command(command);
+        // Clone the applier out so it may call back into the controller
+        let applier = self.command_applier.borrow().clone();
+        if let Some(applier) = applier {
+            applier(command);
+        }
+    }
+
+    fn sync_links_for_command(&self, command: &EditCommand) {
+        match command {
+            EditCommand::CreateLink {
+                link_id,
+                start_pin,
+                end_pin,
+            } => self.register_link(*link_id, *start_pin, *end_pin),
+            EditCommand::DeleteLink { link_id, .. } => self.unregister_link(*link_id),
+            EditCommand::Batch(commands) => {
+                for command in commands {
+                    self.sync_links_for_command(command);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // === Clipboard ===
+
+    /// Copy the selected nodes, their pins and their internal links.
+    ///
+    /// Node rects and pins are taken from the geometry cache; selected nodes
+    /// without cached geometry are skipped. Only links whose start and end
+    /// pins both belong to copied nodes are kept. Replaces the previous
+    /// clipboard contents and returns the number of copied nodes.
+    pub fn copy_selection<I, L>(&self, selection: &SelectionManager, links: I) -> usize
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+    {
+        let mut snapshot = self.cache.borrow().snapshot();
+        snapshot.nodes.retain(|node| selection.contains(node.id));
+        snapshot.pins.retain(|(_, pin)| selection.contains(pin.node_id));
+        let kept: HashSet<i32> = snapshot.pins.iter().map(|(id, _)| *id).collect();
+        snapshot.pin_sides.retain(|(id, _)| kept.contains(id));
+
+        let copied_pins: HashMap<i32, i32> = snapshot
+            .pins
+            .iter()
+            .map(|(id, pin)| (*id, pin.node_id))
+            .collect();
+        let internal = links.into_iter().filter(|link| {
+            copied_pins.contains_key(&link.start_pin_id()) && copied_pins.contains_key(&link.end_pin_id())
+        });
+        let mut snapshot = snapshot.with_links(internal);
+        snapshot.links.sort_by_key(|link| link.id);
+
+        let copied = snapshot.nodes.len();
+        *self.clipboard.borrow_mut() = Some(snapshot);
+        copied
+    }
+
+    /// Whether [`copy_selection`](Self::copy_selection) has stored anything to paste.
+    pub fn has_clipboard(&self) -> bool {
+        self.clipboard
+            .borrow()
+            .as_ref()
+            .is_some_and(|snapshot| !snapshot.nodes.is_empty())
+    }
+
+    /// Produce a fresh copy of the clipboard contents, shifted by the offset.
+    ///
+    /// `remap` is called once per copied node, pin and link (in that order,
+    /// each by ascending original ID) and returns the ID to use for the
+    /// pasted item. The controller does not create anything itself: add the
+    /// returned nodes and links to your models and let the new components
+    /// report their geometry. The clipboard is kept, so pasting repeatedly
+    /// yields independent copies. Returns an empty result if nothing was copied.
+    pub fn paste(
+        &self,
+        offset_x: f32,
+        offset_y: f32,
+        mut remap: impl FnMut(ClipboardId) -> i32,
+    ) -> PasteResult {
+        let clipboard = self.clipboard.borrow();
+        let Some(copied) = clipboard.as_ref() else {
+            return PasteResult::default();
+        };
+
+        let mut result = PasteResult::default();
+        for node in &copied.nodes {
+            let id = remap(ClipboardId::Node(node.id));
+            result.node_ids.insert(node.id, id);
+            result.graph.nodes.push(SimpleNodeGeometry {
+                id,
+                x: node.x + offset_x,
+                y: node.y + offset_y,
+                ..*node
+            });
+        }
+
+        let mut pin_ids: HashMap<i32, i32> = HashMap::new();
+        for (pin_id, pin) in &copied.pins {
+            let Some(&node_id) = result.node_ids.get(&pin.node_id) else {
+                continue;
+            };
+            let id = remap(ClipboardId::Pin(*pin_id));
+            pin_ids.insert(*pin_id, id);
+            result.graph.pins.push((id, StoredPin { node_id, ..*pin }));
+        }
+        for (pin_id, side) in &copied.pin_sides {
+            if let Some(&id) = pin_ids.get(pin_id) {
+                result.graph.pin_sides.push((id, *side));
+            }
+        }
+
+        for link in &copied.links {
+            if let (Some(&start_pin_id), Some(&end_pin_id)) =
+                (pin_ids.get(&link.start_pin_id), pin_ids.get(&link.end_pin_id))
+            {
+                result.graph.links.push(SimpleLink {
+                    id: remap(ClipboardId::Link(link.id)),
+                    start_pin_id,
+                    end_pin_id,
+                    ..link.clone()
+                });
+            }
+        }
+        result
+    }
+
+    // === Drag preview ===
+
+    /// Start dragging the currently selected nodes.
+    ///
+    /// The selection is captured now; until [`commit_drag`](Self::commit_drag)
+    /// or [`cancel_drag`](Self::cancel_drag) the accumulated delta is only
+    /// available through [`preview_offset`](Self::preview_offset), so apps
+    /// can render a live preview without touching their model. Replaces a
+    /// drag that was still in progress.
+    pub fn begin_drag(&self, selection: &SelectionManager) {
+        self.state.borrow_mut().drag = Some(DragPreview {
+            node_ids: selection.iter().copied().collect(),
+            delta_x: 0.0,
+            delta_y: 0.0,
+        });
+    }
+
+    /// Add `(dx, dy)` world units to the drag delta. No-op without a drag.
+    pub fn update_drag(&self, dx: f32, dy: f32) {
+        if let Some(drag) = &mut self.state.borrow_mut().drag {
+            drag.delta_x += dx;
+            drag.delta_y += dy;
+        }
+    }
+
+    /// Whether a drag started with [`begin_drag`](Self::begin_drag) is in progress.
+    pub fn is_dragging(&self) -> bool {
+        self.state.borrow().drag.is_some()
+    }
+
+    /// Preview offset for a node: the accumulated delta if the node is being
+    /// dragged, `(0.0, 0.0)` otherwise.
+    pub fn preview_offset(&self, node_id: i32) -> (f32, f32) {
+        match &self.state.borrow().drag {
+            Some(drag) if drag.node_ids.contains(&node_id) => (drag.delta_x, drag.delta_y),
+            _ => (0.0, 0.0),
+        }
+    }
+
+    /// Like [`preview_offset`](Self::preview_offset), but snapped so a node
+    /// at `node_origin` lands on the controller's grid.
+    ///
+    /// Uses the spacing from [`set_grid_spacing`](Self::set_grid_spacing);
+    /// see [`GraphLogic::snap_drag_delta`]. Nodes that are not being dragged
+    /// get `(0.0, 0.0)`.
+    pub fn snapped_preview_offset(&self, node_id: i32, node_origin: (f32, f32)) -> (f32, f32) {
+        let state = self.state.borrow();
+        match &state.drag {
+            Some(drag) if drag.node_ids.contains(&node_id) => GraphLogic::snap_drag_delta(
+                node_origin,
+                (drag.delta_x, drag.delta_y),
+                state.grid_spacing,
+            ),
+            _ => (0.0, 0.0),
+        }
+    }
+
+    /// Apply the accumulated delta to the dragged nodes in `model` and end
+    /// the drag.
+    ///
+    /// Returns the applied `(delta_x, delta_y)`, e.g. for an
+    /// [`EditCommand::MoveNodes`], or `None` if no drag was in progress.
+    pub fn commit_drag<T: MovableNode>(&self, model: &VecModel<T>) -> Option<(f32, f32)> {
+        let drag = self.state.borrow_mut().drag.take()?;
+        let mut dragged = SelectionManager::new();
+        dragged.replace_selection(drag.node_ids);
+        GraphLogic::commit_drag(model, &dragged, drag.delta_x, drag.delta_y);
+        Some((drag.delta_x, drag.delta_y))
+    }
+
+    /// Like [`commit_drag`](Self::commit_drag), but moves each dragged node
+    /// by its [`snapped_preview_offset`](Self::snapped_preview_offset), so
+    /// the nodes land where the snapped preview showed them.
+    ///
+    /// Returns an [`EditCommand::Batch`] with one [`EditCommand::MoveNodes`]
+    /// per moved node (their deltas differ), or `None` if no drag was in
+    /// progress.
+    pub fn commit_drag_snapped<T: MovableNode>(&self, model: &VecModel<T>) -> Option<EditCommand> {
+        let mut state = self.state.borrow_mut();
+        let drag = state.drag.take()?;
+        let spacing = state.grid_spacing;
+        drop(state);
+
+        let mut moves = Vec::new();
+        for row in 0..model.row_count() {
+            let Some(mut node) = model.row_data(row) else { continue };
+            let id = MovableNode::id(&node);
+            if !drag.node_ids.contains(&id) {
+                continue;
+            }
+            let (delta_x, delta_y) = GraphLogic::snap_drag_delta(
+                (node.x(), node.y()),
+                (drag.delta_x, drag.delta_y),
+                spacing,
+            );
+            node.set_x(node.x() + delta_x);
+            node.set_y(node.y() + delta_y);
+            model.set_row_data(row, node);
+            moves.push(EditCommand::MoveNodes { node_ids: vec![id], delta_x, delta_y });
+        }
+        Some(EditCommand::Batch(moves))
+    }
+
+    /// End the drag without changing any model.
+    pub fn cancel_drag(&self) {
+        self.state.borrow_mut().drag = None;
+    }
+
+    // === Deferred validation ===
+
+    /// Validate a link with an [`AsyncLinkValidator`], tracking it as pending.
+    ///
+    /// The pin pair counts as pending until the validator delivers its
+    /// verdict, so the UI can draw the candidate link in a "running" style
+    /// (see [`is_link_pending`](Self::is_link_pending)). `on_result` is called
+    /// with the verdict after the pair stops being pending.
+    ///
+    /// Validators that resolve immediately call `on_result` before this
+    /// method returns, while the geometry cache is still borrowed; defer any
+    /// cache mutation in `on_result` (e.g. via `slint::invoke_from_event_loop`).
+    pub fn validate_link_deferred<V, L, F>(
+        &self,
+        validator: &V,
+        start_pin: i32,
+        end_pin: i32,
+        links: &[L],
+        on_result: F,
+    ) where
+        V: AsyncLinkValidator<SimpleNodeGeometry, L> + ?Sized,
+        F: FnOnce(ValidationResult) + 'static,
+    {
+        let key = (start_pin, end_pin);
+        *self.state.borrow_mut().pending_validations.entry(key).or_insert(0) += 1;
+
+        let state = Rc::downgrade(&self.state);
+        let cache = self.cache.borrow();
+        validator.validate_deferred(start_pin, end_pin, &cache, links, move |result| {
+            if let Some(state) = state.upgrade() {
+                let mut s = state.borrow_mut();
+                if let Some(count) = s.pending_validations.get_mut(&key) {
+                    *count -= 1;
+                    if *count == 0 {
+                        s.pending_validations.remove(&key);
+                    }
+                }
+            }
+            on_result(result);
+        });
+    }
+
+    /// Whether a deferred validation for this pin pair is still outstanding.
+    pub fn is_link_pending(&self, start_pin: i32, end_pin: i32) -> bool {
+        self.state
+            .borrow()
+            .pending_validations
+            .contains_key(&(start_pin, end_pin))
+    }
+
+    /// Number of deferred validations still waiting for a verdict.
+    pub fn pending_validation_count(&self) -> usize {
+        self.state.borrow().pending_validations.values().sum()
+    }
+
+    // === Coordinate conversion ===
+
+    /// Convert a screen-space point to world space using the stored viewport.
+    ///
+    /// `world = (screen - pan) / zoom`, e.g. to place a new or pasted node
+    /// under the cursor. A non-positive zoom is treated as 1.0.
+    pub fn screen_to_world(&self, x: f32, y: f32) -> (f32, f32) {
+        let s = self.state.borrow();
+        let z = s.safe_zoom();
+        ((x - s.pan_x) / z, (y - s.pan_y) / z)
+    }
+
+    /// Convert a world-space point to screen space using the stored viewport.
+    ///
+    /// Inverse of [`screen_to_world`](Self::screen_to_world):
+    /// `screen = world * zoom + pan`.
+    pub fn world_to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+        let s = self.state.borrow();
+        let z = s.safe_zoom();
+        (x * z + s.pan_x, y * z + s.pan_y)
+    }
+
+    /// World position for a node of `node_w` x `node_h` (world units) dropped
+    /// at a screen point.
+    ///
+    /// With `center` the node is centered on the point, otherwise its top-left
+    /// corner is placed there. The result is snapped to the grid spacing set by
+    /// [`set_grid_spacing`](Self::set_grid_spacing) (no snapping when it is not
+    /// positive), see [`GraphLogic::snap_drag_delta`].
+    pub fn world_position_for_new_node(
+        &self,
+        screen_x: f32,
+        screen_y: f32,
+        node_w: f32,
+        node_h: f32,
+        center: bool,
+    ) -> (f32, f32) {
+        let (mut x, mut y) = self.screen_to_world(screen_x, screen_y);
+        if center {
+            x -= node_w / 2.0;
+            y -= node_h / 2.0;
+        }
+        let spacing = self.state.borrow().grid_spacing;
+        GraphLogic::snap_drag_delta((0.0, 0.0), (x, y), spacing)
+    }
+
+    // === Viewport framing ===
+
+    /// Compute `(zoom, pan_x, pan_y)` that frames all cached nodes.
+    ///
+    /// The content is centered in a `viewport_w` x `viewport_h` viewport with
+    /// at least `padding` screen pixels of margin, and the zoom is clamped to
+    /// the editor's default range. Returns `(1.0, 0.0, 0.0)` when the cache is
+    /// empty. The controller's viewport is not changed; apply the result to
+    /// the `NodeEditor`'s `zoom`, `pan-x` and `pan-y` properties.
+    pub fn compute_fit_viewport(&self, viewport_w: f32, viewport_h: f32, padding: f32) -> (f32, f32, f32) {
+        match self.cache.borrow().graph_bounds() {
+            Some(bounds) => fit_bounds(bounds, viewport_w, viewport_h, padding),
+            None => (1.0, 0.0, 0.0),
+        }
+    }
+
+    /// Compute `(zoom, pan_x, pan_y)` that frames only the selected nodes.
+    ///
+    /// Same framing rules as [`compute_fit_viewport`](Self::compute_fit_viewport),
+    /// using the bounding box of the selected nodes' cached rects. Returns
+    /// `None` when no selected node has cached geometry.
+    pub fn compute_fit_selection(
+        &self,
+        selection: &SelectionManager,
+        viewport_w: f32,
+        viewport_h: f32,
+        padding: f32,
+    ) -> Option<(f32, f32, f32)> {
+        let cache = self.cache.borrow();
+        let bounds = selection
+            .iter()
+            .filter_map(|id| cache.node_rects.get(id).map(|node| node.rect()))
+            .fold(None, |bounds, (x, y, w, h)| {
+                let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((x, y, x + w, y + h));
+                Some((min_x.min(x), min_y.min(y), max_x.max(x + w), max_y.max(y + h)))
+            })?;
+        Some(fit_bounds(bounds, viewport_w, viewport_h, padding))
+    }
+
     // === Screen-space hit-testing facades ===
     //
     // These methods accept screen-space mouse coordinates and handle all
@@ -318,6 +970,7 @@ impl NodeEditorController {
     /// Find the link closest to the given world-space position.
     ///
     /// Returns the link ID, or -1 if no link is within `hover_distance`.
+    /// Curves use the bias set with [`set_bezier_bias`](Self::set_bezier_bias).
     pub fn find_link_at_world(
         &self,
         mouse_x: f32,
@@ -330,20 +983,7 @@ impl NodeEditorController {
         let zoom = s.safe_zoom();
         let cache = self.cache.borrow();
 
-        let link_geometries = s.links.iter().filter_map(|(&id, &(start_pin, end_pin))| {
-            let start_pos = cache.pin_positions.get(&start_pin)?;
-            let end_pos = cache.pin_positions.get(&end_pin)?;
-            let start_rect = cache.node_rects.get(&start_pos.node_id)?.rect();
-            let end_rect = cache.node_rects.get(&end_pos.node_id)?.rect();
-
-            Some(SimpleLinkGeometry {
-                id,
-                start_x: start_rect.0 + start_pos.rel_x,
-                start_y: start_rect.1 + start_pos.rel_y,
-                end_x: end_rect.0 + end_pos.rel_x,
-                end_y: end_rect.1 + end_pos.rel_y,
-            })
-        });
+        let link_geometries = s.routed_links(&cache);
 
         find_link_at(
             mouse_x,
@@ -381,7 +1021,8 @@ impl NodeEditorController {
 
     /// Find the pin closest to the given screen-space position.
     ///
-    /// Returns the pin ID, or 0 if no pin is within `hit_radius`.
+    /// Returns the pin ID, or 0 if no pin is within `hit_radius`. Pins with a
+    /// [`GeometryCache::pin_hit_radius`] use that screen-space radius instead.
     pub fn find_pin_at_screen(&self, mouse_x: f32, mouse_y: f32, hit_radius: f32) -> i32 {
         let s = self.state.borrow();
         let zoom = s.safe_zoom();
@@ -395,10 +1036,11 @@ impl NodeEditorController {
             // World→screen: (node_world + pin_rel) * zoom + pan
             let sx = (rect.0 + pin.rel_x) * zoom + pan_x;
             let sy = (rect.1 + pin.rel_y) * zoom + pan_y;
-            Some(crate::hit_test::SimplePinGeometry {
+            Some(crate::hit_test::SizedPinGeometry {
                 id: pin_id,
                 x: sx,
                 y: sy,
+                hit_radius: cache.pin_hit_radius(pin_id),
             })
         });
 
@@ -428,6 +1070,76 @@ impl NodeEditorController {
             .nodes_in_selection_box(world_x, world_y, world_w, world_h)
     }
 
+    /// Find all nodes whose center lies inside a screen-space lasso polygon.
+    ///
+    /// Converts each vertex from screen→world and delegates to
+    /// [`GeometryCache::nodes_in_polygon`].
+    pub fn nodes_in_polygon_screen(&self, polygon: &[(f32, f32)]) -> Vec<i32> {
+        let s = self.state.borrow();
+        let z = s.safe_zoom();
+        let world: Vec<(f32, f32)> = polygon
+            .iter()
+            .map(|&(x, y)| ((x - s.pan_x) / z, (y - s.pan_y) / z))
+            .collect();
+        drop(s);
+
+        self.cache.borrow().nodes_in_polygon(&world)
+    }
+
+    /// Find all registered links whose curve touches a world-space lasso polygon.
+    ///
+    /// Curves match [`compute_link_path_callback`](Self::compute_link_path_callback)
+    /// (world space, current bezier offset and bias) and follow the links'
+    /// waypoints, sampled `samples` times.
+    pub fn links_in_polygon_world(&self, polygon: &[(f32, f32)], samples: usize) -> Vec<i32> {
+        let s = self.state.borrow();
+        let cache = self.cache.borrow();
+        links_in_polygon(polygon, s.routed_links(&cache), 1.0, s.bezier_offset, samples)
+    }
+
+    /// Find all registered links whose curve touches a screen-space lasso polygon.
+    ///
+    /// Converts each vertex from screen→world and delegates to [`links_in_polygon_world`](Self::links_in_polygon_world).
+    pub fn links_in_polygon_screen(&self, polygon: &[(f32, f32)], samples: usize) -> Vec<i32> {
+        let s = self.state.borrow();
+        let z = s.safe_zoom();
+        let world: Vec<(f32, f32)> = polygon
+            .iter()
+            .map(|&(x, y)| ((x - s.pan_x) / z, (y - s.pan_y) / z))
+            .collect();
+        drop(s);
+
+        self.links_in_polygon_world(&world, samples)
+    }
+
+    /// Screen-space viewport `(0, 0, width, height)` converted to world space.
+    fn viewport_world_rect(&self, width: f32, height: f32) -> (f32, f32, f32, f32) {
+        let s = self.state.borrow();
+        let z = s.safe_zoom();
+        (-s.pan_x / z, -s.pan_y / z, width / z, height / z)
+    }
+
+    /// Nodes visible in a viewport of `width` x `height` screen pixels, in ascending order.
+    ///
+    /// See [`GeometryCache::visible_nodes`].
+    pub fn visible_nodes_screen(&self, width: f32, height: f32) -> Vec<i32> {
+        let viewport = self.viewport_world_rect(width, height);
+        self.cache.borrow().visible_nodes(viewport)
+    }
+
+    /// Registered links visible in a viewport of `width` x `height` screen
+    /// pixels, in ascending order.
+    ///
+    /// See [`GeometryCache::visible_links`]; curves use the current bezier bias.
+    pub fn visible_links_screen(&self, width: f32, height: f32) -> Vec<i32> {
+        let viewport = self.viewport_world_rect(width, height);
+        let s = self.state.borrow();
+        let cache = self.cache.borrow();
+        let mut ids = visible_link_ids(viewport, s.routed_links(&cache), s.bezier_offset);
+        ids.sort_unstable();
+        ids
+    }
+
     /// Find all links that have at least one endpoint inside the given world-space selection box.
     pub fn links_in_selection_box_world(
         &self,
@@ -440,27 +1152,19 @@ impl NodeEditorController {
         let cache = self.cache.borrow();
 
         // Compute world-space link endpoints: node_world + pin_rel
-        let link_geometries = s.links.iter().filter_map(|(&id, &(start_pin, end_pin))| {
-            let start_pos = cache.pin_positions.get(&start_pin)?;
-            let end_pos = cache.pin_positions.get(&end_pin)?;
-            let start_rect = cache.node_rects.get(&start_pos.node_id)?.rect();
-            let end_rect = cache.node_rects.get(&end_pos.node_id)?.rect();
-
-            Some(SimpleLinkGeometry {
-                id,
-                start_x: start_rect.0 + start_pos.rel_x,
-                start_y: start_rect.1 + start_pos.rel_y,
-                end_x: end_rect.0 + end_pos.rel_x,
-                end_y: end_rect.1 + end_pos.rel_y,
-            })
-        });
+        let link_geometries = s.routed_links(&cache);
 
-        crate::hit_test::links_in_selection_box(
+        // Self-loops are bounded as drawn by compute_link_path_callback
+        crate::hit_test::links_in_selection_box_with_mode(
             world_x,
             world_y,
             world_w,
             world_h,
             link_geometries,
+            LinkSelectionMode::EitherEndpoint,
+            1.0,
+            s.bezier_offset,
+            0,
         )
     }
 
@@ -486,11 +1190,96 @@ impl NodeEditorController {
     }
 }
 
+/// Zoom and pan that center world-space `bounds` in the viewport.
+fn fit_bounds(
+    (min_x, min_y, max_x, max_y): (f32, f32, f32, f32),
+    viewport_w: f32,
+    viewport_h: f32,
+    padding: f32,
+) -> (f32, f32, f32) {
+    let available_w = (viewport_w - 2.0 * padding).max(1.0);
+    let available_h = (viewport_h - 2.0 * padding).max(1.0);
+    let content_w = (max_x - min_x).max(f32::EPSILON);
+    let content_h = (max_y - min_y).max(f32::EPSILON);
+
+    let zoom = (available_w / content_w)
+        .min(available_h / content_h)
+        .clamp(FIT_MIN_ZOOM, FIT_MAX_ZOOM);
+    let pan_x = viewport_w / 2.0 - (min_x + max_x) / 2.0 * zoom;
+    let pan_y = viewport_h / 2.0 - (min_y + max_y) / 2.0 * zoom;
+    (zoom, pan_x, pan_y)
+}
+
+/// Pan that keeps the world point under a screen position fixed across a zoom change
+///
+/// With `screen = world * zoom + pan`, the world point under
+/// `(cursor_x, cursor_y)` at `old_zoom` is mapped back to the same screen
+/// position at `new_zoom`. Returns the pan unchanged if either zoom is not
+/// positive.
+pub fn zoom_at_point(
+    old_zoom: f32,
+    new_zoom: f32,
+    cursor_x: f32,
+    cursor_y: f32,
+    pan_x: f32,
+    pan_y: f32,
+) -> (f32, f32) {
+    if old_zoom <= 0.0 || new_zoom <= 0.0 {
+        return (pan_x, pan_y);
+    }
+    let ratio = new_zoom / old_zoom;
+    (
+        cursor_x - (cursor_x - pan_x) * ratio,
+        cursor_y - (cursor_y - pan_y) * ratio,
+    )
+}
+
+/// Limit a pan offset so the graph stays at least partly on screen
+///
+/// `graph_bounds` are `(min_x, min_y, max_x, max_y)` in world space, as from
+/// [`GeometryCache::graph_bounds`]. The pan is adjusted so the content's far
+/// edges stay at least `margin` pixels inside the viewport: its right edge
+/// no further left than `margin`, its left edge no further right than
+/// `viewport_w - margin`, and likewise vertically. A pan that already
+/// satisfies this is returned unchanged, as is any pan for a non-positive
+/// zoom.
+pub fn clamp_pan(
+    pan_x: f32,
+    pan_y: f32,
+    zoom: f32,
+    graph_bounds: (f32, f32, f32, f32),
+    viewport_w: f32,
+    viewport_h: f32,
+    margin: f32,
+) -> (f32, f32) {
+    if zoom <= 0.0 {
+        return (pan_x, pan_y);
+    }
+    let (min_x, min_y, max_x, max_y) = graph_bounds;
+    // screen = world * zoom + pan
+    let clamp_axis = |pan: f32, min: f32, max: f32, extent: f32| {
+        let lowest = margin - max * zoom;
+        let highest = extent - margin - min * zoom;
+        if lowest > highest {
+            // Margin larger than viewport plus content: center instead
+            (lowest + highest) / 2.0
+        } else {
+            pan.clamp(lowest, highest)
+        }
+    };
+    (
+        clamp_axis(pan_x, min_x, max_x, viewport_w),
+        clamp_axis(pan_y, min_y, max_y, viewport_h),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(deprecated)]
     use super::*;
-    use crate::hit_test::NodeGeometry;
+    use crate::hit_test::{NodeGeometry, PinSide};
+    use crate::path::{distance_to_bezier, CubicBezier, LinkShape};
+    use slint::Model;
 
     /// Helper: set up a controller with two nodes and pins, suitable for hit testing.
     fn setup_controller() -> NodeEditorController {
@@ -535,6 +1324,34 @@ mod tests {
         assert_eq!(s.pan_y, 20.0);
     }
 
+    #[test]
+    fn test_set_viewport_clamps_to_zoom_limits() {
+        let ctrl = NodeEditorController::new();
+        assert_eq!(ctrl.zoom_limits(), None);
+        assert_eq!(ctrl.set_viewport(10.0, 0.0, 0.0), 10.0);
+
+        // Tightening the limits clamps the current zoom immediately
+        assert_eq!(ctrl.set_zoom_limits(0.25, 4.0), 4.0);
+        assert_eq!(ctrl.zoom(), 4.0);
+
+        assert_eq!(ctrl.set_viewport(8.0, 5.0, 6.0), 4.0);
+        assert_eq!(ctrl.zoom(), 4.0);
+        assert_eq!(ctrl.set_viewport(0.1, 5.0, 6.0), 0.25);
+        assert_eq!(ctrl.zoom(), 0.25);
+        assert_eq!(ctrl.set_viewport(1.5, 5.0, 6.0), 1.5);
+    }
+
+    #[test]
+    fn test_zoom_limits_swapped_and_used_by_zoom_at_cursor() {
+        let ctrl = NodeEditorController::new();
+        ctrl.set_zoom_limits(2.0, 0.5);
+        assert_eq!(ctrl.zoom_limits(), Some((0.5, 2.0)));
+
+        let pan = ctrl.zoom_at_cursor(5.0, 0.0, 0.0);
+        assert_eq!(ctrl.zoom(), 2.0);
+        assert_eq!(pan, (0.0, 0.0));
+    }
+
     #[test]
     #[allow(deprecated)]
     fn test_set_zoom_deprecated_still_works() {
@@ -543,6 +1360,22 @@ mod tests {
         assert_eq!(ctrl.zoom(), 3.0);
     }
 
+    #[test]
+    fn test_set_bezier_bias_changes_link_path() {
+        let ctrl = setup_controller();
+        assert_eq!(ctrl.bezier_bias(), BezierBias::Horizontal);
+        let horizontal = ctrl.compute_link_path(1001, 2001);
+        let horizontal_world = ctrl.compute_link_path_callback()(1001, 2001, 0);
+
+        ctrl.set_bezier_bias(BezierBias::Vertical);
+        assert_eq!(ctrl.bezier_bias(), BezierBias::Vertical);
+        let vertical = ctrl.compute_link_path(1001, 2001);
+        assert_ne!(vertical, horizontal);
+        assert_ne!(ctrl.compute_link_path_callback()(1001, 2001, 0), horizontal_world);
+        // Vertical control points share the x of their endpoints
+        assert!(vertical.starts_with("M 100 25 C 100 "));
+    }
+
     // ========================================================================
     // Link registration (HashMap-based, idempotent)
     // ========================================================================
@@ -568,8 +1401,10 @@ mod tests {
     fn test_unregister_link() {
         let ctrl = NodeEditorController::new();
         ctrl.register_link(1, 100, 200);
+        ctrl.set_link_waypoints(1, &[(10.0, 10.0)]);
         ctrl.unregister_link(1);
         assert!(ctrl.state.borrow().links.is_empty());
+        assert!(ctrl.state.borrow().link_waypoints.is_empty());
     }
 
     #[test]
@@ -631,6 +1466,80 @@ mod tests {
         assert_eq!(result, 1);
     }
 
+    #[test]
+    fn test_find_link_at_world_follows_pin_sides() {
+        let ctrl = setup_controller();
+        {
+            let mut cache = ctrl.cache.borrow_mut();
+            cache.set_pin_side(1001, PinSide::Bottom);
+            cache.set_pin_side(2001, PinSide::Top);
+        }
+        let shape = LinkShape::default().with_sides(PinSide::Bottom, PinSide::Top);
+        let sided = CubicBezier::from_link_shape((100.0, 25.0), (200.0, 125.0), shape, 1.0, 50.0);
+        let plain = CubicBezier::from_endpoints(100.0, 25.0, 200.0, 125.0, 1.0, 50.0);
+        let (x, y) = sided.eval(0.25);
+        // The point is on the Bottom -> Top curve but off the default horizontal one
+        assert!(distance_to_bezier((x, y), &plain, 50) > 5.0);
+
+        assert_eq!(ctrl.find_link_at_world(x, y, 5.0, 50.0, 50), 1);
+    }
+
+    #[test]
+    fn test_find_link_at_world_follows_bezier_bias() {
+        let ctrl = setup_controller();
+        let vertical = CubicBezier::from_endpoints_biased(
+            100.0,
+            25.0,
+            200.0,
+            125.0,
+            1.0,
+            50.0,
+            BezierBias::Vertical,
+        );
+        let (x, y) = vertical.eval(0.25);
+        assert_eq!(ctrl.find_link_at_world(x, y, 5.0, 50.0, 50), -1);
+
+        ctrl.set_bezier_bias(BezierBias::Vertical);
+        assert_eq!(ctrl.find_link_at_world(x, y, 5.0, 50.0, 50), 1);
+        let (lo_x, lo_y, hi_x, hi_y) = (x - 2.0, y - 2.0, x + 2.0, y + 2.0);
+        let lasso = [(lo_x, lo_y), (hi_x, lo_y), (hi_x, hi_y), (lo_x, hi_y)];
+        assert_eq!(ctrl.links_in_polygon_world(&lasso, 50), vec![1]);
+    }
+
+    #[test]
+    fn test_same_node_link_drawn_and_hit_as_loop() {
+        let ctrl = setup_controller();
+        // Input pin on the left edge of node 1, linked from node 1's output
+        ctrl.cache.borrow_mut().handle_pin_report(1002, 1, 1, 0.0, 25.0);
+        ctrl.register_link(2, 1001, 1002);
+
+        let loop_curve = CubicBezier::self_loop_between((100.0, 25.0), (0.0, 25.0), 50.0, 1.0);
+        let path = ctrl.compute_link_path_callback()(1001, 1002, 0);
+        let (p1, p2) = (loop_curve.p1, loop_curve.p2);
+        assert_eq!(path.as_str(), format!("M 100 25 C {} {} {} {} 0 25", p1.0, p1.1, p2.0, p2.1));
+
+        // Top of the loop, well above node 1
+        let (x, y) = loop_curve.eval(0.5);
+        assert_eq!(ctrl.find_link_at_world(x, y, 5.0, 50.0, 50), 2);
+        assert_eq!(ctrl.links_in_selection_box_world(x - 5.0, y - 5.0, 10.0, 10.0), vec![2]);
+    }
+
+    #[test]
+    fn test_controller_hit_tests_follow_link_waypoints() {
+        let ctrl = setup_controller();
+        let waypoint = (150.0, -150.0);
+        assert_eq!(ctrl.find_link_at_world(waypoint.0, waypoint.1, 5.0, 50.0, 50), -1);
+
+        ctrl.set_link_waypoints(1, &[waypoint]);
+        assert_eq!(ctrl.find_link_at_world(waypoint.0, waypoint.1, 5.0, 50.0, 50), 1);
+        let (lo_x, lo_y, hi_x, hi_y) = (145.0, -155.0, 155.0, -145.0);
+        let lasso = [(lo_x, lo_y), (hi_x, lo_y), (hi_x, hi_y), (lo_x, hi_y)];
+        assert_eq!(ctrl.links_in_polygon_world(&lasso, 50), vec![1]);
+
+        ctrl.set_link_waypoints(1, &[]);
+        assert_eq!(ctrl.find_link_at_world(waypoint.0, waypoint.1, 5.0, 50.0, 50), -1);
+    }
+
     // ========================================================================
     // find_pin_at_screen at various zoom levels
     // ========================================================================
@@ -643,6 +1552,22 @@ mod tests {
         assert_eq!(result, 1001);
     }
 
+    #[test]
+    fn test_find_pin_at_screen_uses_pin_hit_radius() {
+        let ctrl = setup_controller();
+        ctrl.set_viewport(2.0, 10.0, 0.0);
+        // Pin 1001 screen pos = 100*2 + 10 = 210, 25*2 = 50
+        assert_eq!(ctrl.find_pin_at_screen(235.0, 50.0, 10.0), 0);
+
+        assert!(ctrl.cache().borrow_mut().set_pin_hit_radius(1001, Some(30.0)));
+        assert_eq!(ctrl.find_pin_at_screen(235.0, 50.0, 10.0), 1001);
+
+        // Zero is a real radius: only the exact position hits
+        ctrl.cache().borrow_mut().set_pin_hit_radius(1001, Some(0.0));
+        assert_eq!(ctrl.find_pin_at_screen(212.0, 50.0, 10.0), 0);
+        assert_eq!(ctrl.find_pin_at_screen(210.0, 50.0, 10.0), 1001);
+    }
+
     #[test]
     fn test_find_pin_at_screen_miss() {
         let ctrl = setup_controller();
@@ -708,6 +1633,93 @@ mod tests {
         assert!(result.is_empty());
     }
 
+    // ========================================================================
+    // prune_pins_for_node
+    // ========================================================================
+
+    #[test]
+    fn test_prune_pins_for_node_keeps_listed_pins() {
+        let ctrl = NodeEditorController::new();
+        ctrl.handle_node_rect(1, 0.0, 0.0, 100.0, 90.0);
+        ctrl.handle_node_rect(2, 200.0, 0.0, 100.0, 90.0);
+        for (pin_id, rel_y) in [(11, 20.0), (12, 45.0), (13, 70.0)] {
+            ctrl.handle_pin_position(pin_id, 1, 1, 0.0, rel_y);
+        }
+        ctrl.handle_pin_position(21, 2, 1, 0.0, 20.0);
+
+        assert_eq!(ctrl.prune_pins_for_node(1, &[12]), vec![11, 13]);
+
+        let cache = ctrl.cache();
+        let mut remaining: Vec<i32> = cache.borrow().pin_positions.keys().copied().collect();
+        remaining.sort_unstable();
+        assert_eq!(remaining, vec![12, 21]);
+        assert_eq!(ctrl.find_pin_at_screen(0.0, 20.0, 5.0), 0);
+        assert_eq!(ctrl.find_pin_at_screen(0.0, 45.0, 5.0), 12);
+    }
+
+    #[test]
+    fn test_prune_pins_for_node_updates_pin_index() {
+        let ctrl = NodeEditorController::new();
+        ctrl.cache().borrow_mut().build_pin_index(32.0);
+        ctrl.handle_node_rect(1, 0.0, 0.0, 100.0, 90.0);
+        ctrl.handle_pin_position(11, 1, 1, 0.0, 20.0);
+        ctrl.handle_pin_position(12, 1, 2, 100.0, 20.0);
+
+        assert_eq!(ctrl.prune_pins_for_node(1, &[]), vec![11, 12]);
+        assert_eq!(ctrl.find_pin_at_screen(0.0, 20.0, 5.0), 0);
+        assert!(ctrl.prune_pins_for_node(1, &[]).is_empty());
+    }
+
+    // ========================================================================
+    // screen_to_world() / world_to_screen()
+    // ========================================================================
+
+    #[test]
+    fn test_coordinate_conversion_round_trip() {
+        let ctrl = NodeEditorController::new();
+        ctrl.set_viewport(2.0, 30.0, -40.0);
+
+        assert_eq!(ctrl.screen_to_world(130.0, 160.0), (50.0, 100.0));
+        assert_eq!(ctrl.world_to_screen(50.0, 100.0), (130.0, 160.0));
+
+        let (wx, wy) = ctrl.screen_to_world(17.5, -3.25);
+        assert_eq!(ctrl.world_to_screen(wx, wy), (17.5, -3.25));
+    }
+
+    #[test]
+    fn test_screen_to_world_matches_hit_testing() {
+        let ctrl = setup_controller();
+        ctrl.set_viewport(2.0, 30.0, -40.0);
+        // Pin 1001 sits at world (100, 25)
+        let (sx, sy) = ctrl.world_to_screen(100.0, 25.0);
+        assert_eq!(ctrl.find_pin_at_screen(sx, sy, 5.0), 1001);
+    }
+
+    #[test]
+    fn test_world_position_for_new_node_centers_in_world_units() {
+        let ctrl = NodeEditorController::new();
+        ctrl.set_grid_spacing(0.0);
+        ctrl.set_viewport(2.0, 30.0, -40.0);
+
+        // Screen (330, 160) is world (150, 100)
+        let corner = ctrl.world_position_for_new_node(330.0, 160.0, 120.0, 60.0, false);
+        assert_eq!(corner, (150.0, 100.0));
+        // Centering subtracts half the world-space size, independent of zoom
+        let centered = ctrl.world_position_for_new_node(330.0, 160.0, 120.0, 60.0, true);
+        assert_eq!(centered, (90.0, 70.0));
+    }
+
+    #[test]
+    fn test_world_position_for_new_node_snaps_to_grid() {
+        let ctrl = NodeEditorController::new();
+        ctrl.set_grid_spacing(24.0);
+        ctrl.set_viewport(1.0, 0.0, 0.0);
+
+        assert_eq!(ctrl.world_position_for_new_node(50.0, 35.0, 0.0, 0.0, false), (48.0, 24.0));
+        // Centered on (110, 70): top-left (50, 40) snaps to (48, 48)
+        assert_eq!(ctrl.world_position_for_new_node(110.0, 70.0, 120.0, 60.0, true), (48.0, 48.0));
+    }
+
     // ========================================================================
     // safe_zoom guard
     // ========================================================================
@@ -728,5 +1740,604 @@ mod tests {
         ctrl.handle_node_rect(1, 100.0, 200.0, 50.0, 30.0);
         let _ = ctrl.find_link_at_screen(0.0, 0.0, 10.0, 50.0, 20);
         let _ = ctrl.find_pin_at_screen(0.0, 0.0, 10.0);
+        assert_eq!(ctrl.screen_to_world(10.0, 20.0), (10.0, 20.0));
+    }
+
+    // ========================================================================
+    // Undo/redo
+    // ========================================================================
+
+    #[test]
+    fn test_undo_move_applies_inverted_delta() {
+        let ctrl = NodeEditorController::new();
+        let applied = Rc::new(RefCell::new(Vec::new()));
+        ctrl.set_command_applier({
+            let applied = applied.clone();
+            move |cmd| {
+                if let EditCommand::MoveNodes { node_ids, delta_x, delta_y } = cmd {
+                    applied.borrow_mut().push((node_ids.clone(), *delta_x, *delta_y));
+                }
+            }
+        });
+
+        ctrl.push_command(EditCommand::MoveNodes {
+            node_ids: vec![1],
+            delta_x: 30.0,
+            delta_y: -12.0,
+        });
+        assert!(ctrl.can_undo());
+        assert!(ctrl.undo());
+
+        assert_eq!(*applied.borrow(), vec![(vec![1], -30.0, 12.0)]);
+        assert!(!ctrl.can_undo());
+        assert!(ctrl.can_redo());
+
+        assert!(ctrl.redo());
+        assert_eq!(applied.borrow()[1], (vec![1], 30.0, -12.0));
+    }
+
+    #[test]
+    fn test_undo_redo_empty_history() {
+        let ctrl = NodeEditorController::new();
+        assert!(!ctrl.undo());
+        assert!(!ctrl.redo());
+    }
+
+    #[test]
+    fn test_undo_link_create_unregisters_link() {
+        let ctrl = setup_controller();
+        ctrl.register_link(2, 2001, 1001);
+        ctrl.push_command(EditCommand::CreateLink {
+            link_id: 2,
+            start_pin: 2001,
+            end_pin: 1001,
+        });
+
+        ctrl.undo();
+        assert!(!ctrl.state.borrow().links.contains_key(&2));
+        ctrl.redo();
+        assert_eq!(ctrl.state.borrow().links.get(&2), Some(&(2001, 1001)));
+    }
+
+    #[test]
+    fn test_applier_may_reenter_controller() {
+        let ctrl = NodeEditorController::new();
+        ctrl.set_command_applier({
+            let ctrl = ctrl.clone();
+            move |_| {
+                let _ = ctrl.can_undo();
+                ctrl.set_bezier_offset(10.0);
+            }
+        });
+        ctrl.push_command(EditCommand::MoveNodes {
+            node_ids: vec![1],
+            delta_x: 1.0,
+            delta_y: 1.0,
+        });
+        assert!(ctrl.undo());
+    }
+
+    // ========================================================================
+    // compute_fit_viewport
+    // ========================================================================
+
+    #[test]
+    fn test_fit_viewport_empty_is_identity() {
+        let ctrl = NodeEditorController::new();
+        assert_eq!(ctrl.compute_fit_viewport(800.0, 600.0, 20.0), (1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_fit_viewport_centers_single_node() {
+        let ctrl = NodeEditorController::new();
+        ctrl.handle_node_rect(1, 100.0, 100.0, 200.0, 100.0);
+        let (zoom, pan_x, pan_y) = ctrl.compute_fit_viewport(800.0, 600.0, 20.0);
+
+        // Node center (200, 150) lands on the viewport center
+        assert!((200.0 * zoom + pan_x - 400.0).abs() < 1e-3);
+        assert!((150.0 * zoom + pan_y - 300.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_fit_viewport_respects_padding() {
+        let ctrl = setup_controller();
+        // Content spans (0, 0)..(300, 150)
+        let (zoom, pan_x, _) = ctrl.compute_fit_viewport(640.0, 1000.0, 20.0);
+        assert!((zoom - 2.0).abs() < 1e-5);
+        assert!((pan_x - 20.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_fit_viewport_clamps_zoom() {
+        let ctrl = NodeEditorController::new();
+        ctrl.handle_node_rect(1, 0.0, 0.0, 10.0, 10.0);
+        assert_eq!(ctrl.compute_fit_viewport(800.0, 600.0, 0.0).0, 3.0);
+
+        ctrl.handle_node_rect(2, 100_000.0, 100_000.0, 10.0, 10.0);
+        assert_eq!(ctrl.compute_fit_viewport(800.0, 600.0, 0.0).0, 0.1);
+    }
+
+    // ========================================================================
+    // zoom_at_point / zoom_at_cursor
+    // ========================================================================
+
+    #[test]
+    fn test_zoom_at_point_keeps_world_point_under_cursor() {
+        let (old_zoom, pan) = (1.5, (40.0, -25.0));
+        let cursor = (320.0, 210.0);
+        let world = ((cursor.0 - pan.0) / old_zoom, (cursor.1 - pan.1) / old_zoom);
+
+        for new_zoom in [0.25, 1.0, 2.0, 3.0] {
+            let (px, py) = zoom_at_point(old_zoom, new_zoom, cursor.0, cursor.1, pan.0, pan.1);
+            assert!((world.0 * new_zoom + px - cursor.0).abs() < 1e-3, "zoom {}", new_zoom);
+            assert!((world.1 * new_zoom + py - cursor.1).abs() < 1e-3, "zoom {}", new_zoom);
+        }
+    }
+
+    #[test]
+    fn test_zoom_at_point_origin_cursor_scales_pan() {
+        assert_eq!(zoom_at_point(1.0, 2.0, 0.0, 0.0, 10.0, 20.0), (20.0, 40.0));
+        assert_eq!(zoom_at_point(0.0, 2.0, 5.0, 5.0, 10.0, 20.0), (10.0, 20.0));
+    }
+
+    #[test]
+    fn test_zoom_at_cursor_updates_viewport() {
+        let ctrl = setup_controller();
+        // Pin 1001 at world (100, 25) is under the cursor at zoom 1
+        let pan = ctrl.zoom_at_cursor(2.0, 100.0, 25.0);
+
+        assert_eq!(ctrl.zoom(), 2.0);
+        assert_eq!(pan, (-100.0, -25.0));
+        assert_eq!(ctrl.world_to_screen(100.0, 25.0), (100.0, 25.0));
+        assert_eq!(ctrl.find_pin_at_screen(100.0, 25.0, 5.0), 1001);
+    }
+
+    // ========================================================================
+    // clamp_pan / set_viewport_clamped
+    // ========================================================================
+
+    #[test]
+    fn test_clamp_pan_pulls_extreme_pan_back() {
+        // Content (0, 0)..(300, 150) at zoom 2 in an 800x600 viewport
+        let bounds = (0.0, 0.0, 300.0, 150.0);
+
+        // Panned far left: right edge (600 + pan) must stay at x >= 50
+        assert_eq!(clamp_pan(-10_000.0, 0.0, 2.0, bounds, 800.0, 600.0, 50.0), (-550.0, 0.0));
+        // Panned far down-right: left/top edges stay within margin of the far side
+        assert_eq!(clamp_pan(5_000.0, 9_000.0, 2.0, bounds, 800.0, 600.0, 50.0), (750.0, 550.0));
+        // Panned far up: bottom edge (300 + pan) stays at y >= 50
+        assert_eq!(clamp_pan(0.0, -9_000.0, 2.0, bounds, 800.0, 600.0, 50.0), (0.0, -250.0));
+    }
+
+    #[test]
+    fn test_clamp_pan_keeps_reasonable_pan() {
+        let bounds = (-100.0, -100.0, 100.0, 100.0);
+        assert_eq!(clamp_pan(400.0, 300.0, 1.0, bounds, 800.0, 600.0, 50.0), (400.0, 300.0));
+        assert_eq!(clamp_pan(-9e6, 9e6, 0.0, bounds, 800.0, 600.0, 50.0), (-9e6, 9e6));
+    }
+
+    #[test]
+    fn test_set_viewport_clamped_stores_clamped_pan() {
+        let ctrl = setup_controller();
+        let pan = ctrl.set_viewport_clamped(2.0, -10_000.0, 0.0, 800.0, 600.0, 50.0);
+        assert_eq!(pan, (-550.0, 0.0));
+        assert_eq!(ctrl.world_to_screen(300.0, 0.0), (50.0, 0.0));
+
+        let empty = NodeEditorController::new();
+        let unclamped = empty.set_viewport_clamped(1.0, -10_000.0, 0.0, 800.0, 600.0, 50.0);
+        assert_eq!(unclamped, (-10_000.0, 0.0));
+    }
+
+    // ========================================================================
+    // compute_fit_selection
+    // ========================================================================
+
+    #[test]
+    fn test_fit_selection_none_when_nothing_selected() {
+        let ctrl = setup_controller();
+        let selection = SelectionManager::new();
+        assert_eq!(ctrl.compute_fit_selection(&selection, 800.0, 600.0, 20.0), None);
+    }
+
+    #[test]
+    fn test_fit_selection_far_apart_nodes_zoom_out() {
+        let ctrl = setup_controller();
+        ctrl.handle_node_rect(3, 3000.0, 2000.0, 100.0, 50.0);
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([1, 3]);
+
+        let (zoom, pan_x, pan_y) = ctrl.compute_fit_selection(&selection, 800.0, 600.0, 20.0).unwrap();
+        assert!(zoom < 1.0);
+        // Selection spans (0, 0)..(3100, 2050); its center maps to the viewport center
+        assert!((1550.0 * zoom + pan_x - 400.0).abs() < 1e-2);
+        assert!((1025.0 * zoom + pan_y - 300.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_fit_selection_ignores_unselected_nodes() {
+        let ctrl = setup_controller();
+        ctrl.handle_node_rect(3, 3000.0, 2000.0, 100.0, 50.0);
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([2]);
+
+        let fit = ctrl.compute_fit_selection(&selection, 800.0, 600.0, 20.0).unwrap();
+        ctrl.cache.borrow_mut().remove_node(1);
+        ctrl.cache.borrow_mut().remove_node(3);
+        assert_eq!(fit, ctrl.compute_fit_viewport(800.0, 600.0, 20.0));
+    }
+
+    // ========================================================================
+    // Clipboard
+    // ========================================================================
+
+    fn link(id: i32, start_pin: i32, end_pin: i32) -> SimpleLink {
+        SimpleLink::new(id, start_pin, end_pin, slint::Color::default())
+    }
+
+    /// Fresh IDs from a shared counter, starting at 101
+    fn counter_remap() -> impl FnMut(ClipboardId) -> i32 {
+        let mut next = 100;
+        move |_| {
+            next += 1;
+            next
+        }
+    }
+
+    #[test]
+    fn test_copy_paste_connected_nodes() {
+        let ctrl = setup_controller();
+        ctrl.handle_node_rect(3, 500.0, 0.0, 100.0, 50.0);
+        ctrl.handle_pin_position(3001, 3, 1, 0.0, 25.0);
+        let links = [link(1, 1001, 2001), link(2, 1001, 3001)];
+
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([1, 2]);
+        assert_eq!(ctrl.copy_selection(&selection, links.iter().cloned()), 2);
+        assert!(ctrl.has_clipboard());
+
+        let result = ctrl.paste(20.0, 30.0, counter_remap());
+        assert_eq!(result.graph.nodes.len(), 2);
+        assert_eq!(result.graph.links.len(), 1);
+
+        let new_1 = result.node_ids[&1];
+        let new_2 = result.node_ids[&2];
+        assert!(new_1 != 1 && new_2 != 2 && new_1 != new_2);
+
+        let node_2 = result.graph.nodes.iter().find(|n| n.id == new_2).unwrap();
+        assert_eq!((node_2.x, node_2.y), (220.0, 130.0));
+
+        // The internal link connects the pasted pins, not the originals
+        let pasted = &result.graph.links[0];
+        let pin_owner = |pin: i32| result.graph.pins.iter().find(|(id, _)| *id == pin).unwrap().1.node_id;
+        assert!(pasted.id != 1 && pasted.id != 2);
+        assert_eq!(pin_owner(pasted.start_pin_id), new_1);
+        assert_eq!(pin_owner(pasted.end_pin_id), new_2);
+    }
+
+    #[test]
+    fn test_paste_remaps_each_kind() {
+        let ctrl = setup_controller();
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([1, 2]);
+        ctrl.cache().borrow_mut().set_pin_side(1001, PinSide::Bottom);
+        ctrl.copy_selection(&selection, vec![link(1, 1001, 2001)]);
+
+        let mut seen = Vec::new();
+        let result = ctrl.paste(0.0, 0.0, |id| {
+            seen.push(id);
+            match id {
+                ClipboardId::Node(old) => old + 10,
+                ClipboardId::Pin(old) | ClipboardId::Link(old) => old + 5000,
+            }
+        });
+        assert_eq!(
+            seen,
+            vec![
+                ClipboardId::Node(1),
+                ClipboardId::Node(2),
+                ClipboardId::Pin(1001),
+                ClipboardId::Pin(2001),
+                ClipboardId::Link(1),
+            ]
+        );
+        // Pin sides follow the remapped pin IDs
+        assert_eq!(result.graph.pin_sides, vec![(6001, PinSide::Bottom)]);
+    }
+
+    #[test]
+    fn test_paste_twice_and_empty_clipboard() {
+        let ctrl = setup_controller();
+        assert!(!ctrl.has_clipboard());
+        assert!(ctrl.paste(0.0, 0.0, counter_remap()).graph.nodes.is_empty());
+
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([2]);
+        ctrl.copy_selection(&selection, Vec::<SimpleLink>::new());
+
+        let mut remap = counter_remap();
+        let first = ctrl.paste(10.0, 0.0, &mut remap);
+        let second = ctrl.paste(10.0, 0.0, &mut remap);
+        assert_ne!(first.node_ids[&2], second.node_ids[&2]);
+        assert_eq!(first.graph.pins.len(), 1);
+        assert!(first.graph.links.is_empty());
+    }
+
+    #[test]
+    fn test_apply_reports_populates_cache() {
+        let ctrl = NodeEditorController::new();
+        ctrl.apply_reports(
+            &[(1, 0.0, 0.0, 100.0, 50.0), (2, 200.0, 100.0, 100.0, 50.0)],
+            &[(1001, 1, 2, 100.0, 25.0), (2001, 2, 1, 0.0, 25.0)],
+        );
+        assert_eq!(ctrl.find_pin_at_screen(200.0, 125.0, 5.0), 2001);
+        assert!(!ctrl.compute_link_path(1001, 2001).is_empty());
+    }
+
+    // ========================================================================
+    // Drag preview
+    // ========================================================================
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct DragNode {
+        id: i32,
+        x: f32,
+        y: f32,
+    }
+
+    impl MovableNode for DragNode {
+        fn id(&self) -> i32 { self.id }
+        fn x(&self) -> f32 { self.x }
+        fn y(&self) -> f32 { self.y }
+        fn set_x(&mut self, x: f32) { self.x = x; }
+        fn set_y(&mut self, y: f32) { self.y = y; }
+    }
+
+    fn drag_model() -> VecModel<DragNode> {
+        VecModel::from(vec![
+            DragNode { id: 1, x: 0.0, y: 0.0 },
+            DragNode { id: 2, x: 100.0, y: 50.0 },
+            DragNode { id: 3, x: 300.0, y: 300.0 },
+        ])
+    }
+
+    fn positions(model: &VecModel<DragNode>) -> Vec<(f32, f32)> {
+        (0..model.row_count()).filter_map(|i| model.row_data(i)).map(|n| (n.x, n.y)).collect()
+    }
+
+    #[test]
+    fn test_drag_preview_offsets_selected_nodes_only() {
+        let ctrl = NodeEditorController::new();
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([1, 2]);
+
+        ctrl.begin_drag(&selection);
+        ctrl.update_drag(10.0, 5.0);
+        ctrl.update_drag(2.0, -1.0);
+
+        assert!(ctrl.is_dragging());
+        assert_eq!(ctrl.preview_offset(1), (12.0, 4.0));
+        assert_eq!(ctrl.preview_offset(2), (12.0, 4.0));
+        assert_eq!(ctrl.preview_offset(3), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_snapped_preview_offset_uses_grid_spacing() {
+        let ctrl = NodeEditorController::new();
+        ctrl.set_grid_spacing(24.0);
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([1, 2]);
+
+        ctrl.begin_drag(&selection);
+        ctrl.update_drag(7.0, 0.0);
+        // Node 1 at (100, 48): 107 snaps to 96; node 2 at (110, 48): 117 snaps to 120
+        assert_eq!(ctrl.snapped_preview_offset(1, (100.0, 48.0)), (-4.0, 0.0));
+        assert_eq!(ctrl.snapped_preview_offset(2, (110.0, 48.0)), (10.0, 0.0));
+        assert_eq!(ctrl.snapped_preview_offset(3, (101.0, 50.0)), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_cancel_drag_leaves_model_untouched() {
+        let ctrl = NodeEditorController::new();
+        let model = drag_model();
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([1]);
+
+        ctrl.begin_drag(&selection);
+        ctrl.update_drag(40.0, 40.0);
+        ctrl.cancel_drag();
+
+        assert!(!ctrl.is_dragging());
+        assert_eq!(ctrl.preview_offset(1), (0.0, 0.0));
+        assert_eq!(ctrl.commit_drag(&model), None);
+        assert_eq!(positions(&model), positions(&drag_model()));
+    }
+
+    #[test]
+    fn test_commit_drag_applies_accumulated_delta() {
+        let ctrl = NodeEditorController::new();
+        let model = drag_model();
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([1, 2]);
+
+        ctrl.begin_drag(&selection);
+        ctrl.update_drag(10.0, 0.0);
+        ctrl.update_drag(5.0, 20.0);
+        // Selection changes mid-drag don't affect which nodes move
+        selection.clear();
+
+        assert_eq!(ctrl.commit_drag(&model), Some((15.0, 20.0)));
+        assert_eq!(positions(&model), vec![(15.0, 20.0), (115.0, 70.0), (300.0, 300.0)]);
+        assert!(!ctrl.is_dragging());
+    }
+
+    #[test]
+    fn test_commit_drag_snapped_matches_snapped_preview() {
+        let ctrl = NodeEditorController::new();
+        ctrl.set_grid_spacing(24.0);
+        let model = VecModel::from(vec![
+            DragNode { id: 1, x: 100.0, y: 48.0 },
+            DragNode { id: 2, x: 110.0, y: 53.0 },
+            DragNode { id: 3, x: 301.0, y: 300.0 },
+        ]);
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([1, 2]);
+
+        ctrl.begin_drag(&selection);
+        ctrl.update_drag(7.0, 30.0);
+        let previewed: Vec<(f32, f32)> = positions(&model)
+            .into_iter()
+            .zip([1, 2, 3])
+            .map(|((x, y), id)| {
+                let (dx, dy) = ctrl.snapped_preview_offset(id, (x, y));
+                (x + dx, y + dy)
+            })
+            .collect();
+
+        let command = ctrl.commit_drag_snapped(&model).unwrap();
+        assert_eq!(positions(&model), previewed);
+        assert_eq!(positions(&model), vec![(96.0, 72.0), (120.0, 72.0), (301.0, 300.0)]);
+        let EditCommand::Batch(moves) = command else { panic!("expected a batch") };
+        assert_eq!(moves.len(), 2);
+        assert!(!ctrl.is_dragging());
+        assert!(ctrl.commit_drag_snapped(&model).is_none());
+    }
+
+    // ========================================================================
+    // Lasso selection
+    // ========================================================================
+
+    #[test]
+    fn test_nodes_in_polygon_screen_converts_to_world() {
+        let ctrl = setup_controller();
+        // Zoom 2, pan (100, 50): node 1's center (50, 25) is at screen (200, 100)
+        ctrl.set_viewport(2.0, 100.0, 50.0);
+        let triangle = [(150.0, 60.0), (260.0, 60.0), (200.0, 160.0)];
+        assert_eq!(ctrl.nodes_in_polygon_screen(&triangle), vec![1]);
+
+        // The same triangle read as world coordinates would miss node 1
+        ctrl.set_viewport(1.0, 0.0, 0.0);
+        assert!(ctrl.nodes_in_polygon_screen(&triangle).is_empty());
+    }
+
+    #[test]
+    fn test_links_in_polygon_screen_selects_crossing_link() {
+        let ctrl = setup_controller();
+        // Link 1 runs from world (100, 25) to (200, 125); its midpoint (150, 75)
+        // is at screen (300, 150) with zoom 2 and no pan
+        ctrl.set_viewport(2.0, 0.0, 0.0);
+        let lasso = [(280.0, 130.0), (320.0, 130.0), (320.0, 170.0), (280.0, 170.0)];
+        assert_eq!(ctrl.links_in_polygon_screen(&lasso, 20), vec![1]);
+
+        let elsewhere = [(0.0, 300.0), (40.0, 300.0), (40.0, 340.0)];
+        assert!(ctrl.links_in_polygon_screen(&elsewhere, 20).is_empty());
+    }
+
+    // ========================================================================
+    // Viewport culling
+    // ========================================================================
+
+    #[test]
+    fn test_visible_nodes_screen_follows_pan_and_zoom() {
+        let ctrl = setup_controller();
+        assert_eq!(ctrl.visible_nodes_screen(400.0, 300.0), vec![1, 2]);
+
+        // Pan so the view shows world x in [150, 350): only node 2
+        ctrl.set_viewport(1.0, -150.0, 0.0);
+        assert_eq!(ctrl.visible_nodes_screen(200.0, 300.0), vec![2]);
+
+        // Zoom 2 at no pan shows world (0, 0)-(100, 75): only node 1
+        ctrl.set_viewport(2.0, 0.0, 0.0);
+        assert_eq!(ctrl.visible_nodes_screen(200.0, 150.0), vec![1]);
+    }
+
+    #[test]
+    fn test_visible_links_screen_skips_off_screen_links() {
+        let ctrl = setup_controller();
+        assert_eq!(ctrl.visible_links_screen(400.0, 300.0), vec![1]);
+
+        ctrl.set_viewport(1.0, 1000.0, 1000.0);
+        assert!(ctrl.visible_links_screen(400.0, 300.0).is_empty());
+    }
+
+    // ========================================================================
+    // Deferred validation
+    // ========================================================================
+
+    type PendingCallback = Box<dyn FnOnce(ValidationResult)>;
+
+    /// Deferred validator that holds callbacks until the test resolves them
+    #[derive(Default)]
+    struct BackendValidator {
+        queue: RefCell<Vec<PendingCallback>>,
+    }
+
+    impl AsyncLinkValidator<SimpleNodeGeometry, SimpleLink> for BackendValidator {
+        fn validate_deferred<F>(
+            &self,
+            _start_pin: i32,
+            _end_pin: i32,
+            _cache: &GeometryCache,
+            _links: &[SimpleLink],
+            on_result: F,
+        ) where
+            F: FnOnce(ValidationResult) + 'static,
+        {
+            self.queue.borrow_mut().push(Box::new(on_result));
+        }
+    }
+
+    fn controller_with_two_pins() -> NodeEditorController {
+        let ctrl = NodeEditorController::new();
+        ctrl.handle_node_rect(1, 0.0, 0.0, 100.0, 50.0);
+        ctrl.handle_node_rect(2, 200.0, 0.0, 100.0, 50.0);
+        ctrl.handle_pin_position(11, 1, 2, 100.0, 25.0);
+        ctrl.handle_pin_position(21, 2, 1, 0.0, 25.0);
+        ctrl
+    }
+
+    #[test]
+    fn test_validate_link_deferred_immediate_result() {
+        use crate::graph::{BasicLinkValidator, ImmediateValidator};
+
+        let ctrl = controller_with_two_pins();
+        let validator = ImmediateValidator(BasicLinkValidator::new(2));
+        let verdict = Rc::new(RefCell::new(None));
+
+        let sink = verdict.clone();
+        let links: [SimpleLink; 0] = [];
+        ctrl.validate_link_deferred(&validator, 11, 21, &links, move |result| {
+            *sink.borrow_mut() = Some(result)
+        });
+
+        assert_eq!(*verdict.borrow(), Some(ValidationResult::Valid));
+        assert!(!ctrl.is_link_pending(11, 21));
+        assert_eq!(ctrl.pending_validation_count(), 0);
+    }
+
+    #[test]
+    fn test_validate_link_deferred_pending_until_resolved() {
+        let ctrl = controller_with_two_pins();
+        let validator = BackendValidator::default();
+        let verdict = Rc::new(RefCell::new(None));
+
+        let sink = verdict.clone();
+        ctrl.validate_link_deferred(&validator, 11, 21, &[], move |result| {
+            *sink.borrow_mut() = Some(result)
+        });
+        ctrl.validate_link_deferred(&validator, 11, 21, &[], |_| {});
+
+        assert!(ctrl.is_link_pending(11, 21));
+        assert!(!ctrl.is_link_pending(21, 11));
+        assert_eq!(ctrl.pending_validation_count(), 2);
+        assert!(verdict.borrow().is_none());
+
+        let first = validator.queue.borrow_mut().remove(0);
+        first(ValidationResult::Valid);
+        assert_eq!(*verdict.borrow(), Some(ValidationResult::Valid));
+        assert!(ctrl.is_link_pending(11, 21));
+
+        let second = validator.queue.borrow_mut().remove(0);
+        second(ValidationResult::Valid);
+        assert!(!ctrl.is_link_pending(11, 21));
     }
 }
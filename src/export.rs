@@ -0,0 +1,175 @@
+//! Export helpers for debugging and documentation.
+//!
+//! [`to_dot`] renders the graph topology as a GraphViz DOT digraph, so a
+//! graph can be piped into `dot -Tsvg` to compare against the editor's own
+//! layout.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use slint_node_editor::export::to_dot_with_labels;
+//!
+//! let dot = to_dot_with_labels(links.iter(), &cache.borrow(), |id| format!("Node {}", id));
+//! std::fs::write("graph.dot", dot)?;
+//! ```
+
+use crate::graph::LinkModel;
+use crate::state::GeometryCache;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Render the graph as a DOT digraph with node IDs as labels.
+///
+/// See [`to_dot_with_labels`] for details.
+pub fn to_dot<I, L, N>(links: I, cache: &GeometryCache<N>) -> String
+where
+    I: IntoIterator<Item = L>,
+    L: LinkModel,
+{
+    render_dot(links, cache, None)
+}
+
+/// Render the graph as a DOT digraph with custom node labels.
+///
+/// Every node in `cache.node_rects` (and every node referenced by a link) is
+/// declared once as `n<id>`, in ascending ID order. Each link becomes one
+/// `n<start> -> n<end>` edge after resolving its pins to their owning nodes;
+/// links with unknown pins are skipped.
+///
+/// # Arguments
+/// * `links` - Links to export
+/// * `cache` - Geometry cache used to resolve pins to nodes
+/// * `label` - Produces the display label for a node ID
+pub fn to_dot_with_labels<I, L, N, F>(links: I, cache: &GeometryCache<N>, label: F) -> String
+where
+    I: IntoIterator<Item = L>,
+    L: LinkModel,
+    F: Fn(i32) -> String,
+{
+    render_dot(links, cache, Some(&label))
+}
+
+fn render_dot<I, L, N>(
+    links: I,
+    cache: &GeometryCache<N>,
+    label: Option<&dyn Fn(i32) -> String>,
+) -> String
+where
+    I: IntoIterator<Item = L>,
+    L: LinkModel,
+{
+    let edges: Vec<(i32, i32)> = links
+        .into_iter()
+        .filter_map(|link| {
+            let start = cache.pin_positions.get(&link.start_pin_id())?.node_id;
+            let end = cache.pin_positions.get(&link.end_pin_id())?.node_id;
+            Some((start, end))
+        })
+        .collect();
+
+    let mut nodes: BTreeSet<i32> = cache.node_rects.keys().copied().collect();
+    nodes.extend(edges.iter().flat_map(|&(start, end)| [start, end]));
+
+    let mut out = String::from("digraph {\n");
+    for id in nodes {
+        match label {
+            Some(label) => {
+                let _ = writeln!(out, "    n{} [label=\"{}\"];", id, escape_label(&label(id)));
+            }
+            None => {
+                let _ = writeln!(out, "    n{};", id);
+            }
+        }
+    }
+    for (start, end) in edges {
+        let _ = writeln!(out, "    n{} -> n{};", start, end);
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Escape a string for use inside a double-quoted DOT ID
+fn escape_label(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::SimpleLink;
+    use crate::hit_test::SimpleNodeGeometry;
+    use slint::Color;
+
+    /// Node `n` has input pin `n * 10` and output pin `n * 10 + 1`
+    fn cache_with_nodes(ids: &[i32]) -> GeometryCache<SimpleNodeGeometry> {
+        let mut cache = GeometryCache::new();
+        for &id in ids {
+            cache.update_node_rect(id, 0.0, 0.0, 100.0, 50.0);
+            cache.handle_pin_report(id * 10, id, 1, 0.0, 25.0);
+            cache.handle_pin_report(id * 10 + 1, id, 2, 100.0, 25.0);
+        }
+        cache
+    }
+
+    fn link(id: i32, from: i32, to: i32) -> SimpleLink {
+        SimpleLink::new(id, from * 10 + 1, to * 10, Color::from_rgb_u8(255, 255, 255))
+    }
+
+    fn diamond_links() -> Vec<SimpleLink> {
+        vec![link(1, 1, 2), link(2, 1, 3), link(3, 2, 4), link(4, 3, 4)]
+    }
+
+    #[test]
+    fn test_diamond_has_four_edges_and_unique_nodes() {
+        let cache = cache_with_nodes(&[1, 2, 3, 4]);
+        let dot = to_dot(diamond_links(), &cache);
+
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert_eq!(dot.matches(" -> ").count(), 4);
+        for id in 1..=4 {
+            assert_eq!(dot.matches(&format!("    n{};", id)).count(), 1);
+        }
+        assert!(dot.contains("n1 -> n2;"));
+        assert!(dot.contains("n3 -> n4;"));
+    }
+
+    #[test]
+    fn test_isolated_nodes_are_declared() {
+        let cache = cache_with_nodes(&[1, 2, 3]);
+        let dot = to_dot(vec![link(1, 1, 2)], &cache);
+        assert!(dot.contains("    n3;"));
+    }
+
+    #[test]
+    fn test_unresolved_links_are_skipped() {
+        let cache = cache_with_nodes(&[1, 2]);
+        let links = vec![link(1, 1, 2), SimpleLink::new(2, 999, 20, Color::default())];
+        let dot = to_dot(links, &cache);
+        assert_eq!(dot.matches(" -> ").count(), 1);
+    }
+
+    #[test]
+    fn test_labels_are_escaped() {
+        let cache = cache_with_nodes(&[1]);
+        let links: Vec<SimpleLink> = vec![];
+        let dot = to_dot_with_labels(links, &cache, |id| format!("say \"{}\"", id));
+        assert!(dot.contains("    n1 [label=\"say \\\"1\\\"\"];"));
+    }
+
+    #[test]
+    fn test_empty_graph() {
+        let cache: GeometryCache<SimpleNodeGeometry> = GeometryCache::new();
+        let links: Vec<SimpleLink> = vec![];
+        assert_eq!(to_dot(links, &cache), "digraph {\n}\n");
+    }
+}
@@ -2,6 +2,9 @@ use crate::hit_test::{NodeGeometry, SimpleNodeGeometry};
 use crate::selection::SelectionManager;
 use crate::state::GeometryCache;
 use slint::{Color, Model, VecModel};
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::fmt;
 
 /// Trait for link data to support graph topology and rendering operations.
@@ -51,6 +54,16 @@ pub trait LinkModel {
     fn status(&self) -> i32 {
         -1
     }
+    /// Dash pattern as `(dash, gap)` lengths in pixels (default: `None`, solid).
+    /// Animate it with [`dash_offset_for_time`](crate::links::dash_offset_for_time).
+    fn dash_pattern(&self) -> Option<(f32, f32)> {
+        None
+    }
+    /// World-space points the link is routed through (default: none).
+    /// Render with [`generate_waypoint_path`](crate::path::generate_waypoint_path).
+    fn waypoints(&self) -> &[(f32, f32)] {
+        &[]
+    }
 }
 
 /// Simple link data structure implementing [`LinkModel`].
@@ -58,10 +71,12 @@ pub trait LinkModel {
 /// Use this for basic link storage, or implement [`LinkModel`] on your own
 /// type if you need additional fields.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimpleLink {
     pub id: i32,
     pub start_pin_id: i32,
     pub end_pin_id: i32,
+    #[cfg_attr(feature = "serde", serde(with = "color_serde"))]
     pub color: Color,
     pub line_width: f32,
     pub status: i32,
@@ -99,6 +114,21 @@ impl SimpleLink {
     }
 }
 
+/// Serialize [`Color`] as its encoded ARGB `u32`
+#[cfg(feature = "serde")]
+mod color_serde {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use slint::Color;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(color.as_argb_encoded())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        u32::deserialize(deserializer).map(Color::from_argb_encoded)
+    }
+}
+
 impl LinkModel for SimpleLink {
     fn id(&self) -> i32 {
         self.id
@@ -130,6 +160,40 @@ pub trait MovableNode: Clone + 'static {
     fn set_y(&mut self, y: f32);
 }
 
+/// Overlap (in world units) that [`GraphLogic::resolve_overlaps`] treats as separated.
+const OVERLAP_TOLERANCE: f32 = 1e-3;
+
+/// Edge or center line used by [`GraphLogic::align_selected`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignMode {
+    /// Match the leftmost left edge
+    Left,
+    /// Match the rightmost right edge
+    Right,
+    /// Match the topmost top edge
+    Top,
+    /// Match the bottommost bottom edge
+    Bottom,
+    /// Center horizontally on the selection's bounding box
+    CenterX,
+    /// Center vertically on the selection's bounding box
+    CenterY,
+}
+
+/// Endpoint of a link, used by [`GraphLogic::reconnect_link`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkEnd {
+    Start,
+    End,
+}
+
+/// Axis used by [`GraphLogic::distribute_selected`] and [`Guide`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
 /// Helper functions for graph operations
 pub struct GraphLogic;
 
@@ -152,14 +216,8 @@ impl GraphLogic {
     {
         links
             .filter(|link| {
-                let start_node = cache
-                    .pin_positions
-                    .get(&link.start_pin_id())
-                    .map(|p| p.node_id);
-                let end_node = cache
-                    .pin_positions
-                    .get(&link.end_pin_id())
-                    .map(|p| p.node_id);
+                let start_node = Self::pin_node_id(link.start_pin_id(), cache);
+                let end_node = Self::pin_node_id(link.end_pin_id(), cache);
 
                 start_node == Some(node_id) || end_node == Some(node_id)
             })
@@ -167,6 +225,302 @@ impl GraphLogic {
             .collect()
     }
 
+    /// Find all links that reference any of the deleted nodes
+    ///
+    /// Call this after removing nodes to get the links that must go with
+    /// them. Pins are resolved to nodes as in
+    /// [`find_links_connected_to_node`](Self::find_links_connected_to_node),
+    /// so the cache must still hold the deleted nodes' pins.
+    pub fn links_orphaned_by_nodes<I, L, N>(
+        deleted_node_ids: &[i32],
+        links: I,
+        cache: &GeometryCache<N>,
+    ) -> Vec<i32>
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+    {
+        let deleted: HashSet<i32> = deleted_node_ids.iter().copied().collect();
+        let is_deleted =
+            |pin_id| Self::pin_node_id(pin_id, cache).is_some_and(|node| deleted.contains(&node));
+
+        links
+            .into_iter()
+            .filter(|link| is_deleted(link.start_pin_id()) || is_deleted(link.end_pin_id()))
+            .map(|link| link.id())
+            .collect()
+    }
+
+    /// Suggest where links crossing a collapsed group's boundary should attach
+    ///
+    /// A link crosses the boundary when exactly one of its pins belongs to a
+    /// node in `member_ids`. That member pin is replaced by the lowest-ID pin
+    /// of the group node `collapsed_group_id` with the same pin type, so an
+    /// external link into a member input attaches to the group's input.
+    /// Links inside the group or outside it are not returned, nor are links
+    /// for which the group has no pin of a matching type. The caller applies
+    /// the suggestions (and restores the original pins on expand).
+    ///
+    /// # Returns
+    /// `(link_id, replacement_pin)` pairs in the order of `links`
+    pub fn reroute_links_to_group<I, L, N>(
+        collapsed_group_id: i32,
+        member_ids: &[i32],
+        links: I,
+        cache: &GeometryCache<N>,
+    ) -> Vec<(i32, i32)>
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+    {
+        let members: HashSet<i32> = member_ids.iter().copied().collect();
+        let is_member =
+            |pin_id| Self::pin_node_id(pin_id, cache).is_some_and(|node| members.contains(&node));
+        let group_pin = |member_pin: i32| {
+            let pin_type = cache.pin_positions.get(&member_pin)?.pin_type;
+            cache
+                .node_pins(collapsed_group_id)
+                .into_iter()
+                .find(|pin| cache.pin_positions.get(pin).is_some_and(|p| p.pin_type == pin_type))
+        };
+
+        links
+            .into_iter()
+            .filter_map(|link| {
+                let (start, end) = (link.start_pin_id(), link.end_pin_id());
+                let member_pin = match (is_member(start), is_member(end)) {
+                    (true, false) => start,
+                    (false, true) => end,
+                    _ => return None,
+                };
+                Some((link.id(), group_pin(member_pin)?))
+            })
+            .collect()
+    }
+
+    /// Resolve the node that owns a pin
+    fn pin_node_id<N>(pin_id: i32, cache: &GeometryCache<N>) -> Option<i32> {
+        cache.pin_positions.get(&pin_id).map(|p| p.node_id)
+    }
+
+    /// Resolve links to deduplicated `(start_node, end_node)` edges.
+    ///
+    /// Links whose pins are not in the cache are skipped.
+    fn node_edges<I, L, N>(links: I, cache: &GeometryCache<N>) -> HashSet<(i32, i32)>
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+    {
+        links
+            .into_iter()
+            .filter_map(|link| {
+                Some((
+                    Self::pin_node_id(link.start_pin_id(), cache)?,
+                    Self::pin_node_id(link.end_pin_id(), cache)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Find every node reachable by following links downstream from `node_id`
+    ///
+    /// Links are resolved from pins to nodes the same way as
+    /// [`find_links_connected_to_node`](Self::find_links_connected_to_node).
+    /// The starting node itself is not included.
+    pub fn descendants<I, L, N>(node_id: i32, links: I, cache: &GeometryCache<N>) -> HashSet<i32>
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+        N: NodeGeometry + Copy,
+    {
+        let mut adjacency: HashMap<i32, Vec<i32>> = HashMap::new();
+        for (from, to) in Self::node_edges(links, cache) {
+            adjacency.entry(from).or_default().push(to);
+        }
+        let mut reached = Self::reachable(node_id, &adjacency);
+        reached.remove(&node_id);
+        reached
+    }
+
+    /// Find every node that reaches `node_id` by following links downstream
+    ///
+    /// The starting node itself is not included.
+    pub fn ancestors<I, L, N>(node_id: i32, links: I, cache: &GeometryCache<N>) -> HashSet<i32>
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+        N: NodeGeometry + Copy,
+    {
+        let mut adjacency: HashMap<i32, Vec<i32>> = HashMap::new();
+        for (from, to) in Self::node_edges(links, cache) {
+            adjacency.entry(to).or_default().push(from);
+        }
+        let mut reached = Self::reachable(node_id, &adjacency);
+        reached.remove(&node_id);
+        reached
+    }
+
+    /// Find all nodes connected to `node_id`, ignoring link direction
+    ///
+    /// The result always contains `node_id` itself, so an isolated node
+    /// forms a component of size 1.
+    pub fn connected_component<I, L, N>(
+        node_id: i32,
+        links: I,
+        cache: &GeometryCache<N>,
+    ) -> HashSet<i32>
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+        N: NodeGeometry + Copy,
+    {
+        let mut adjacency: HashMap<i32, Vec<i32>> = HashMap::new();
+        for (from, to) in Self::node_edges(links, cache) {
+            adjacency.entry(from).or_default().push(to);
+            adjacency.entry(to).or_default().push(from);
+        }
+        let mut reached = Self::reachable(node_id, &adjacency);
+        reached.insert(node_id);
+        reached
+    }
+
+    /// Breadth-first search; returns every node reached from `start`
+    /// (including `start` only if it lies on a cycle)
+    fn reachable(start: i32, adjacency: &HashMap<i32, Vec<i32>>) -> HashSet<i32> {
+        let mut reached = HashSet::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for &next in adjacency.get(&node).into_iter().flatten() {
+                if reached.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Order nodes so every link goes from an earlier node to a later one
+    ///
+    /// Links are resolved from pins to their owning nodes; several links
+    /// between the same pair of nodes count as one edge. All nodes in
+    /// `cache.node_rects` are included, so unconnected nodes appear too.
+    /// Among nodes that are ready at the same time, lower IDs come first.
+    ///
+    /// # Returns
+    /// `Ok(order)` with every node ID, or `Err(nodes)` with the sorted IDs of
+    /// the nodes that lie on a cycle (a link from a node to itself counts).
+    pub fn topological_order<I, L, N>(
+        links: I,
+        cache: &GeometryCache<N>,
+    ) -> Result<Vec<i32>, Vec<i32>>
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+        N: NodeGeometry + Copy,
+    {
+        let edges = Self::node_edges(links, cache);
+
+        let mut in_degree: HashMap<i32, usize> =
+            cache.node_rects.keys().map(|&id| (id, 0)).collect();
+        let mut successors: HashMap<i32, Vec<i32>> = HashMap::new();
+        for &(from, to) in &edges {
+            in_degree.entry(from).or_insert(0);
+            *in_degree.entry(to).or_insert(0) += 1;
+            successors.entry(from).or_default().push(to);
+        }
+
+        let mut ready: BinaryHeap<Reverse<i32>> = in_degree
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&id, _)| Reverse(id))
+            .collect();
+
+        let mut order = Vec::with_capacity(in_degree.len());
+        while let Some(Reverse(id)) = ready.pop() {
+            order.push(id);
+            for &next in successors.get(&id).into_iter().flatten() {
+                let degree = in_degree.get_mut(&next).expect("edge endpoint has a degree");
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+
+        if order.len() == in_degree.len() {
+            return Ok(order);
+        }
+
+        let mut remaining: Vec<i32> = in_degree
+            .into_iter()
+            .filter(|&(_, degree)| degree > 0)
+            .map(|(id, _)| id)
+            .collect();
+        remaining.sort_unstable();
+        Err(Self::nodes_on_cycles(&remaining, &edges))
+    }
+
+    /// Nodes of `nodes` that belong to a cycle (strongly connected component
+    /// with more than one node, or a node linked to itself).
+    fn nodes_on_cycles(nodes: &[i32], edges: &HashSet<(i32, i32)>) -> Vec<i32> {
+        let members: HashSet<i32> = nodes.iter().copied().collect();
+        let mut forward: HashMap<i32, Vec<i32>> = HashMap::new();
+        let mut backward: HashMap<i32, Vec<i32>> = HashMap::new();
+        for &(from, to) in edges {
+            if members.contains(&from) && members.contains(&to) {
+                forward.entry(from).or_default().push(to);
+                backward.entry(to).or_default().push(from);
+            }
+        }
+
+        // Pass 1: post-order over forward edges
+        let mut visited = HashSet::new();
+        let mut finished = Vec::with_capacity(nodes.len());
+        for &start in nodes {
+            if !visited.insert(start) {
+                continue;
+            }
+            let mut stack = vec![(start, 0usize)];
+            while let Some((node, next)) = stack.last_mut() {
+                let succ = forward.get(node).map(Vec::as_slice).unwrap_or(&[]);
+                if let Some(&child) = succ.get(*next) {
+                    *next += 1;
+                    if visited.insert(child) {
+                        stack.push((child, 0));
+                    }
+                } else {
+                    finished.push(*node);
+                    stack.pop();
+                }
+            }
+        }
+
+        // Pass 2: components over backward edges in reverse finish order
+        let mut assigned = HashSet::new();
+        let mut on_cycle = Vec::new();
+        for &root in finished.iter().rev() {
+            if !assigned.insert(root) {
+                continue;
+            }
+            let mut component = vec![root];
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                for &prev in backward.get(&node).into_iter().flatten() {
+                    if assigned.insert(prev) {
+                        component.push(prev);
+                        stack.push(prev);
+                    }
+                }
+            }
+            if component.len() > 1 || edges.contains(&(root, root)) {
+                on_cycle.extend(component);
+            }
+        }
+
+        on_cycle.sort_unstable();
+        on_cycle
+    }
+
     /// Normalize a link so (start, end) is always (Output, Input)
     ///
     /// Returns (output_pin_id, input_pin_id)
@@ -210,95 +564,664 @@ impl GraphLogic {
         }
     }
 
-    /// Check if a link with the given direction already exists
-    ///
-    /// Prevents duplicate connections between the same pins.
+    /// Move every member of a node group by `(dx, dy)`
     ///
-    /// # Arguments
-    /// * `start_pin` - Start pin ID
-    /// * `end_pin` - End pin ID
-    /// * `links` - Iterator over existing links
-    pub fn duplicate_link_exists<I, L>(start_pin: i32, end_pin: i32, links: I) -> bool
+    /// A group is just the list of its member node IDs; the application owns
+    /// it and draws the container in Slint. IDs missing from `model` are
+    /// ignored.
+    pub fn move_group<T>(model: &VecModel<T>, member_ids: &[i32], dx: f32, dy: f32)
     where
-        I: IntoIterator<Item = L>,
-        L: LinkModel,
+        T: MovableNode,
     {
-        links
-            .into_iter()
-            .any(|link| link.start_pin_id() == start_pin && link.end_pin_id() == end_pin)
+        let members: HashSet<i32> = member_ids.iter().copied().collect();
+        for i in 0..model.row_count() {
+            if let Some(mut node) = model.row_data(i) {
+                if members.contains(&MovableNode::id(&node)) {
+                    node.set_x(node.x() + dx);
+                    node.set_y(node.y() + dy);
+                    model.set_row_data(i, node);
+                }
+            }
+        }
     }
 
-    /// Find a node by ID in a VecModel using a predicate function
+    /// Bounding box `(x, y, width, height)` enclosing every member of a node group
     ///
-    /// Useful for searching multiple node models when IDs need to be matched
-    /// against specific fields.
+    /// Positions are read from `model` and `node_sizes` returns a member's
+    /// `(width, height)`, as in [`resolve_overlaps`](Self::resolve_overlaps).
+    /// Use it to size the group's container. IDs missing from `model` are
+    /// ignored; with no members found the result is all zeros.
+    pub fn group_bounds<T>(
+        model: &VecModel<T>,
+        member_ids: &[i32],
+        node_sizes: impl Fn(i32) -> (f32, f32),
+    ) -> (f32, f32, f32, f32)
+    where
+        T: MovableNode,
+    {
+        let members: HashSet<i32> = member_ids.iter().copied().collect();
+        let mut bounds: Option<(f32, f32, f32, f32)> = None;
+        for node in (0..model.row_count()).filter_map(|row| model.row_data(row)) {
+            let id = MovableNode::id(&node);
+            if !members.contains(&id) {
+                continue;
+            }
+            let (w, h) = node_sizes(id);
+            let (x, y) = (node.x(), node.y());
+            bounds = Some(match bounds {
+                None => (x, y, x + w, y + h),
+                Some((min_x, min_y, max_x, max_y)) => {
+                    (min_x.min(x), min_y.min(y), max_x.max(x + w), max_y.max(y + h))
+                }
+            });
+        }
+        bounds.map_or((0.0, 0.0, 0.0, 0.0), |(min_x, min_y, max_x, max_y)| {
+            (min_x, min_y, max_x - min_x, max_y - min_y)
+        })
+    }
+
+    /// Adjust a drag delta so the node lands on a grid intersection
     ///
-    /// # Arguments
-    /// * `model` - The VecModel containing nodes
-    /// * `id` - The ID to search for
-    /// * `predicate` - Function that extracts ID from a node
+    /// Snaps the resulting position `node_origin + raw_delta` to the nearest
+    /// multiple of `spacing` and returns the delta that reaches it, so nodes
+    /// of a multi-selection that started off-grid each land on the grid. A
+    /// non-positive `spacing` returns `raw_delta` unchanged.
+    pub fn snap_drag_delta(node_origin: (f32, f32), raw_delta: (f32, f32), spacing: f32) -> (f32, f32) {
+        if spacing <= 0.0 {
+            return raw_delta;
+        }
+        let snap = |value: f32| (value / spacing).round() * spacing;
+        (
+            snap(node_origin.0 + raw_delta.0) - node_origin.0,
+            snap(node_origin.1 + raw_delta.1) - node_origin.1,
+        )
+    }
+
+    /// Round every node's position to the nearest multiple of `spacing`
     ///
-    /// # Example
-    /// ```ignore
-    /// let (index, node) = GraphLogic::find_node_by_id(
-    ///     &nodes_model,
-    ///     42,
-    ///     |n| n.id,
-    /// ).unwrap();
-    /// ```
-    pub fn find_node_by_id<T, F>(model: &VecModel<T>, id: i32, predicate: F) -> Option<(usize, T)>
+    /// Bulk counterpart to the editor's `snap-to-grid` function, e.g. for a
+    /// "snap all to grid" action after importing a layout. Rows that are
+    /// already aligned are not written back. A non-positive `spacing` is a no-op.
+    pub fn snap_positions_to_grid<T>(model: &VecModel<T>, spacing: f32)
     where
-        T: Clone + 'static,
-        F: Fn(&T) -> i32,
+        T: MovableNode,
     {
+        if spacing <= 0.0 {
+            return;
+        }
+        let snap = |value: f32| (value / spacing).round() * spacing;
         for i in 0..model.row_count() {
-            if let Some(node) = model.row_data(i) {
-                if predicate(&node) == id {
-                    return Some((i, node));
+            if let Some(mut node) = model.row_data(i) {
+                let (x, y) = (snap(node.x()), snap(node.y()));
+                if x != node.x() || y != node.y() {
+                    node.set_x(x);
+                    node.set_y(y);
+                    model.set_row_data(i, node);
                 }
             }
         }
-        None
     }
-}
 
-// ============================================================================
-// Link Validation Framework
-// ============================================================================
+    /// Align the selected nodes to a common edge or center line
+    ///
+    /// Positions are read from `model`; node sizes come from `cache` (nodes
+    /// without a cached rect are treated as zero-sized). Unselected nodes are
+    /// not touched.
+    pub fn align_selected<T, N>(
+        model: &VecModel<T>,
+        selection: &SelectionManager,
+        cache: &GeometryCache<N>,
+        mode: AlignMode,
+    ) where
+        T: MovableNode,
+        N: NodeGeometry,
+    {
+        let nodes = Self::selected_rows(model, selection, cache);
+        if nodes.is_empty() {
+            return;
+        }
 
-/// Result of link validation with optional rejection reason
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ValidationResult {
-    /// Link is valid
-    Valid,
-    /// Link is invalid with a reason
-    Invalid(ValidationError),
-}
+        let min_x = nodes.iter().map(|n| n.1.x()).fold(f32::INFINITY, f32::min);
+        let min_y = nodes.iter().map(|n| n.1.y()).fold(f32::INFINITY, f32::min);
+        let max_x = nodes.iter().map(|n| n.1.x() + n.2).fold(f32::NEG_INFINITY, f32::max);
+        let max_y = nodes.iter().map(|n| n.1.y() + n.3).fold(f32::NEG_INFINITY, f32::max);
+
+        for (row, mut node, w, h) in nodes {
+            match mode {
+                AlignMode::Left => node.set_x(min_x),
+                AlignMode::Right => node.set_x(max_x - w),
+                AlignMode::Top => node.set_y(min_y),
+                AlignMode::Bottom => node.set_y(max_y - h),
+                AlignMode::CenterX => node.set_x((min_x + max_x - w) / 2.0),
+                AlignMode::CenterY => node.set_y((min_y + max_y - h) / 2.0),
+            }
+            model.set_row_data(row, node);
+        }
+    }
 
-impl ValidationResult {
-    /// Check if the result is valid
-    pub fn is_valid(&self) -> bool {
-        matches!(self, ValidationResult::Valid)
+    /// Space the selected nodes evenly along `axis`
+    ///
+    /// The two extreme nodes stay in place and the ones in between are moved
+    /// so the gaps between neighbouring node edges are equal. Sizes come from
+    /// `cache` as in [`align_selected`](Self::align_selected). Fewer than
+    /// three selected nodes is a no-op.
+    pub fn distribute_selected<T, N>(
+        model: &VecModel<T>,
+        selection: &SelectionManager,
+        cache: &GeometryCache<N>,
+        axis: Axis,
+    ) where
+        T: MovableNode,
+        N: NodeGeometry,
+    {
+        let horizontal = axis == Axis::Horizontal;
+        let pos = |node: &T| if horizontal { node.x() } else { node.y() };
+
+        let mut nodes = Self::selected_rows(model, selection, cache);
+        if nodes.len() < 3 {
+            return;
+        }
+        nodes.sort_by(|a, b| {
+            pos(&a.1)
+                .total_cmp(&pos(&b.1))
+                .then_with(|| MovableNode::id(&a.1).cmp(&MovableNode::id(&b.1)))
+        });
+
+        let size = |n: &(usize, T, f32, f32)| if horizontal { n.2 } else { n.3 };
+        let first = &nodes[0];
+        let last = &nodes[nodes.len() - 1];
+        let span = pos(&last.1) + size(last) - pos(&first.1);
+        let total: f32 = nodes.iter().map(size).sum();
+        let gap = (span - total) / (nodes.len() - 1) as f32;
+
+        let mut cursor = pos(&first.1) + size(first) + gap;
+        let count = nodes.len();
+        for node in nodes.iter_mut().take(count - 1).skip(1) {
+            if horizontal {
+                node.1.set_x(cursor);
+            } else {
+                node.1.set_y(cursor);
+            }
+            cursor += size(node) + gap;
+            model.set_row_data(node.0, node.1.clone());
+        }
     }
 
-    /// Combine two results (AND logic): returns first error if any
-    pub fn and(self, other: ValidationResult) -> ValidationResult {
-        match self {
-            ValidationResult::Valid => other,
-            invalid => invalid,
+    /// Push overlapping nodes apart so every pair is at least `padding` apart
+    ///
+    /// Use after an automatic layout or a bulk placement that ignores node
+    /// sizes. `node_sizes` returns a node's `(width, height)`. Each iteration
+    /// visits node pairs in ascending ID order and moves both nodes of an
+    /// overlapping pair half the remaining distance apart along the axis
+    /// where they overlap least. When centers coincide on that axis the node
+    /// with the lower ID moves left or up, so results are deterministic.
+    /// Stops early once an iteration finds no overlap; only moved rows are
+    /// written back.
+    ///
+    /// # Returns
+    /// `true` if no overlaps remain, `false` if `iterations` ran out first
+    pub fn resolve_overlaps<T>(
+        model: &VecModel<T>,
+        node_sizes: impl Fn(i32) -> (f32, f32),
+        iterations: usize,
+        padding: f32,
+    ) -> bool
+    where
+        T: MovableNode,
+    {
+        let mut nodes: Vec<(usize, T, f32, f32)> = (0..model.row_count())
+            .filter_map(|row| {
+                let node = model.row_data(row)?;
+                let (w, h) = node_sizes(MovableNode::id(&node));
+                Some((row, node, w, h))
+            })
+            .collect();
+        nodes.sort_by_key(|n| MovableNode::id(&n.1));
+        let mut moved = vec![false; nodes.len()];
+
+        let mut resolved = false;
+        for _ in 0..iterations {
+            let mut any_overlap = false;
+            for i in 0..nodes.len() {
+                for j in (i + 1)..nodes.len() {
+                    let (a, b) = (&nodes[i], &nodes[j]);
+                    let (ax, ay, bx, by) = (a.1.x(), a.1.y(), b.1.x(), b.1.y());
+                    let pen_x = (ax + a.2 + padding - bx).min(bx + b.2 + padding - ax);
+                    let pen_y = (ay + a.3 + padding - by).min(by + b.3 + padding - ay);
+                    // Tolerate float error left over from earlier pushes
+                    if pen_x <= OVERLAP_TOLERANCE || pen_y <= OVERLAP_TOLERANCE {
+                        continue;
+                    }
+                    any_overlap = true;
+
+                    let (shift_x, shift_y) = if pen_x <= pen_y {
+                        let dir = if bx + b.2 / 2.0 >= ax + a.2 / 2.0 { 1.0 } else { -1.0 };
+                        (dir * pen_x / 2.0, 0.0)
+                    } else {
+                        let dir = if by + b.3 / 2.0 >= ay + a.3 / 2.0 { 1.0 } else { -1.0 };
+                        (0.0, dir * pen_y / 2.0)
+                    };
+                    let a = &mut nodes[i].1;
+                    a.set_x(ax - shift_x);
+                    a.set_y(ay - shift_y);
+                    let b = &mut nodes[j].1;
+                    b.set_x(bx + shift_x);
+                    b.set_y(by + shift_y);
+                    moved[i] = true;
+                    moved[j] = true;
+                }
+            }
+            if !any_overlap {
+                resolved = true;
+                break;
+            }
         }
+
+        for (node, _) in nodes.into_iter().zip(moved).filter(|(_, moved)| *moved) {
+            model.set_row_data(node.0, node.1);
+        }
+        resolved
     }
-}
 
-/// Reasons why a link validation failed
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ValidationError {
-    /// Pin does not exist in the geometry cache
-    PinNotFound(i32),
-    /// Cannot link a pin to itself
-    SamePin,
-    /// Cannot link pins on the same node
-    SameNode,
+    /// Sugiyama layout of just the selected nodes
+    ///
+    /// Runs [`sugiyama_layout`](crate::layout::sugiyama_layout) on the
+    /// subgraph induced by the selection: `edges` are `(source_node_id,
+    /// target_node_id)` pairs and any edge touching an unselected node is
+    /// dropped. `node_sizes` returns a node's `(width, height)`. The result
+    /// is translated so the centroid of the laid-out node centers matches the
+    /// centroid of the selection's current centers, so the tidied subgraph
+    /// stays where it was. Selected IDs missing from `model` are not laid out.
+    ///
+    /// The model is not modified; apply the returned positions as for any
+    /// other layout.
+    #[cfg(feature = "layout")]
+    pub fn auto_layout_selection<T>(
+        model: &VecModel<T>,
+        selection: &SelectionManager,
+        edges: &[(i32, i32)],
+        node_sizes: impl Fn(i32) -> (f32, f32),
+        config: &crate::layout::SugiyamaConfig,
+    ) -> Vec<crate::layout::NodePosition>
+    where
+        T: MovableNode,
+    {
+        let mut sizes: Vec<(i32, (f64, f64))> = Vec::new();
+        let mut sizes_by_id: HashMap<i32, (f64, f64)> = HashMap::new();
+        let (mut center_x, mut center_y) = (0.0_f64, 0.0_f64);
+        for node in (0..model.row_count()).filter_map(|row| model.row_data(row)) {
+            let id = MovableNode::id(&node);
+            if !selection.contains(id) || sizes_by_id.contains_key(&id) {
+                continue;
+            }
+            let (w, h) = node_sizes(id);
+            let size = (w as f64, h as f64);
+            center_x += node.x() as f64 + size.0 / 2.0;
+            center_y += node.y() as f64 + size.1 / 2.0;
+            sizes.push((id, size));
+            sizes_by_id.insert(id, size);
+        }
+        if sizes.is_empty() {
+            return Vec::new();
+        }
+
+        let sub_edges: Vec<(i32, i32)> = edges
+            .iter()
+            .copied()
+            .filter(|(src, dst)| sizes_by_id.contains_key(src) && sizes_by_id.contains_key(dst))
+            .collect();
+        let mut positions = crate::layout::sugiyama_layout(&sub_edges, &sizes, config);
+
+        // Shift the layout's centroid onto the selection's old centroid
+        let count = positions.len() as f64;
+        let (mut laid_x, mut laid_y) = (0.0_f64, 0.0_f64);
+        for pos in &positions {
+            let (w, h) = sizes_by_id[&pos.id];
+            laid_x += pos.x + w / 2.0;
+            laid_y += pos.y + h / 2.0;
+        }
+        let n = sizes.len() as f64;
+        let (dx, dy) = (center_x / n - laid_x / count, center_y / n - laid_y / count);
+        for pos in &mut positions {
+            pos.x += dx;
+            pos.y += dy;
+        }
+        positions
+    }
+
+    /// Selected rows of `model` with their cached `(width, height)`
+    fn selected_rows<T, N>(
+        model: &VecModel<T>,
+        selection: &SelectionManager,
+        cache: &GeometryCache<N>,
+    ) -> Vec<(usize, T, f32, f32)>
+    where
+        T: MovableNode,
+        N: NodeGeometry,
+    {
+        (0..model.row_count())
+            .filter_map(|row| {
+                let node = model.row_data(row)?;
+                let id = MovableNode::id(&node);
+                if !selection.contains(id) {
+                    return None;
+                }
+                let (_, _, w, h) = cache
+                    .node_rects
+                    .get(&id)
+                    .map(NodeGeometry::rect)
+                    .unwrap_or_default();
+                Some((row, node, w, h))
+            })
+            .collect()
+    }
+
+    /// Append a copy of every selected node, offset by `(dx, dy)`
+    ///
+    /// The Ctrl+D gesture. Each copy gets its ID from `id_gen`, written into
+    /// the copy by `assign_id`, and copies are appended in model order. Links
+    /// are not duplicated; the new nodes report their pins once Slint renders
+    /// them.
+    ///
+    /// # Returns
+    /// The IDs of the new nodes, in the order they were appended
+    pub fn duplicate_selected<T>(
+        model: &VecModel<T>,
+        selection: &SelectionManager,
+        dx: f32,
+        dy: f32,
+        mut id_gen: impl FnMut() -> i32,
+        mut assign_id: impl FnMut(&mut T, i32),
+    ) -> Vec<i32>
+    where
+        T: MovableNode,
+    {
+        let copies: Vec<T> = (0..model.row_count())
+            .filter_map(|row| model.row_data(row))
+            .filter(|node| selection.contains(MovableNode::id(node)))
+            .collect();
+
+        copies
+            .into_iter()
+            .map(|mut node| {
+                let id = id_gen();
+                assign_id(&mut node, id);
+                node.set_x(node.x() + dx);
+                node.set_y(node.y() + dy);
+                model.push(node);
+                id
+            })
+            .collect()
+    }
+
+    /// Check if a link with the given direction already exists
+    ///
+    /// Prevents duplicate connections between the same pins.
+    ///
+    /// # Arguments
+    /// * `start_pin` - Start pin ID
+    /// * `end_pin` - End pin ID
+    /// * `links` - Iterator over existing links
+    pub fn duplicate_link_exists<I, L>(start_pin: i32, end_pin: i32, links: I) -> bool
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+    {
+        links
+            .into_iter()
+            .any(|link| link.start_pin_id() == start_pin && link.end_pin_id() == end_pin)
+    }
+
+    /// Find the IDs of all links from `start_pin` to `end_pin`
+    ///
+    /// Like [`duplicate_link_exists`](Self::duplicate_link_exists) the pair is
+    /// directed: links from `end_pin` to `start_pin` are not returned. Use
+    /// this to remove a specific connection, e.g. when the user clicks a pin
+    /// pair to disconnect it.
+    ///
+    /// # Returns
+    /// Matching link IDs in iteration order
+    pub fn find_links_between_pins<I, L>(start_pin: i32, end_pin: i32, links: I) -> Vec<i32>
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+    {
+        links
+            .into_iter()
+            .filter(|link| link.start_pin_id() == start_pin && link.end_pin_id() == end_pin)
+            .map(|link| link.id())
+            .collect()
+    }
+
+    /// Find a node by ID in a VecModel using a predicate function
+    ///
+    /// Useful for searching multiple node models when IDs need to be matched
+    /// against specific fields.
+    ///
+    /// # Arguments
+    /// * `model` - The VecModel containing nodes
+    /// * `id` - The ID to search for
+    /// * `predicate` - Function that extracts ID from a node
+    ///
+    /// # Example
+    /// ```ignore
+    /// let (index, node) = GraphLogic::find_node_by_id(
+    ///     &nodes_model,
+    ///     42,
+    ///     |n| n.id,
+    /// ).unwrap();
+    /// ```
+    pub fn find_node_by_id<T, F>(model: &VecModel<T>, id: i32, predicate: F) -> Option<(usize, T)>
+    where
+        T: Clone + 'static,
+        F: Fn(&T) -> i32,
+    {
+        for i in 0..model.row_count() {
+            if let Some(node) = model.row_data(i) {
+                if predicate(&node) == id {
+                    return Some((i, node));
+                }
+            }
+        }
+        None
+    }
+
+    /// Split a link by routing it through a newly inserted node
+    ///
+    /// The link `link_id` (start pin A, end pin B) is replaced by A → `new_node_in_pin`
+    /// and `new_node_out_pin` → B, e.g. after the user drops a node onto a link.
+    /// `LinkModel` is read-only, so the new links are built by `make_link`,
+    /// which receives `(link_id, start_pin, end_pin)`; their IDs come from
+    /// `id_gen`. The first new link takes the original's row and the second is
+    /// inserted right after it.
+    ///
+    /// # Returns
+    /// The IDs of the two new links, or `None` (and no change) if `link_id` is
+    /// not in the model
+    pub fn split_link<L>(
+        link_id: i32,
+        new_node_in_pin: i32,
+        new_node_out_pin: i32,
+        links: &VecModel<L>,
+        make_link: impl Fn(i32, i32, i32) -> L,
+        mut id_gen: impl FnMut() -> i32,
+    ) -> Option<(i32, i32)>
+    where
+        L: LinkModel + Clone + 'static,
+    {
+        let (row, original) = Self::find_node_by_id(links, link_id, |link| link.id())?;
+
+        let first_id = id_gen();
+        let second_id = id_gen();
+        links.set_row_data(row, make_link(first_id, original.start_pin_id(), new_node_in_pin));
+        links.insert(row + 1, make_link(second_id, new_node_out_pin, original.end_pin_id()));
+        Some((first_id, second_id))
+    }
+
+    /// Move one end of an existing link to a different pin
+    ///
+    /// The gesture of grabbing a link's endpoint and dropping it on another
+    /// pin. The candidate pin pair is checked with `validator` against all
+    /// other links (the link being moved is left out, so it never counts as
+    /// its own duplicate). If it passes, the link's row is replaced with
+    /// `rebuild(&old_link, new_pin)`, which must return the link with
+    /// `which_end` set to `new_pin`; the link keeps its row.
+    ///
+    /// # Errors
+    /// [`ValidationError::LinkNotFound`] if `link_id` is not in the model, or
+    /// the validator's error. The model is unchanged on error.
+    #[allow(clippy::too_many_arguments)]
+    pub fn reconnect_link<L, N, V>(
+        link_id: i32,
+        which_end: LinkEnd,
+        new_pin: i32,
+        links: &VecModel<L>,
+        rebuild: impl Fn(&L, i32) -> L,
+        validator: &V,
+        cache: &GeometryCache<N>,
+    ) -> Result<(), ValidationError>
+    where
+        L: LinkModel + Clone + 'static,
+        V: LinkValidator<N, L> + ?Sized,
+    {
+        let (row, original) = Self::find_node_by_id(links, link_id, |link| link.id())
+            .ok_or(ValidationError::LinkNotFound(link_id))?;
+
+        let (start_pin, end_pin) = match which_end {
+            LinkEnd::Start => (new_pin, original.end_pin_id()),
+            LinkEnd::End => (original.start_pin_id(), new_pin),
+        };
+        let others: Vec<L> = links.iter().filter(|link| link.id() != link_id).collect();
+        if let ValidationResult::Invalid(error) =
+            validator.validate(start_pin, end_pin, cache, &others)
+        {
+            return Err(error);
+        }
+
+        links.set_row_data(row, rebuild(&original, new_pin));
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Alignment Guides
+// ============================================================================
+
+/// Snap guide line produced by [`compute_alignment_guides`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guide {
+    /// Orientation of the guide line: `Vertical` is the line `x = position`
+    /// (aligning left edges, centers or right edges), `Horizontal` is the
+    /// line `y = position`
+    pub axis: Axis,
+    /// World coordinate of the line
+    pub position: f32,
+    /// Offset to add to the moving node's x (vertical guides) or y
+    /// (horizontal guides) so it lands on the line
+    pub snapped_delta: f32,
+}
+
+/// Edge and center coordinates `[start, center, end]` of a span
+fn span_lines(start: f32, size: f32) -> [f32; 3] {
+    [start, start + size / 2.0, start + size]
+}
+
+/// Snap guides for a node being dragged near other nodes.
+///
+/// Compares the left edge, center and right edge of `moving_rect`
+/// `(x, y, width, height)` with those of every rect in `other_rects` (and
+/// likewise top, center and bottom). On each axis the closest match within
+/// `threshold` wins; all lines that coincide with the moved node after
+/// applying that snap are returned, so several nodes sharing an edge yield a
+/// guide each. Vertical guides come first, each group sorted by position.
+///
+/// Apply the `snapped_delta` of the first guide of each axis to the drag
+/// position; guides of the same axis share the same delta.
+pub fn compute_alignment_guides(
+    moving_rect: (f32, f32, f32, f32),
+    other_rects: impl Iterator<Item = (f32, f32, f32, f32)>,
+    threshold: f32,
+) -> Vec<Guide> {
+    let (mx, my, mw, mh) = moving_rect;
+    let moving_x = span_lines(mx, mw);
+    let moving_y = span_lines(my, mh);
+
+    let mut lines_x = Vec::new();
+    let mut lines_y = Vec::new();
+    for (x, y, w, h) in other_rects {
+        lines_x.extend(span_lines(x, w));
+        lines_y.extend(span_lines(y, h));
+    }
+
+    let mut guides = axis_guides(Axis::Vertical, &moving_x, &lines_x, threshold);
+    guides.extend(axis_guides(Axis::Horizontal, &moving_y, &lines_y, threshold));
+    guides
+}
+
+/// Guides on one axis: the closest snap within `threshold` and every line it aligns
+fn axis_guides(axis: Axis, moving: &[f32; 3], others: &[f32], threshold: f32) -> Vec<Guide> {
+    // Smallest |delta| wins; on a tie prefer the negative (left/up) snap
+    let best = moving
+        .iter()
+        .flat_map(|&m| others.iter().map(move |&o| o - m))
+        .filter(|delta| delta.abs() <= threshold)
+        .min_by(|a, b| a.abs().total_cmp(&b.abs()).then(a.total_cmp(b)));
+    let Some(delta) = best else {
+        return Vec::new();
+    };
+
+    let mut positions: Vec<f32> = others
+        .iter()
+        .copied()
+        .filter(|&o| moving.iter().any(|&m| (m + delta - o).abs() <= 1e-3))
+        .collect();
+    positions.sort_by(f32::total_cmp);
+    positions.dedup_by(|a, b| (*a - *b).abs() <= 1e-3);
+    positions
+        .into_iter()
+        .map(|position| Guide { axis, position, snapped_delta: delta })
+        .collect()
+}
+
+// ============================================================================
+// Link Validation Framework
+// ============================================================================
+
+/// Result of link validation with optional rejection reason
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationResult {
+    /// Link is valid
+    Valid,
+    /// Link is invalid with a reason
+    Invalid(ValidationError),
+}
+
+impl ValidationResult {
+    /// Check if the result is valid
+    pub fn is_valid(&self) -> bool {
+        matches!(self, ValidationResult::Valid)
+    }
+
+    /// Combine two results (AND logic): returns first error if any
+    pub fn and(self, other: ValidationResult) -> ValidationResult {
+        match self {
+            ValidationResult::Valid => other,
+            invalid => invalid,
+        }
+    }
+}
+
+/// Reasons why a link validation failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// Pin does not exist in the geometry cache
+    PinNotFound(i32),
+    /// Link does not exist in the link model
+    LinkNotFound(i32),
+    /// Cannot link a pin to itself
+    SamePin,
+    /// Cannot link pins on the same node
+    SameNode,
     /// Both pins are inputs or both are outputs
     IncompatibleDirection,
     /// A link between these pins already exists
@@ -307,6 +1230,14 @@ pub enum ValidationError {
     MaxConnectionsReached { pin_id: i32, max: usize },
     /// Data types are incompatible
     TypeMismatch { expected: i32, found: i32 },
+    /// The link would close a cycle through other nodes
+    WouldCreateCycle,
+    /// The link would feed a node's output back into one of its own inputs
+    ///
+    /// Reported by cycle checks for editors that otherwise allow links
+    /// between pins of the same node; [`SameNode`](Self::SameNode) is the
+    /// blanket rule that forbids them outright.
+    SelfLoop,
     /// Custom validation failure
     Custom(String),
 }
@@ -315,6 +1246,7 @@ impl fmt::Display for ValidationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::PinNotFound(id) => write!(f, "Pin {} not found", id),
+            Self::LinkNotFound(id) => write!(f, "Link {} not found", id),
             Self::SamePin => write!(f, "Cannot link pin to itself"),
             Self::SameNode => write!(f, "Cannot link pins on same node"),
             Self::IncompatibleDirection => write!(f, "Must connect input to output"),
@@ -325,11 +1257,56 @@ impl fmt::Display for ValidationError {
             Self::TypeMismatch { expected, found } => {
                 write!(f, "Type mismatch: expected {}, found {}", expected, found)
             }
+            Self::WouldCreateCycle => write!(f, "Link would create a cycle"),
+            Self::SelfLoop => write!(f, "Cannot link a node to itself"),
             Self::Custom(msg) => write!(f, "{}", msg),
         }
     }
 }
 
+/// Stable error code plus English fallback text for showing a
+/// [`ValidationError`] to users.
+///
+/// Look up `code` in your translation table and fall back to
+/// `default_message` when no translation exists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationMessage {
+    /// Machine-readable identifier that does not change between releases
+    pub code: &'static str,
+    /// The English text from the `Display` impl
+    pub default_message: String,
+}
+
+impl ValidationError {
+    /// Stable code for this error, independent of the display text.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::PinNotFound(_) => "pin_not_found",
+            Self::LinkNotFound(_) => "link_not_found",
+            Self::SamePin => "same_pin",
+            Self::SameNode => "same_node",
+            Self::IncompatibleDirection => "incompatible_direction",
+            Self::DuplicateLink => "duplicate_link",
+            Self::MaxConnectionsReached { .. } => "max_connections_reached",
+            Self::TypeMismatch { .. } => "type_mismatch",
+            Self::WouldCreateCycle => "would_create_cycle",
+            Self::SelfLoop => "self_loop",
+            Self::Custom(_) => "custom",
+        }
+    }
+
+    /// Code and default message for localized UIs.
+    ///
+    /// Variant fields (pin IDs, limits, type IDs) are not part of the code;
+    /// match on the error itself to fill them into a translated string.
+    pub fn as_user_message(&self) -> ValidationMessage {
+        ValidationMessage {
+            code: self.code(),
+            default_message: self.to_string(),
+        }
+    }
+}
+
 /// Trait for custom link validation logic.
 ///
 /// Implement this to add custom validation rules for connecting pins.
@@ -484,52 +1461,65 @@ where
     }
 }
 
-/// Composite validator that combines multiple validators
-///
-/// All validators must return Valid for the link to be valid (AND logic).
-/// Returns the first error encountered (short-circuits on failure).
+/// Validator that limits how many links may attach to a pin
 ///
-/// Note: Uses `Vec<Box<dyn ...>>` which allocates. For zero-allocation
-/// validation, chain validators manually using `ValidationResult::and()`.
+/// Which of the two pins is the input is decided with the same `output_type`
+/// convention as [`BasicLinkValidator`], so the limit applies regardless of
+/// the drag direction. Existing links are counted if either end is on the pin.
+/// A limit of `None` means unlimited. Pins that are missing from the cache or
+/// have the same direction are left for [`BasicLinkValidator`] to reject.
 ///
 /// # Example
 ///
 /// ```ignore
-/// let validator = CompositeValidator::new()
-///     .with(BasicLinkValidator::new(2))
-///     .with(NoDuplicatesValidator);
-///
+/// // Inputs accept a single link, outputs fan out freely
+/// let validator = MaxConnectionsValidator::new(2, Some(1), None);
 /// let result = validator.validate(start_pin, end_pin, &cache, &links);
 /// ```
-pub struct CompositeValidator<N = SimpleNodeGeometry, L = ()> {
-    validators: Vec<Box<dyn LinkValidator<N, L>>>,
-}
-
-impl<N, L> Default for CompositeValidator<N, L> {
-    fn default() -> Self {
-        Self::new()
-    }
+#[derive(Clone, Copy, Debug)]
+pub struct MaxConnectionsValidator {
+    /// Maximum links per input pin
+    pub per_input: Option<usize>,
+    /// Maximum links per output pin
+    pub per_output: Option<usize>,
+    output_type: i32,
 }
 
-impl<N, L> CompositeValidator<N, L> {
-    /// Create a new empty composite validator
-    pub fn new() -> Self {
+impl MaxConnectionsValidator {
+    /// Create a new connection-limit validator
+    ///
+    /// # Arguments
+    /// * `output_type` - The pin type integer representing "Output"
+    /// * `per_input` - Maximum links per input pin (`None` = unlimited)
+    /// * `per_output` - Maximum links per output pin (`None` = unlimited)
+    pub fn new(output_type: i32, per_input: Option<usize>, per_output: Option<usize>) -> Self {
         Self {
-            validators: Vec::new(),
+            per_input,
+            per_output,
+            output_type,
         }
     }
 
-    /// Add a validator to the composite
-    ///
-    /// Validators are checked in the order they were added.
-    /// The first validator to return Invalid will short-circuit.
-    pub fn with<V: LinkValidator<N, L> + 'static>(mut self, validator: V) -> Self {
-        self.validators.push(Box::new(validator));
-        self
+    fn check_limit<L: LinkModel>(pin_id: i32, max: Option<usize>, links: &[L]) -> ValidationResult {
+        let Some(max) = max else {
+            return ValidationResult::Valid;
+        };
+        let existing = links
+            .iter()
+            .filter(|link| link.start_pin_id() == pin_id || link.end_pin_id() == pin_id)
+            .count();
+        if existing >= max {
+            ValidationResult::Invalid(ValidationError::MaxConnectionsReached { pin_id, max })
+        } else {
+            ValidationResult::Valid
+        }
     }
 }
 
-impl<N, L> LinkValidator<N, L> for CompositeValidator<N, L> {
+impl<N, L> LinkValidator<N, L> for MaxConnectionsValidator
+where
+    L: LinkModel,
+{
     fn validate(
         &self,
         start_pin: i32,
@@ -537,27 +1527,448 @@ impl<N, L> LinkValidator<N, L> for CompositeValidator<N, L> {
         cache: &GeometryCache<N>,
         links: &[L],
     ) -> ValidationResult {
-        for v in &self.validators {
-            let result = v.validate(start_pin, end_pin, cache, links);
-            if !result.is_valid() {
-                return result;
-            }
-        }
-        ValidationResult::Valid
-    }
-}
+        let (Some(start_pos), Some(end_pos)) = (
+            cache.pin_positions.get(&start_pin),
+            cache.pin_positions.get(&end_pin),
+        ) else {
+            return ValidationResult::Valid;
+        };
 
-// ============================================================================
-// Tests
-// ============================================================================
+        let (output_pin, input_pin) = match (
+            start_pos.pin_type == self.output_type,
+            end_pos.pin_type == self.output_type,
+        ) {
+            (true, false) => (start_pin, end_pin),
+            (false, true) => (end_pin, start_pin),
+            _ => return ValidationResult::Valid,
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::hit_test::SimpleNodeGeometry;
-    use crate::state::StoredPin;
+        Self::check_limit(input_pin, self.per_input, links)
+            .and(Self::check_limit(output_pin, self.per_output, links))
+    }
+}
 
-    /// Helper to create a test geometry cache with pins
+/// Validator that keeps the graph acyclic, for data-flow (DAG) editors
+///
+/// The prospective link is oriented from its output pin to its input pin
+/// with the same `output_type` convention as [`BasicLinkValidator`], so the
+/// drag direction does not matter. It is rejected when the output's node is
+/// reachable from the input's node through the existing links (followed
+/// start to end, see [`GraphLogic::descendants`]) with
+/// [`ValidationError::WouldCreateCycle`], or with
+/// [`ValidationError::SelfLoop`] when both pins are on the same node. Pins that are missing from the cache or have the same
+/// direction are left for [`BasicLinkValidator`] to reject.
+///
+/// # Example
+///
+/// ```ignore
+/// let validator = CompositeValidator::new()
+///     .with(BasicLinkValidator::new(2))
+///     .with(AcyclicValidator::new(2));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct AcyclicValidator {
+    output_type: i32,
+}
+
+impl AcyclicValidator {
+    /// Create a new cycle-preventing validator
+    ///
+    /// # Arguments
+    /// * `output_type` - The pin type integer representing "Output"
+    pub fn new(output_type: i32) -> Self {
+        Self { output_type }
+    }
+}
+
+impl<N, L> LinkValidator<N, L> for AcyclicValidator
+where
+    N: NodeGeometry + Copy,
+    L: LinkModel + Clone,
+{
+    fn validate(
+        &self,
+        start_pin: i32,
+        end_pin: i32,
+        cache: &GeometryCache<N>,
+        links: &[L],
+    ) -> ValidationResult {
+        let (Some(start_pos), Some(end_pos)) = (
+            cache.pin_positions.get(&start_pin),
+            cache.pin_positions.get(&end_pin),
+        ) else {
+            return ValidationResult::Valid;
+        };
+
+        let (source, target) = match (
+            start_pos.pin_type == self.output_type,
+            end_pos.pin_type == self.output_type,
+        ) {
+            (true, false) => (start_pos.node_id, end_pos.node_id),
+            (false, true) => (end_pos.node_id, start_pos.node_id),
+            _ => return ValidationResult::Valid,
+        };
+
+        if source == target {
+            ValidationResult::Invalid(ValidationError::SelfLoop)
+        } else if GraphLogic::descendants(target, links.iter().cloned(), cache).contains(&source) {
+            ValidationResult::Invalid(ValidationError::WouldCreateCycle)
+        } else {
+            ValidationResult::Valid
+        }
+    }
+}
+
+/// Validator that checks data-type compatibility between pins
+///
+/// Both the data type of a pin and the compatibility rule are supplied by the
+/// application, so pin IDs stay opaque to the library. The output pin is
+/// identified with the same `output_type` convention as [`BasicLinkValidator`],
+/// and the rule is always called as `compatible(source_type, target_type)`
+/// with the output's type first. Pins that are missing from the cache or have
+/// the same direction are left for [`BasicLinkValidator`] to reject.
+///
+/// Rejections report `TypeMismatch { expected: target_type, found: source_type }`.
+///
+/// # Example
+///
+/// ```ignore
+/// // Pin IDs encode their data type in the last two digits
+/// let validator = TypeValidator::new(
+///     2,
+///     |pin_id| pin_id % 100,
+///     |source, target| source == target || target == ANY,
+/// );
+/// ```
+#[derive(Clone, Copy)]
+pub struct TypeValidator<F, P> {
+    output_type: i32,
+    pin_data_type: P,
+    compatible: F,
+}
+
+impl<F, P> TypeValidator<F, P>
+where
+    F: Fn(i32, i32) -> bool,
+    P: Fn(i32) -> i32,
+{
+    /// Create a new type validator
+    ///
+    /// # Arguments
+    /// * `output_type` - The pin type integer representing "Output"
+    /// * `pin_data_type` - Maps a pin ID to its application data type code
+    /// * `compatible` - Returns whether `(source_type, target_type)` may connect
+    pub fn new(output_type: i32, pin_data_type: P, compatible: F) -> Self {
+        Self {
+            output_type,
+            pin_data_type,
+            compatible,
+        }
+    }
+}
+
+impl<F, P> fmt::Debug for TypeValidator<F, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeValidator")
+            .field("output_type", &self.output_type)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<N, L, F, P> LinkValidator<N, L> for TypeValidator<F, P>
+where
+    F: Fn(i32, i32) -> bool,
+    P: Fn(i32) -> i32,
+{
+    fn validate(
+        &self,
+        start_pin: i32,
+        end_pin: i32,
+        cache: &GeometryCache<N>,
+        _links: &[L],
+    ) -> ValidationResult {
+        let (Some(start_pos), Some(end_pos)) = (
+            cache.pin_positions.get(&start_pin),
+            cache.pin_positions.get(&end_pin),
+        ) else {
+            return ValidationResult::Valid;
+        };
+
+        let (output_pin, input_pin) = match (
+            start_pos.pin_type == self.output_type,
+            end_pos.pin_type == self.output_type,
+        ) {
+            (true, false) => (start_pin, end_pin),
+            (false, true) => (end_pin, start_pin),
+            _ => return ValidationResult::Valid,
+        };
+
+        let source_type = (self.pin_data_type)(output_pin);
+        let target_type = (self.pin_data_type)(input_pin);
+
+        if (self.compatible)(source_type, target_type) {
+            ValidationResult::Valid
+        } else {
+            ValidationResult::Invalid(ValidationError::TypeMismatch {
+                expected: target_type,
+                found: source_type,
+            })
+        }
+    }
+}
+
+/// Composite validator that combines multiple validators
+///
+/// All validators must return Valid for the link to be valid (AND logic).
+/// Returns the first error encountered (short-circuits on failure).
+///
+/// Note: Uses `Vec<Box<dyn ...>>` which allocates. For zero-allocation
+/// validation, chain validators manually using `ValidationResult::and()`.
+///
+/// # Example
+///
+/// ```ignore
+/// let validator = CompositeValidator::new()
+///     .with(BasicLinkValidator::new(2))
+///     .with(NoDuplicatesValidator);
+///
+/// let result = validator.validate(start_pin, end_pin, &cache, &links);
+/// ```
+pub struct CompositeValidator<N = SimpleNodeGeometry, L = ()> {
+    validators: Vec<Box<dyn LinkValidator<N, L>>>,
+}
+
+impl<N, L> Default for CompositeValidator<N, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, L> CompositeValidator<N, L> {
+    /// Create a new empty composite validator
+    pub fn new() -> Self {
+        Self {
+            validators: Vec::new(),
+        }
+    }
+
+    /// Add a validator to the composite
+    ///
+    /// Validators are checked in the order they were added.
+    /// The first validator to return Invalid will short-circuit.
+    pub fn with<V: LinkValidator<N, L> + 'static>(mut self, validator: V) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Run every validator and collect all failures
+    ///
+    /// Unlike [`validate`](LinkValidator::validate), this does not stop at
+    /// the first failure, so a UI can show every reason a connection is
+    /// rejected at once. Errors are returned in the order validators were
+    /// added; an empty vector means the link is valid.
+    pub fn validate_all(
+        &self,
+        start_pin: i32,
+        end_pin: i32,
+        cache: &GeometryCache<N>,
+        links: &[L],
+    ) -> Vec<ValidationError> {
+        self.validators
+            .iter()
+            .filter_map(|v| match v.validate(start_pin, end_pin, cache, links) {
+                ValidationResult::Valid => None,
+                ValidationResult::Invalid(err) => Some(err),
+            })
+            .collect()
+    }
+}
+
+impl<N, L> LinkValidator<N, L> for CompositeValidator<N, L> {
+    fn validate(
+        &self,
+        start_pin: i32,
+        end_pin: i32,
+        cache: &GeometryCache<N>,
+        links: &[L],
+    ) -> ValidationResult {
+        for v in &self.validators {
+            let result = v.validate(start_pin, end_pin, cache, links);
+            if !result.is_valid() {
+                return result;
+            }
+        }
+        ValidationResult::Valid
+    }
+}
+
+/// Validate many prospective links at once, e.g. when several selected links
+/// are dropped onto new pins
+///
+/// Each `(start_pin, end_pin)` request is checked on its own against
+/// `existing_links`; requests in the same batch do not see each other. A
+/// [`CompositeValidator`] still stops at the first failure within a request,
+/// but one invalid request does not stop the rest of the batch.
+///
+/// # Returns
+/// One result per request, in request order
+pub fn validate_links_batch<N, L, V>(
+    requests: &[(i32, i32)],
+    cache: &GeometryCache<N>,
+    existing_links: &[L],
+    validator: &V,
+) -> Vec<ValidationResult>
+where
+    V: LinkValidator<N, L> + ?Sized,
+{
+    requests
+        .iter()
+        .map(|&(start_pin, end_pin)| validator.validate(start_pin, end_pin, cache, existing_links))
+        .collect()
+}
+
+/// Memoized validation results for hover feedback
+///
+/// Hovering over a pin while dragging a link asks for the same
+/// `(start_pin, end_pin)` verdict on every mouse move. This cache stores each
+/// result until [`invalidate`](Self::invalidate) is called, which bumps the
+/// [`generation`](Self::generation). Invalidate whenever links are added or
+/// removed, or pins change type, so stale verdicts are never returned.
+///
+/// # Example
+///
+/// ```ignore
+/// let validation = Rc::new(ValidationCache::new());
+///
+/// window.on_validate_link(move |start_pin, end_pin| {
+///     if let Some(result) = validation.get(start_pin, end_pin) {
+///         return result.is_valid();
+///     }
+///     let links_vec: Vec<LinkData> = links.iter().collect();
+///     validation
+///         .cached_validate(start_pin, end_pin, &cache.borrow(), &links_vec, &validator)
+///         .is_valid()
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct ValidationCache {
+    generation: Cell<u64>,
+    results: RefCell<HashMap<(i32, i32), ValidationResult>>,
+}
+
+impl ValidationCache {
+    /// Create an empty cache at generation 0
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of times the cache has been invalidated
+    pub fn generation(&self) -> u64 {
+        self.generation.get()
+    }
+
+    /// Drop all cached results and start a new generation
+    pub fn invalidate(&self) {
+        self.generation.set(self.generation.get() + 1);
+        self.results.borrow_mut().clear();
+    }
+
+    /// Cached result for a pin pair, if it was validated in this generation
+    pub fn get(&self, start_pin: i32, end_pin: i32) -> Option<ValidationResult> {
+        self.results.borrow().get(&(start_pin, end_pin)).cloned()
+    }
+
+    /// Validate a pin pair, running `validator` only on a cache miss
+    ///
+    /// The pair is ordered: `(a, b)` and `(b, a)` are cached separately
+    /// because validators may depend on direction.
+    pub fn cached_validate<N, L, V>(
+        &self,
+        start_pin: i32,
+        end_pin: i32,
+        cache: &GeometryCache<N>,
+        links: &[L],
+        validator: &V,
+    ) -> ValidationResult
+    where
+        V: LinkValidator<N, L> + ?Sized,
+    {
+        if let Some(result) = self.get(start_pin, end_pin) {
+            return result;
+        }
+        let result = validator.validate(start_pin, end_pin, cache, links);
+        self.results
+            .borrow_mut()
+            .insert((start_pin, end_pin), result.clone());
+        result
+    }
+}
+
+/// Link validator whose verdict may arrive later.
+///
+/// Use this for rules that need an external system, e.g. asking a compiler
+/// or language server whether two types unify. The validator hands the
+/// verdict to `on_result` whenever it is ready: immediately, from a timer,
+/// or from an event-loop callback such as `slint::invoke_from_event_loop`.
+/// `on_result` must be called exactly once.
+///
+/// Wrap a synchronous [`LinkValidator`] in [`ImmediateValidator`] to use it
+/// where an `AsyncLinkValidator` is expected.
+pub trait AsyncLinkValidator<N = SimpleNodeGeometry, L = ()> {
+    /// Start validating a link between two pins
+    ///
+    /// # Arguments
+    /// * `start_pin` - ID of the starting pin
+    /// * `end_pin` - ID of the ending pin
+    /// * `cache` - Geometry cache for pin information (only valid during this call)
+    /// * `links` - Slice of existing links (only valid during this call)
+    /// * `on_result` - Receives the verdict once it is known
+    fn validate_deferred<F>(
+        &self,
+        start_pin: i32,
+        end_pin: i32,
+        cache: &GeometryCache<N>,
+        links: &[L],
+        on_result: F,
+    ) where
+        F: FnOnce(ValidationResult) + 'static;
+}
+
+/// Adapter that runs a synchronous [`LinkValidator`] as an
+/// [`AsyncLinkValidator`], calling `on_result` before returning.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ImmediateValidator<V>(pub V);
+
+impl<N, L, V> AsyncLinkValidator<N, L> for ImmediateValidator<V>
+where
+    V: LinkValidator<N, L>,
+{
+    fn validate_deferred<F>(
+        &self,
+        start_pin: i32,
+        end_pin: i32,
+        cache: &GeometryCache<N>,
+        links: &[L],
+        on_result: F,
+    ) where
+        F: FnOnce(ValidationResult) + 'static,
+    {
+        on_result(self.0.validate(start_pin, end_pin, cache, links));
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hit_test::SimpleNodeGeometry;
+    use crate::state::StoredPin;
+    use std::rc::Rc;
+
+    /// Helper to create a test geometry cache with pins
     fn setup_cache() -> GeometryCache<SimpleNodeGeometry> {
         let mut cache = GeometryCache::new();
 
@@ -798,17 +2209,52 @@ mod tests {
     }
 
     #[test]
-    fn test_validator_validate_directly() {
+    fn test_composite_validate_all_collects_every_error() {
+        let cache = setup_cache_with_second_output();
+        let validator: CompositeValidator<_, TestLink> = CompositeValidator::new()
+            .with(BasicLinkValidator::new(2))
+            .with(NoDuplicatesValidator);
+
+        // Output to output, and the link already exists
+        let links = vec![TestLink {
+            id: 1,
+            start: 1001,
+            end: 3001,
+        }];
+
+        let errors = validator.validate_all(1001, 3001, &cache, &links);
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::IncompatibleDirection,
+                ValidationError::DuplicateLink
+            ]
+        );
+    }
+
+    #[test]
+    fn test_composite_validate_all_empty_when_valid() {
         let cache = setup_cache();
-        let validator = BasicLinkValidator::new(2);
+        let validator: CompositeValidator<_, TestLink> = CompositeValidator::new()
+            .with(BasicLinkValidator::new(2))
+            .with(NoDuplicatesValidator);
         let links: Vec<TestLink> = vec![];
 
-        let result = validator.validate(1001, 2001, &cache, &links);
-        assert!(result.is_valid());
+        assert!(validator.validate_all(1001, 2001, &cache, &links).is_empty());
     }
 
     #[test]
-    fn test_validation_result_and_combinator() {
+    fn test_validator_validate_directly() {
+        let cache = setup_cache();
+        let validator = BasicLinkValidator::new(2);
+        let links: Vec<TestLink> = vec![];
+
+        let result = validator.validate(1001, 2001, &cache, &links);
+        assert!(result.is_valid());
+    }
+
+    #[test]
+    fn test_validation_result_and_combinator() {
         let result1 = ValidationResult::Valid;
         let result2 = ValidationResult::Valid;
         assert!(result1.and(result2).is_valid());
@@ -878,12 +2324,54 @@ mod tests {
             ),
             "Type mismatch: expected 1, found 2"
         );
+        assert_eq!(
+            format!("{}", ValidationError::WouldCreateCycle),
+            "Link would create a cycle"
+        );
+        assert_eq!(
+            format!("{}", ValidationError::SelfLoop),
+            "Cannot link a node to itself"
+        );
         assert_eq!(
             format!("{}", ValidationError::Custom("Test error".to_string())),
             "Test error"
         );
     }
 
+    #[test]
+    fn test_validation_error_user_message_codes_are_stable() {
+        let cases = [
+            (ValidationError::PinNotFound(42), "pin_not_found"),
+            (ValidationError::LinkNotFound(7), "link_not_found"),
+            (ValidationError::SamePin, "same_pin"),
+            (ValidationError::SameNode, "same_node"),
+            (ValidationError::IncompatibleDirection, "incompatible_direction"),
+            (ValidationError::DuplicateLink, "duplicate_link"),
+            (
+                ValidationError::MaxConnectionsReached { pin_id: 1, max: 2 },
+                "max_connections_reached",
+            ),
+            (ValidationError::TypeMismatch { expected: 1, found: 2 }, "type_mismatch"),
+            (ValidationError::WouldCreateCycle, "would_create_cycle"),
+            (ValidationError::SelfLoop, "self_loop"),
+            (ValidationError::Custom("Nope".to_string()), "custom"),
+        ];
+
+        for (error, code) in cases {
+            let message = error.as_user_message();
+            assert_eq!(message.code, code);
+            assert_eq!(message.default_message, error.to_string());
+        }
+    }
+
+    #[test]
+    fn test_validation_error_code_ignores_fields() {
+        assert_eq!(
+            ValidationError::PinNotFound(1).code(),
+            ValidationError::PinNotFound(2).code()
+        );
+    }
+
     /// Test a custom validator implementation
     #[test]
     fn test_custom_validator() {
@@ -945,6 +2433,291 @@ mod tests {
         assert!(result.is_valid());
     }
 
+    // ========================================================================
+    // MaxConnectionsValidator tests
+    // ========================================================================
+
+    /// setup_cache() plus node 3 with output pin 3001
+    fn setup_cache_with_second_output() -> GeometryCache<SimpleNodeGeometry> {
+        let mut cache = setup_cache();
+        cache.update_node_rect(3, 0.0, 200.0, 100.0, 50.0);
+        cache.handle_pin_report(3001, 3, 2, 100.0, 25.0);
+        cache
+    }
+
+    #[test]
+    fn test_max_connections_single_input_rejects_second_link() {
+        let cache = setup_cache_with_second_output();
+        let validator = MaxConnectionsValidator::new(2, Some(1), None);
+        let existing = vec![TestLink { id: 1, start: 1001, end: 2001 }];
+
+        let result = validator.validate(3001, 2001, &cache, &existing);
+        assert_eq!(
+            result,
+            ValidationResult::Invalid(ValidationError::MaxConnectionsReached {
+                pin_id: 2001,
+                max: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_max_connections_input_detected_when_dragging_from_input() {
+        let cache = setup_cache_with_second_output();
+        let validator = MaxConnectionsValidator::new(2, Some(1), None);
+        let existing = vec![TestLink { id: 1, start: 1001, end: 2001 }];
+
+        // Dragged from the input pin towards the output
+        let result = validator.validate(2001, 3001, &cache, &existing);
+        assert!(!result.is_valid());
+    }
+
+    #[test]
+    fn test_max_connections_unlimited_fan_out() {
+        let cache = setup_cache_with_second_output();
+        let validator = MaxConnectionsValidator::new(2, Some(1), None);
+        let existing = vec![TestLink { id: 1, start: 1001, end: 2001 }];
+
+        // Output 1001 already has a link but outputs are unlimited
+        let result = validator.validate(1001, 2002, &cache, &existing);
+        assert!(result.is_valid());
+    }
+
+    #[test]
+    fn test_max_connections_per_output_limit() {
+        let cache = setup_cache();
+        let validator = MaxConnectionsValidator::new(2, None, Some(1));
+        let existing = vec![TestLink { id: 1, start: 1001, end: 2001 }];
+
+        let result = validator.validate(1001, 2002, &cache, &existing);
+        assert_eq!(
+            result,
+            ValidationResult::Invalid(ValidationError::MaxConnectionsReached {
+                pin_id: 1001,
+                max: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_max_connections_ignores_unknown_pins() {
+        let cache = setup_cache();
+        let validator = MaxConnectionsValidator::new(2, Some(0), Some(0));
+        let no_links: Vec<TestLink> = vec![];
+        assert!(validator.validate(1001, 9999, &cache, &no_links).is_valid());
+    }
+
+    // ========================================================================
+    // AcyclicValidator tests
+    // ========================================================================
+
+    #[test]
+    fn test_acyclic_rejects_link_back_to_ancestor() {
+        // 1 -> 2 -> 3
+        let cache = chain_cache(&[1, 2, 3]);
+        let links = vec![node_link(1, 1, 2), node_link(2, 2, 3)];
+        let validator = AcyclicValidator::new(2);
+        let cycle = ValidationResult::Invalid(ValidationError::WouldCreateCycle);
+
+        // Output of 3 into input of 1
+        assert_eq!(validator.validate(31, 10, &cache, &links), cycle);
+        // Same link dragged from the input side
+        assert_eq!(validator.validate(10, 31, &cache, &links), cycle);
+        // Node 2 feeding itself
+        assert_eq!(
+            validator.validate(21, 20, &cache, &links),
+            ValidationResult::Invalid(ValidationError::SelfLoop)
+        );
+    }
+
+    #[test]
+    fn test_acyclic_allows_forward_link() {
+        let cache = chain_cache(&[1, 2, 3, 4]);
+        let links = vec![node_link(1, 1, 2), node_link(2, 2, 3)];
+        let validator = AcyclicValidator::new(2);
+
+        // Skip connection 1 -> 3 and a link into an unconnected node
+        assert!(validator.validate(11, 30, &cache, &links).is_valid());
+        assert!(validator.validate(41, 10, &cache, &links).is_valid());
+        // Unknown pins are BasicLinkValidator's job
+        assert!(validator.validate(99, 10, &cache, &links).is_valid());
+    }
+
+    // ========================================================================
+    // TypeValidator tests
+    // ========================================================================
+
+    /// Data type encoded in the last digit of the pin ID
+    fn last_digit(pin_id: i32) -> i32 {
+        pin_id % 10
+    }
+
+    #[test]
+    fn test_type_validator_equal_types_only() {
+        let mut cache = setup_cache();
+        cache.handle_pin_report(2003, 2, 1, 0.0, 45.0);
+        let validator = TypeValidator::new(2, last_digit, |source, target| source == target);
+        let no_links: Vec<TestLink> = vec![];
+
+        // 1001 (type 1) -> 2001 (type 1)
+        assert!(validator.validate(1001, 2001, &cache, &no_links).is_valid());
+        // 1001 (type 1) -> 2003 (type 3)
+        assert_eq!(
+            validator.validate(1001, 2003, &cache, &no_links),
+            ValidationResult::Invalid(ValidationError::TypeMismatch {
+                expected: 3,
+                found: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_type_validator_source_is_output_regardless_of_drag_direction() {
+        let mut cache = setup_cache();
+        cache.handle_pin_report(2003, 2, 1, 0.0, 45.0);
+        let validator = TypeValidator::new(2, last_digit, |source, target| source == target);
+        let no_links: Vec<TestLink> = vec![];
+
+        assert_eq!(
+            validator.validate(2003, 1001, &cache, &no_links),
+            ValidationResult::Invalid(ValidationError::TypeMismatch {
+                expected: 3,
+                found: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_type_validator_any_accepts_all() {
+        const ANY: i32 = 9;
+        let mut cache = setup_cache();
+        cache.handle_pin_report(2009, 2, 1, 0.0, 45.0);
+        let validator = TypeValidator::new(2, last_digit, |source, target| {
+            source == target || target == ANY
+        });
+        let no_links: Vec<TestLink> = vec![];
+
+        assert!(validator.validate(1001, 2009, &cache, &no_links).is_valid());
+        assert!(!validator.validate(1001, 2002, &cache, &no_links).is_valid());
+    }
+
+    #[test]
+    fn test_type_validator_in_composite() {
+        let mut cache = setup_cache();
+        cache.handle_pin_report(2003, 2, 1, 0.0, 45.0);
+        let validator: CompositeValidator<_, TestLink> = CompositeValidator::new()
+            .with(BasicLinkValidator::new(2))
+            .with(TypeValidator::new(2, last_digit, |s, t| s == t));
+        let no_links: Vec<TestLink> = vec![];
+
+        assert!(validator.validate(1001, 2001, &cache, &no_links).is_valid());
+        assert!(!validator.validate(1001, 2003, &cache, &no_links).is_valid());
+    }
+
+    // ========================================================================
+    // validate_links_batch() tests
+    // ========================================================================
+
+    #[test]
+    fn test_validate_links_batch_results_in_request_order() {
+        let cache = setup_cache_with_second_output();
+        let validator: CompositeValidator<_, TestLink> = CompositeValidator::new()
+            .with(BasicLinkValidator::new(2))
+            .with(NoDuplicatesValidator);
+        let existing = vec![TestLink { id: 1, start: 1001, end: 2001 }];
+        let requests = [(3001, 2001), (1001, 2001), (1001, 1001), (2001, 3001), (9999, 2001)];
+
+        let results = validate_links_batch(&requests, &cache, &existing, &validator);
+        assert_eq!(
+            results,
+            vec![
+                ValidationResult::Valid,
+                ValidationResult::Invalid(ValidationError::DuplicateLink),
+                ValidationResult::Invalid(ValidationError::SamePin),
+                ValidationResult::Valid,
+                ValidationResult::Invalid(ValidationError::PinNotFound(9999)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_links_batch_empty() {
+        let cache = setup_cache();
+        let no_links: Vec<TestLink> = vec![];
+        let results = validate_links_batch(&[], &cache, &no_links, &BasicLinkValidator::new(2));
+        assert!(results.is_empty());
+    }
+
+    // ========================================================================
+    // ValidationCache tests
+    // ========================================================================
+
+    /// Validator that counts how often it runs
+    #[derive(Default)]
+    struct CountingValidator {
+        calls: Cell<usize>,
+    }
+
+    impl LinkValidator<SimpleNodeGeometry, TestLink> for CountingValidator {
+        fn validate(
+            &self,
+            start_pin: i32,
+            end_pin: i32,
+            cache: &GeometryCache<SimpleNodeGeometry>,
+            links: &[TestLink],
+        ) -> ValidationResult {
+            self.calls.set(self.calls.get() + 1);
+            BasicLinkValidator::new(2).validate(start_pin, end_pin, cache, links)
+        }
+    }
+
+    #[test]
+    fn test_validation_cache_hit_skips_validator() {
+        let cache = setup_cache();
+        let validator = CountingValidator::default();
+        let validation = ValidationCache::new();
+        let no_links: Vec<TestLink> = vec![];
+
+        let first = validation.cached_validate(1001, 2001, &cache, &no_links, &validator);
+        let second = validation.cached_validate(1001, 2001, &cache, &no_links, &validator);
+        assert!(first.is_valid());
+        assert_eq!(first, second);
+        assert_eq!(validator.calls.get(), 1);
+        assert_eq!(validation.generation(), 0);
+
+        // Reverse direction is a different key
+        validation.cached_validate(2001, 1001, &cache, &no_links, &validator);
+        assert_eq!(validator.calls.get(), 2);
+    }
+
+    #[test]
+    fn test_validation_cache_invalidate_revalidates() {
+        let cache = setup_cache();
+        let validator = CountingValidator::default();
+        let validation = ValidationCache::new();
+        let no_links: Vec<TestLink> = vec![];
+
+        validation.cached_validate(1001, 2001, &cache, &no_links, &validator);
+        validation.invalidate();
+        assert_eq!(validation.generation(), 1);
+        assert_eq!(validation.get(1001, 2001), None);
+
+        validation.cached_validate(1001, 2001, &cache, &no_links, &validator);
+        assert_eq!(validator.calls.get(), 2);
+    }
+
+    #[test]
+    fn test_validation_cache_stores_invalid_results() {
+        let cache = setup_cache();
+        let validator = CountingValidator::default();
+        let validation = ValidationCache::new();
+        let no_links: Vec<TestLink> = vec![];
+
+        let result = validation.cached_validate(1001, 1001, &cache, &no_links, &validator);
+        assert_eq!(result, ValidationResult::Invalid(ValidationError::SamePin));
+        assert_eq!(validation.get(1001, 1001), Some(result));
+    }
+
     // ========================================================================
     // GraphLogic::find_links_connected_to_node() tests
     // ========================================================================
@@ -1032,6 +2805,41 @@ mod tests {
         assert!(!connected.contains(&3));
     }
 
+    // ========================================================================
+    // GraphLogic::links_orphaned_by_nodes() tests
+    // ========================================================================
+
+    fn orphan_test_links() -> Vec<TestLink> {
+        vec![
+            TestLink { id: 1, start: 1001, end: 2001 },
+            TestLink { id: 2, start: 3001, end: 2001 },
+        ]
+    }
+
+    #[test]
+    fn test_links_orphaned_by_start_node() {
+        let cache = setup_cache_with_second_output();
+        let orphaned = GraphLogic::links_orphaned_by_nodes(&[1], orphan_test_links(), &cache);
+        assert_eq!(orphaned, vec![1]);
+    }
+
+    #[test]
+    fn test_links_orphaned_by_end_node() {
+        let cache = setup_cache_with_second_output();
+        let orphaned = GraphLogic::links_orphaned_by_nodes(&[2], orphan_test_links(), &cache);
+        assert_eq!(orphaned, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_links_orphaned_leaves_untouched_links() {
+        let cache = setup_cache_with_second_output();
+        let orphaned = GraphLogic::links_orphaned_by_nodes(&[3], orphan_test_links(), &cache);
+        assert_eq!(orphaned, vec![2]);
+
+        let none = GraphLogic::links_orphaned_by_nodes(&[99], orphan_test_links(), &cache);
+        assert!(none.is_empty());
+    }
+
     // ========================================================================
     // GraphLogic::normalize_link_direction() tests
     // ========================================================================
@@ -1100,6 +2908,26 @@ mod tests {
         assert!(!GraphLogic::duplicate_link_exists(2001, 1001, links));
     }
 
+    // ========================================================================
+    // GraphLogic::find_links_between_pins() tests
+    // ========================================================================
+
+    #[test]
+    fn test_find_links_between_pins_returns_all_duplicates() {
+        let links = vec![
+            TestLink { id: 1, start: 1001, end: 2001 },
+            TestLink { id: 2, start: 1001, end: 2002 },
+            TestLink { id: 3, start: 1001, end: 2001 },
+        ];
+        assert_eq!(GraphLogic::find_links_between_pins(1001, 2001, links), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_find_links_between_pins_direction_matters() {
+        let links = vec![TestLink { id: 1, start: 1001, end: 2001 }];
+        assert!(GraphLogic::find_links_between_pins(2001, 1001, links).is_empty());
+    }
+
     // ========================================================================
     // GraphLogic::find_node_by_id() tests
     // ========================================================================
@@ -1337,4 +3165,860 @@ mod tests {
         assert_eq!(node.x, 50.0);
         assert_eq!(node.y, 70.0);
     }
+
+    // ========================================================================
+    // GraphLogic::snap_positions_to_grid() tests
+    // ========================================================================
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct PositionedNode {
+        id: i32,
+        x: f32,
+        y: f32,
+    }
+
+    impl MovableNode for PositionedNode {
+        fn id(&self) -> i32 {
+            self.id
+        }
+        fn x(&self) -> f32 {
+            self.x
+        }
+        fn y(&self) -> f32 {
+            self.y
+        }
+        fn set_x(&mut self, x: f32) {
+            self.x = x;
+        }
+        fn set_y(&mut self, y: f32) {
+            self.y = y;
+        }
+    }
+
+    fn positioned_model(nodes: &[(i32, f32, f32)]) -> VecModel<PositionedNode> {
+        VecModel::from(
+            nodes
+                .iter()
+                .map(|&(id, x, y)| PositionedNode { id, x, y })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn position_of(model: &VecModel<PositionedNode>, row: usize) -> (f32, f32) {
+        let node = model.row_data(row).unwrap();
+        (node.x, node.y)
+    }
+
+    #[test]
+    fn test_snap_positions_to_grid_rounds_to_nearest() {
+        let model = positioned_model(&[(1, 103.0, 97.0), (2, -13.0, 12.0)]);
+        GraphLogic::snap_positions_to_grid(&model, 24.0);
+        assert_eq!(position_of(&model, 0), (96.0, 96.0));
+        assert_eq!(position_of(&model, 1), (-24.0, 24.0));
+    }
+
+    #[test]
+    fn test_snap_positions_to_grid_leaves_aligned_nodes() {
+        let model = positioned_model(&[(1, 48.0, 72.0), (2, 0.0, 0.0)]);
+        GraphLogic::snap_positions_to_grid(&model, 24.0);
+        assert_eq!(position_of(&model, 0), (48.0, 72.0));
+        assert_eq!(position_of(&model, 1), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_snap_positions_to_grid_zero_spacing_is_noop() {
+        let model = positioned_model(&[(1, 103.0, 97.0)]);
+        GraphLogic::snap_positions_to_grid(&model, 0.0);
+        assert_eq!(position_of(&model, 0), (103.0, 97.0));
+    }
+
+    #[test]
+    fn test_snap_drag_delta_snaps_position_not_delta() {
+        // 107 rounds down to 96, 117 rounds up to 120
+        assert_eq!(GraphLogic::snap_drag_delta((100.0, 48.0), (7.0, 0.0), 24.0), (-4.0, 0.0));
+        assert_eq!(GraphLogic::snap_drag_delta((100.0, 48.0), (17.0, 0.0), 24.0), (20.0, 0.0));
+        // Off-grid origin in y: 30 + 10 = 40 lands on 48
+        assert_eq!(GraphLogic::snap_drag_delta((96.0, 30.0), (0.0, 10.0), 24.0), (0.0, 18.0));
+    }
+
+    #[test]
+    fn test_snap_drag_delta_zero_spacing_is_passthrough() {
+        assert_eq!(GraphLogic::snap_drag_delta((100.0, 0.0), (7.0, 3.0), 0.0), (7.0, 3.0));
+    }
+
+    // ========================================================================
+    // GraphLogic::align_selected() / distribute_selected() tests
+    // ========================================================================
+
+    fn sized_cache(rects: &[(i32, f32, f32)]) -> GeometryCache<SimpleNodeGeometry> {
+        let mut cache = GeometryCache::new();
+        for &(id, w, h) in rects {
+            cache.update_node_rect(id, 0.0, 0.0, w, h);
+        }
+        cache
+    }
+
+    fn select(ids: &[i32]) -> SelectionManager {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection(ids.iter().copied());
+        selection
+    }
+
+    #[test]
+    fn test_align_left_sets_equal_x() {
+        let model = positioned_model(&[(1, 30.0, 0.0), (2, 10.0, 50.0), (3, 70.0, 100.0), (4, 0.0, 0.0)]);
+        let cache = sized_cache(&[(1, 100.0, 40.0), (2, 80.0, 40.0), (3, 60.0, 40.0)]);
+        GraphLogic::align_selected(&model, &select(&[1, 2, 3]), &cache, AlignMode::Left);
+
+        assert_eq!(position_of(&model, 0), (10.0, 0.0));
+        assert_eq!(position_of(&model, 1), (10.0, 50.0));
+        assert_eq!(position_of(&model, 2), (10.0, 100.0));
+        // Unselected node untouched
+        assert_eq!(position_of(&model, 3), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_align_right_and_center_use_node_sizes() {
+        let cache = sized_cache(&[(1, 100.0, 40.0), (2, 50.0, 20.0)]);
+
+        let model = positioned_model(&[(1, 0.0, 0.0), (2, 0.0, 100.0)]);
+        GraphLogic::align_selected(&model, &select(&[1, 2]), &cache, AlignMode::Right);
+        assert_eq!(position_of(&model, 1), (50.0, 100.0));
+
+        let model = positioned_model(&[(1, 0.0, 0.0), (2, 0.0, 100.0)]);
+        GraphLogic::align_selected(&model, &select(&[1, 2]), &cache, AlignMode::CenterX);
+        assert_eq!(position_of(&model, 0), (0.0, 0.0));
+        assert_eq!(position_of(&model, 1), (25.0, 100.0));
+
+        let model = positioned_model(&[(1, 0.0, 0.0), (2, 200.0, 100.0)]);
+        GraphLogic::align_selected(&model, &select(&[1, 2]), &cache, AlignMode::Bottom);
+        assert_eq!(position_of(&model, 0), (0.0, 80.0));
+        assert_eq!(position_of(&model, 1), (200.0, 100.0));
+    }
+
+    #[test]
+    fn test_distribute_horizontal_yields_equal_gaps() {
+        let model = positioned_model(&[(1, 0.0, 0.0), (2, 40.0, 10.0), (3, 300.0, 20.0)]);
+        let cache = sized_cache(&[(1, 100.0, 40.0), (2, 60.0, 40.0), (3, 80.0, 40.0)]);
+        GraphLogic::distribute_selected(&model, &select(&[1, 2, 3]), &cache, Axis::Horizontal);
+
+        let (x1, _) = position_of(&model, 0);
+        let (x2, y2) = position_of(&model, 1);
+        let (x3, _) = position_of(&model, 2);
+        assert_eq!(x1, 0.0);
+        assert_eq!(x3, 300.0);
+        assert_eq!(x2 - (x1 + 100.0), x3 - (x2 + 60.0));
+        // Only the distributed axis changes
+        assert_eq!(y2, 10.0);
+    }
+
+    #[test]
+    fn test_distribute_vertical_orders_by_position() {
+        // Model order differs from spatial order
+        let model = positioned_model(&[(1, 0.0, 200.0), (2, 0.0, 0.0), (3, 0.0, 20.0)]);
+        let cache = sized_cache(&[(1, 50.0, 50.0), (2, 50.0, 50.0), (3, 50.0, 50.0)]);
+        GraphLogic::distribute_selected(&model, &select(&[1, 2, 3]), &cache, Axis::Vertical);
+        assert_eq!(position_of(&model, 2), (0.0, 100.0));
+    }
+
+    #[test]
+    fn test_distribute_needs_three_nodes() {
+        let model = positioned_model(&[(1, 0.0, 0.0), (2, 40.0, 0.0)]);
+        let cache = sized_cache(&[(1, 10.0, 10.0), (2, 10.0, 10.0)]);
+        GraphLogic::distribute_selected(&model, &select(&[1, 2]), &cache, Axis::Horizontal);
+        assert_eq!(position_of(&model, 1), (40.0, 0.0));
+    }
+
+    // ========================================================================
+    // GraphLogic::auto_layout_selection() tests
+    // ========================================================================
+
+    #[cfg(feature = "layout")]
+    #[test]
+    fn test_auto_layout_selection_only_returns_selected_nodes() {
+        let model = positioned_model(&[
+            (1, 0.0, 0.0),
+            (2, 500.0, 0.0),
+            (3, 0.0, 500.0),
+            (4, 900.0, 900.0),
+        ]);
+        let edges = [(1, 2), (2, 3), (3, 4)];
+        let positions = GraphLogic::auto_layout_selection(
+            &model,
+            &select(&[1, 2, 3, 99]),
+            &edges,
+            |_| (100.0, 50.0),
+            &crate::layout::SugiyamaConfig::default(),
+        );
+
+        let mut ids: Vec<i32> = positions.iter().map(|p| p.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec![1, 2, 3]);
+        // Edges inside the selection still order the layers
+        let y_of = |id: i32| positions.iter().find(|p| p.id == id).unwrap().y;
+        assert!(y_of(1) < y_of(2) && y_of(2) < y_of(3));
+    }
+
+    #[cfg(feature = "layout")]
+    #[test]
+    fn test_auto_layout_selection_keeps_centroid() {
+        let model = positioned_model(&[(1, 1000.0, 2000.0), (2, 1200.0, 2200.0)]);
+        let positions = GraphLogic::auto_layout_selection(
+            &model,
+            &select(&[1, 2]),
+            &[(1, 2)],
+            |_| (100.0, 50.0),
+            &crate::layout::SugiyamaConfig::default(),
+        );
+
+        // Old centers: (1050, 2025) and (1250, 2225)
+        let (sum_x, sum_y) = positions
+            .iter()
+            .fold((0.0, 0.0), |(x, y), p| (x + p.x + 50.0, y + p.y + 25.0));
+        assert!((sum_x / 2.0 - 1150.0).abs() < 1e-6);
+        assert!((sum_y / 2.0 - 2125.0).abs() < 1e-6);
+    }
+
+    #[cfg(feature = "layout")]
+    #[test]
+    fn test_auto_layout_selection_empty_selection() {
+        let model = positioned_model(&[(1, 0.0, 0.0)]);
+        let positions = GraphLogic::auto_layout_selection(
+            &model,
+            &select(&[]),
+            &[],
+            |_| (100.0, 50.0),
+            &crate::layout::SugiyamaConfig::default(),
+        );
+        assert!(positions.is_empty());
+    }
+
+    // ========================================================================
+    // GraphLogic::resolve_overlaps() tests
+    // ========================================================================
+
+    /// Gap between two nodes' rects along their separating axis (negative if overlapping)
+    fn gap_between(a: (f32, f32), b: (f32, f32), size: (f32, f32)) -> f32 {
+        let gap_x = (a.0 - b.0).abs() - size.0;
+        let gap_y = (a.1 - b.1).abs() - size.1;
+        gap_x.max(gap_y)
+    }
+
+    #[test]
+    fn test_resolve_overlaps_separates_coincident_nodes() {
+        let model = positioned_model(&[(1, 100.0, 100.0), (2, 100.0, 100.0)]);
+        let resolved = GraphLogic::resolve_overlaps(&model, |_| (100.0, 50.0), 10, 10.0);
+
+        assert!(resolved);
+        let (a, b) = (position_of(&model, 0), position_of(&model, 1));
+        assert!(gap_between(a, b, (100.0, 50.0)) >= 10.0);
+        // Split along the shallower (vertical) axis, lower ID moves up
+        assert_eq!(a, (100.0, 70.0));
+        assert_eq!(b, (100.0, 130.0));
+    }
+
+    #[test]
+    fn test_resolve_overlaps_converges_for_pile() {
+        let nodes: Vec<(i32, f32, f32)> =
+            (1..=6).map(|id| (id, 10.0 * id as f32, 5.0 * id as f32)).collect();
+        let model = positioned_model(&nodes);
+        assert!(GraphLogic::resolve_overlaps(&model, |_| (80.0, 40.0), 200, 5.0));
+
+        for i in 0..6 {
+            for j in (i + 1)..6 {
+                let (a, b) = (position_of(&model, i), position_of(&model, j));
+                assert!(gap_between(a, b, (80.0, 40.0)) >= 5.0 - 1e-3, "{} vs {}", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_overlaps_is_deterministic_and_leaves_separated_nodes() {
+        let nodes = [(3, 0.0, 0.0), (1, 20.0, 10.0), (2, 500.0, 500.0)];
+        let first = positioned_model(&nodes);
+        let second = positioned_model(&nodes);
+        GraphLogic::resolve_overlaps(&first, |_| (50.0, 50.0), 20, 0.0);
+        GraphLogic::resolve_overlaps(&second, |_| (50.0, 50.0), 20, 0.0);
+
+        for row in 0..3 {
+            assert_eq!(position_of(&first, row), position_of(&second, row));
+        }
+        assert_eq!(position_of(&first, 2), (500.0, 500.0));
+    }
+
+    #[test]
+    fn test_resolve_overlaps_reports_unresolved_when_out_of_iterations() {
+        let model = positioned_model(&[(1, 0.0, 0.0), (2, 0.0, 0.0)]);
+        assert!(!GraphLogic::resolve_overlaps(&model, |_| (10.0, 10.0), 0, 0.0));
+        assert_eq!(position_of(&model, 1), (0.0, 0.0));
+    }
+
+    // ========================================================================
+    // GraphLogic::move_group() / group_bounds() tests
+    // ========================================================================
+
+    #[test]
+    fn test_move_group_moves_only_members() {
+        let model =
+            positioned_model(&[(1, 0.0, 0.0), (2, 100.0, 50.0), (3, 40.0, 200.0), (4, 5.0, 5.0)]);
+        GraphLogic::move_group(&model, &[1, 2, 3, 99], 25.0, -10.0);
+
+        assert_eq!(position_of(&model, 0), (25.0, -10.0));
+        assert_eq!(position_of(&model, 1), (125.0, 40.0));
+        assert_eq!(position_of(&model, 2), (65.0, 190.0));
+        assert_eq!(position_of(&model, 3), (5.0, 5.0));
+    }
+
+    #[test]
+    fn test_group_bounds_encloses_members() {
+        let model =
+            positioned_model(&[(1, 0.0, 0.0), (2, 100.0, 50.0), (3, 40.0, 200.0), (4, 900.0, 900.0)]);
+        let sizes = |id: i32| if id == 2 { (120.0, 60.0) } else { (80.0, 40.0) };
+
+        assert_eq!(GraphLogic::group_bounds(&model, &[1, 2, 3], sizes), (0.0, 0.0, 220.0, 240.0));
+
+        GraphLogic::move_group(&model, &[1, 2, 3], 10.0, 20.0);
+        assert_eq!(GraphLogic::group_bounds(&model, &[1, 2, 3], sizes), (10.0, 20.0, 220.0, 240.0));
+    }
+
+    #[test]
+    fn test_group_bounds_empty_group() {
+        let model = positioned_model(&[(1, 30.0, 30.0)]);
+        assert_eq!(GraphLogic::group_bounds(&model, &[], |_| (10.0, 10.0)), (0.0, 0.0, 0.0, 0.0));
+        assert_eq!(GraphLogic::group_bounds(&model, &[7], |_| (10.0, 10.0)), (0.0, 0.0, 0.0, 0.0));
+    }
+
+    // ========================================================================
+    // compute_alignment_guides() tests
+    // ========================================================================
+
+    #[test]
+    fn test_alignment_guide_snaps_to_left_edge() {
+        // Moving node's left edge is 4 units right of the other node's left edge
+        let guides = compute_alignment_guides(
+            (104.0, 300.0, 80.0, 40.0),
+            [(100.0, 0.0, 120.0, 60.0)].into_iter(),
+            8.0,
+        );
+
+        assert_eq!(
+            guides,
+            vec![Guide { axis: Axis::Vertical, position: 100.0, snapped_delta: -4.0 }]
+        );
+    }
+
+    #[test]
+    fn test_alignment_guides_outside_threshold_are_ignored() {
+        let guides = compute_alignment_guides(
+            (120.0, 300.0, 80.0, 40.0),
+            [(100.0, 0.0, 300.0, 60.0)].into_iter(),
+            8.0,
+        );
+        assert!(guides.is_empty());
+    }
+
+    #[test]
+    fn test_alignment_guides_pick_closest_line_per_axis() {
+        // x: right edge 198 is 2 from the other's center 200 (left edge is 6 away)
+        // y: top 53 is 3 from the other's bottom 50
+        let guides = compute_alignment_guides(
+            (106.0, 53.0, 92.0, 30.0),
+            [(100.0, 0.0, 200.0, 50.0)].into_iter(),
+            8.0,
+        );
+
+        assert_eq!(
+            guides,
+            vec![
+                Guide { axis: Axis::Vertical, position: 200.0, snapped_delta: 2.0 },
+                Guide { axis: Axis::Horizontal, position: 50.0, snapped_delta: -3.0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alignment_guides_report_every_aligned_line() {
+        // Two nodes share left edge 100; after snapping, both the left edge and
+        // the right edge (100 + 50 = 150, another node's left edge) line up
+        let others = [
+            (100.0, 0.0, 200.0, 40.0),
+            (100.0, 400.0, 200.0, 40.0),
+            (150.0, 800.0, 200.0, 40.0),
+        ];
+        let guides = compute_alignment_guides((103.0, 200.0, 50.0, 40.0), others.into_iter(), 5.0);
+        let vertical: Vec<f32> = guides
+            .iter()
+            .filter(|g| g.axis == Axis::Vertical)
+            .map(|g| g.position)
+            .collect();
+
+        assert_eq!(vertical, vec![100.0, 150.0]);
+        assert!(guides.iter().all(|g| g.axis != Axis::Vertical || g.snapped_delta == -3.0));
+    }
+
+    // ========================================================================
+    // GraphLogic::duplicate_selected() tests
+    // ========================================================================
+
+    #[test]
+    fn test_duplicate_selected_appends_offset_copies() {
+        let model = positioned_model(&[(1, 0.0, 0.0), (2, 100.0, 50.0), (3, 200.0, 0.0)]);
+        let mut next_id = 10;
+        let new_ids = GraphLogic::duplicate_selected(
+            &model,
+            &select(&[1, 3]),
+            20.0,
+            30.0,
+            || {
+                next_id += 1;
+                next_id
+            },
+            |node, id| node.id = id,
+        );
+
+        assert_eq!(new_ids, vec![11, 12]);
+        assert_eq!(model.row_count(), 5);
+        assert_eq!(model.row_data(3).unwrap(), PositionedNode { id: 11, x: 20.0, y: 30.0 });
+        assert_eq!(model.row_data(4).unwrap(), PositionedNode { id: 12, x: 220.0, y: 30.0 });
+        // Originals stay where they were
+        assert_eq!(position_of(&model, 0), (0.0, 0.0));
+        assert_eq!(position_of(&model, 2), (200.0, 0.0));
+    }
+
+    #[test]
+    fn test_duplicate_empty_selection_is_noop() {
+        let model = positioned_model(&[(1, 0.0, 0.0)]);
+        let new_ids = GraphLogic::duplicate_selected(
+            &model,
+            &SelectionManager::new(),
+            20.0,
+            20.0,
+            || 99,
+            |node, id| node.id = id,
+        );
+        assert!(new_ids.is_empty());
+        assert_eq!(model.row_count(), 1);
+    }
+
+    // ========================================================================
+    // GraphLogic::split_link() tests
+    // ========================================================================
+
+    fn link_model(links: &[(i32, i32, i32)]) -> VecModel<SimpleLink> {
+        VecModel::from(
+            links
+                .iter()
+                .map(|&(id, start, end)| SimpleLink::new(id, start, end, Color::default()))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn make_simple_link(id: i32, start: i32, end: i32) -> SimpleLink {
+        SimpleLink::new(id, start, end, Color::default())
+    }
+
+    #[test]
+    fn test_split_link_replaces_link_with_two() {
+        let links = link_model(&[(1, 11, 20), (2, 21, 30)]);
+        let mut next_id = 100;
+        let new_ids = GraphLogic::split_link(1, 90, 91, &links, make_simple_link, || {
+            next_id += 1;
+            next_id
+        });
+
+        assert_eq!(new_ids, Some((101, 102)));
+        let rows: Vec<(i32, i32, i32)> = links
+            .iter()
+            .map(|link| (link.id, link.start_pin_id, link.end_pin_id))
+            .collect();
+        assert_eq!(rows, vec![(101, 11, 90), (102, 91, 20), (2, 21, 30)]);
+        assert!(links.iter().all(|link| link.id != 1));
+    }
+
+    #[test]
+    fn test_split_unknown_link_is_noop() {
+        let links = link_model(&[(1, 11, 20)]);
+        let result = GraphLogic::split_link(99, 90, 91, &links, make_simple_link, || 5);
+        assert_eq!(result, None);
+        assert_eq!(links.row_count(), 1);
+        assert_eq!(links.row_data(0).unwrap().id, 1);
+    }
+
+    // ========================================================================
+    // GraphLogic::reconnect_link() tests
+    // ========================================================================
+
+    /// Rebuild a link with its end pin replaced
+    fn with_end(link: &SimpleLink, pin: i32) -> SimpleLink {
+        SimpleLink { end_pin_id: pin, ..link.clone() }
+    }
+
+    /// Rebuild a link with its start pin replaced
+    fn with_start(link: &SimpleLink, pin: i32) -> SimpleLink {
+        SimpleLink { start_pin_id: pin, ..link.clone() }
+    }
+
+    fn link_pins(links: &VecModel<SimpleLink>) -> Vec<(i32, i32, i32)> {
+        links
+            .iter()
+            .map(|link| (link.id, link.start_pin_id, link.end_pin_id))
+            .collect()
+    }
+
+    #[test]
+    fn test_reconnect_link_to_valid_pin_updates_link() {
+        let cache = chain_cache(&[1, 2, 3]);
+        let links = link_model(&[(1, 11, 20), (2, 21, 30)]);
+        let validator = BasicLinkValidator::new(2);
+
+        let result =
+            GraphLogic::reconnect_link(1, LinkEnd::End, 30, &links, with_end, &validator, &cache);
+        assert_eq!(result, Ok(()));
+        assert_eq!(link_pins(&links), vec![(1, 11, 30), (2, 21, 30)]);
+
+        let result = GraphLogic::reconnect_link(
+            2,
+            LinkEnd::Start,
+            11,
+            &links,
+            with_start,
+            &validator,
+            &cache,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(link_pins(&links), vec![(1, 11, 30), (2, 11, 30)]);
+    }
+
+    #[test]
+    fn test_reconnect_link_to_incompatible_pin_is_rejected() {
+        let cache = chain_cache(&[1, 2, 3]);
+        let links = link_model(&[(1, 11, 20)]);
+        let validator = BasicLinkValidator::new(2);
+
+        // Pin 31 is an output, so output -> output is rejected
+        let result =
+            GraphLogic::reconnect_link(1, LinkEnd::End, 31, &links, with_end, &validator, &cache);
+        assert_eq!(result, Err(ValidationError::IncompatibleDirection));
+        assert_eq!(link_pins(&links), vec![(1, 11, 20)]);
+    }
+
+    #[test]
+    fn test_reconnect_link_ignores_itself_for_duplicates() {
+        let cache = chain_cache(&[1, 2, 3]);
+        let links = link_model(&[(1, 11, 20), (2, 11, 30)]);
+        let validator = NoDuplicatesValidator;
+
+        // Dropping the end back on its own pin is not a duplicate of itself
+        let result =
+            GraphLogic::reconnect_link(1, LinkEnd::End, 20, &links, with_end, &validator, &cache);
+        assert_eq!(result, Ok(()));
+
+        let result =
+            GraphLogic::reconnect_link(1, LinkEnd::End, 30, &links, with_end, &validator, &cache);
+        assert_eq!(result, Err(ValidationError::DuplicateLink));
+        assert_eq!(link_pins(&links), vec![(1, 11, 20), (2, 11, 30)]);
+    }
+
+    #[test]
+    fn test_reconnect_unknown_link_is_rejected() {
+        let cache = chain_cache(&[1, 2]);
+        let links = link_model(&[(1, 11, 20)]);
+        let result = GraphLogic::reconnect_link(
+            9,
+            LinkEnd::End,
+            20,
+            &links,
+            with_end,
+            &BasicLinkValidator::new(2),
+            &cache,
+        );
+        assert_eq!(result, Err(ValidationError::LinkNotFound(9)));
+    }
+
+    // ========================================================================
+    // GraphLogic::topological_order() tests
+    // ========================================================================
+
+    /// Cache where node `n` has an input pin `n * 10` and an output pin `n * 10 + 1`
+    fn chain_cache(node_ids: &[i32]) -> GeometryCache<SimpleNodeGeometry> {
+        let mut cache = GeometryCache::new();
+        for &id in node_ids {
+            cache.update_node_rect(id, id as f32 * 150.0, 0.0, 100.0, 50.0);
+            cache.handle_pin_report(id * 10, id, 1, 0.0, 25.0);
+            cache.handle_pin_report(id * 10 + 1, id, 2, 100.0, 25.0);
+        }
+        cache
+    }
+
+    /// Link from `from` node's output to `to` node's input
+    fn node_link(id: i32, from: i32, to: i32) -> TestLink {
+        TestLink {
+            id,
+            start: from * 10 + 1,
+            end: to * 10,
+        }
+    }
+
+    #[test]
+    fn test_topological_order_dag() {
+        let cache = chain_cache(&[1, 2, 3, 4]);
+        // 3 -> 1 -> 2, 3 -> 4 -> 2
+        let links = vec![
+            node_link(1, 3, 1),
+            node_link(2, 1, 2),
+            node_link(3, 3, 4),
+            node_link(4, 4, 2),
+        ];
+
+        let order = GraphLogic::topological_order(links, &cache).unwrap();
+        assert_eq!(order, vec![3, 1, 4, 2]);
+    }
+
+    #[test]
+    fn test_topological_order_parallel_links_count_once() {
+        let mut cache = chain_cache(&[1, 2]);
+        cache.handle_pin_report(12, 1, 2, 100.0, 40.0);
+        let links = vec![
+            node_link(1, 1, 2),
+            TestLink { id: 2, start: 12, end: 20 },
+        ];
+
+        let order = GraphLogic::topological_order(links, &cache).unwrap();
+        assert_eq!(order, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_topological_order_two_cycle() {
+        let cache = chain_cache(&[1, 2, 3, 4]);
+        // 4 -> 1 <-> 2 -> 3: only 1 and 2 are on the cycle
+        let links = vec![
+            node_link(1, 4, 1),
+            node_link(2, 1, 2),
+            node_link(3, 2, 1),
+            node_link(4, 2, 3),
+        ];
+
+        let cycle = GraphLogic::topological_order(links, &cache).unwrap_err();
+        assert_eq!(cycle, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_topological_order_self_loop_is_cycle() {
+        let cache = chain_cache(&[1, 2]);
+        let links = vec![node_link(1, 1, 1)];
+
+        let cycle = GraphLogic::topological_order(links, &cache).unwrap_err();
+        assert_eq!(cycle, vec![1]);
+    }
+
+    #[test]
+    fn test_topological_order_disconnected_graph() {
+        let cache = chain_cache(&[1, 2, 3, 4, 5]);
+        // Two components (2 -> 1, 5 -> 4) plus isolated node 3
+        let links = vec![node_link(1, 2, 1), node_link(2, 5, 4)];
+
+        let order = GraphLogic::topological_order(links, &cache).unwrap();
+        assert_eq!(order.len(), 5);
+        let pos = |id: i32| order.iter().position(|&n| n == id).unwrap();
+        assert!(pos(2) < pos(1));
+        assert!(pos(5) < pos(4));
+        assert!(order.contains(&3));
+    }
+
+    #[test]
+    fn test_topological_order_ignores_unresolved_pins() {
+        let cache = chain_cache(&[1, 2]);
+        let links = vec![node_link(1, 1, 2), TestLink { id: 2, start: 999, end: 10 }];
+
+        let order = GraphLogic::topological_order(links, &cache).unwrap();
+        assert_eq!(order, vec![1, 2]);
+    }
+
+    // ========================================================================
+    // GraphLogic::descendants() / ancestors() / connected_component() tests
+    // ========================================================================
+
+    /// Diamond 1 -> {2, 3} -> 4, plus 4 -> 5 and isolated node 6
+    fn diamond() -> (GeometryCache<SimpleNodeGeometry>, Vec<TestLink>) {
+        let cache = chain_cache(&[1, 2, 3, 4, 5, 6]);
+        let links = vec![
+            node_link(1, 1, 2),
+            node_link(2, 1, 3),
+            node_link(3, 2, 4),
+            node_link(4, 3, 4),
+            node_link(5, 4, 5),
+        ];
+        (cache, links)
+    }
+
+    #[test]
+    fn test_descendants_of_diamond_root() {
+        let (cache, links) = diamond();
+        let down = GraphLogic::descendants(1, links, &cache);
+        assert_eq!(down, HashSet::from([2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_descendants_of_leaf_is_empty() {
+        let (cache, links) = diamond();
+        assert!(GraphLogic::descendants(5, links, &cache).is_empty());
+    }
+
+    #[test]
+    fn test_ancestors_of_diamond_sink() {
+        let (cache, links) = diamond();
+        let up = GraphLogic::ancestors(4, links, &cache);
+        assert_eq!(up, HashSet::from([1, 2, 3]));
+    }
+
+    #[test]
+    fn test_connected_component_ignores_direction() {
+        let (cache, links) = diamond();
+        let component = GraphLogic::connected_component(3, links, &cache);
+        assert_eq!(component, HashSet::from([1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_connected_component_isolated_node() {
+        let (cache, links) = diamond();
+        let component = GraphLogic::connected_component(6, links, &cache);
+        assert_eq!(component, HashSet::from([6]));
+    }
+
+    #[test]
+    fn test_descendants_excludes_start_on_cycle() {
+        let cache = chain_cache(&[1, 2]);
+        let links = vec![node_link(1, 1, 2), node_link(2, 2, 1)];
+        assert_eq!(GraphLogic::descendants(1, links, &cache), HashSet::from([2]));
+    }
+
+    // ========================================================================
+    // GraphLogic::reroute_links_to_group() tests
+    // ========================================================================
+
+    #[test]
+    fn test_reroute_links_to_group_boundary_pins() {
+        // Group node 9 collapses members 2 and 3; nodes 1 and 5 stay outside
+        let cache = chain_cache(&[1, 2, 3, 5, 9]);
+        let links = vec![
+            node_link(1, 1, 2),
+            node_link(2, 2, 3),
+            node_link(3, 3, 5),
+            node_link(4, 1, 5),
+        ];
+
+        let reroutes = GraphLogic::reroute_links_to_group(9, &[2, 3], links, &cache);
+        // External link into member 2's input goes to the group's input,
+        // member 3's output link leaves from the group's output
+        assert_eq!(reroutes, vec![(1, 90), (3, 91)]);
+    }
+
+    #[test]
+    fn test_reroute_links_to_group_without_matching_pin() {
+        let mut cache = chain_cache(&[1, 2, 9]);
+        cache.remove_pin(90);
+        let links = vec![node_link(1, 1, 2)];
+        let reroutes = GraphLogic::reroute_links_to_group(9, &[2], links, &cache);
+        assert!(reroutes.is_empty());
+    }
+
+    #[test]
+    fn test_reroute_links_to_group_sees_directly_inserted_pins() {
+        let mut cache = chain_cache(&[1, 2]);
+        cache.update_node_rect(9, 0.0, 0.0, 100.0, 50.0);
+        cache.pin_positions.insert(
+            95,
+            StoredPin { node_id: 9, pin_type: 1, rel_x: 0.0, rel_y: 25.0 },
+        );
+        let links = vec![node_link(1, 1, 2)];
+        let reroutes = GraphLogic::reroute_links_to_group(9, &[2], links, &cache);
+        assert_eq!(reroutes, vec![(1, 95)]);
+    }
+
+    // ========================================================================
+    // AsyncLinkValidator
+    // ========================================================================
+
+    type QueuedCallback = Box<dyn FnOnce(ValidationResult)>;
+
+    /// Deferred validator that queues its callbacks until the test resolves them
+    #[derive(Default)]
+    struct QueuedValidator {
+        queue: RefCell<Vec<QueuedCallback>>,
+    }
+
+    impl QueuedValidator {
+        fn resolve_all(&self, result: ValidationResult) {
+            let queued: Vec<_> = self.queue.borrow_mut().drain(..).collect();
+            for on_result in queued {
+                on_result(result.clone());
+            }
+        }
+    }
+
+    impl AsyncLinkValidator<SimpleNodeGeometry, SimpleLink> for QueuedValidator {
+        fn validate_deferred<F>(
+            &self,
+            _start_pin: i32,
+            _end_pin: i32,
+            _cache: &GeometryCache<SimpleNodeGeometry>,
+            _links: &[SimpleLink],
+            on_result: F,
+        ) where
+            F: FnOnce(ValidationResult) + 'static,
+        {
+            self.queue.borrow_mut().push(Box::new(on_result));
+        }
+    }
+
+    #[test]
+    fn test_immediate_validator_resolves_before_returning() {
+        let cache = setup_cache();
+        let validator = ImmediateValidator(BasicLinkValidator::new(2));
+        let verdict = Rc::new(RefCell::new(None));
+
+        let sink = verdict.clone();
+        AsyncLinkValidator::<_, SimpleLink>::validate_deferred(
+            &validator,
+            1001,
+            2001,
+            &cache,
+            &[],
+            move |result| *sink.borrow_mut() = Some(result),
+        );
+        assert_eq!(*verdict.borrow(), Some(ValidationResult::Valid));
+
+        let sink = verdict.clone();
+        AsyncLinkValidator::<_, SimpleLink>::validate_deferred(
+            &validator,
+            1001,
+            1001,
+            &cache,
+            &[],
+            move |result| *sink.borrow_mut() = Some(result),
+        );
+        assert_eq!(
+            *verdict.borrow(),
+            Some(ValidationResult::Invalid(ValidationError::SamePin))
+        );
+    }
+
+    #[test]
+    fn test_queued_validator_resolves_later() {
+        let cache = setup_cache();
+        let validator = QueuedValidator::default();
+        let verdict = Rc::new(RefCell::new(None));
+
+        let sink = verdict.clone();
+        validator.validate_deferred(1001, 2001, &cache, &[], move |result| {
+            *sink.borrow_mut() = Some(result)
+        });
+        assert!(verdict.borrow().is_none());
+
+        validator.resolve_all(ValidationResult::Invalid(ValidationError::Custom("backend".into())));
+        assert_eq!(
+            *verdict.borrow(),
+            Some(ValidationResult::Invalid(ValidationError::Custom("backend".into())))
+        );
+    }
 }
@@ -20,41 +20,252 @@ pub fn generate_grid_commands(
     pan_x: f32,
     pan_y: f32,
     spacing: f32,
+) -> String {
+    generate_grid_commands_xy(width, height, zoom, pan_x, pan_y, spacing, spacing)
+}
+
+/// Generate SVG path commands for a grid with rectangular cells
+///
+/// Same as [`generate_grid_commands`], except that vertical lines are
+/// `spacing_x` apart and horizontal lines `spacing_y` apart (both before
+/// zoom), e.g. for wide columns and short rows. Each direction is skipped
+/// on its own once its zoomed spacing is too small to be visible.
+pub fn generate_grid_commands_xy(
+    width: f32,
+    height: f32,
+    zoom: f32,
+    pan_x: f32,
+    pan_y: f32,
+    spacing_x: f32,
+    spacing_y: f32,
+) -> String {
+    let effective_x = spacing_x * zoom;
+    let effective_y = spacing_y * zoom;
+
+    let mut commands = String::with_capacity(10000);
+
+    // Generate vertical lines, unless spacing is too small to be visible.
+    // The offset is pan modulo spacing for an infinite grid effect.
+    if effective_x >= 4.0 {
+        let mut x = pan_x.rem_euclid(effective_x);
+        while x < width + effective_x {
+            if !commands.is_empty() {
+                commands.push(' ');
+            }
+            commands.push_str(&format!("M {} 0 L {} {}", x, x, height));
+            x += effective_x;
+        }
+    }
+
+    // Generate horizontal lines
+    if effective_y >= 4.0 {
+        let mut y = pan_y.rem_euclid(effective_y);
+        while y < height + effective_y {
+            if !commands.is_empty() {
+                commands.push(' ');
+            }
+            commands.push_str(&format!("M 0 {} L {} {}", y, width, y));
+            y += effective_y;
+        }
+    }
+
+    commands
+}
+
+/// Grid lines plus the world axes, from [`generate_grid_with_axes`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GridWithAxes {
+    /// Regular grid lines, as from [`generate_grid_commands`]
+    pub grid: String,
+    /// The world x=0 and y=0 lines that fall inside the canvas (possibly empty)
+    pub axes: String,
+}
+
+/// Generate grid lines with the world origin axes as a separate path
+///
+/// Same arguments as [`generate_grid_commands`]. The world `x = 0` axis is a
+/// vertical line at screen `pan_x` and the `y = 0` axis a horizontal line at
+/// screen `pan_y`; each is only emitted while it is inside the canvas, so
+/// `axes` is empty once the origin is panned well off-screen. Stroke `axes`
+/// with an emphasized style so users keep track of the origin.
+pub fn generate_grid_with_axes(
+    width: f32,
+    height: f32,
+    zoom: f32,
+    pan_x: f32,
+    pan_y: f32,
+    spacing: f32,
+) -> GridWithAxes {
+    let mut axes = String::new();
+    if (0.0..=width).contains(&pan_x) {
+        axes.push_str(&format!("M {} 0 L {} {}", pan_x, pan_x, height));
+    }
+    if (0.0..=height).contains(&pan_y) {
+        if !axes.is_empty() {
+            axes.push(' ');
+        }
+        axes.push_str(&format!("M 0 {} L {} {}", pan_y, width, pan_y));
+    }
+
+    GridWithAxes {
+        grid: generate_grid_commands(width, height, zoom, pan_x, pan_y, spacing),
+        axes,
+    }
+}
+
+/// Generate SVG path commands for a dot grid
+///
+/// Alternative to [`generate_grid_commands`] that draws a small filled circle
+/// at each grid intersection instead of full lines. Only dots inside the
+/// `width` x `height` canvas are emitted. Fill the path rather than stroking it.
+///
+/// # Arguments
+/// * `width` - Canvas width in pixels
+/// * `height` - Canvas height in pixels
+/// * `zoom` - Current zoom level
+/// * `pan_x` - Pan offset X in pixels
+/// * `pan_y` - Pan offset Y in pixels
+/// * `spacing` - Base grid spacing (before zoom)
+/// * `dot_radius` - Dot radius in pixels (not scaled by zoom)
+///
+/// # Returns
+/// SVG path commands with two arcs per dot, or an empty string when the
+/// spacing is too small to be visible (same threshold as the line grid)
+pub fn generate_dot_grid_commands(
+    width: f32,
+    height: f32,
+    zoom: f32,
+    pan_x: f32,
+    pan_y: f32,
+    spacing: f32,
+    dot_radius: f32,
 ) -> String {
     let effective_spacing = spacing * zoom;
 
     // Skip if spacing is too small to be visible
-    if effective_spacing < 4.0 {
+    if effective_spacing < 4.0 || dot_radius <= 0.0 {
         return String::new();
     }
 
-    // Calculate grid offset based on pan (modulo spacing for infinite grid effect)
     let offset_x = pan_x.rem_euclid(effective_spacing);
     let offset_y = pan_y.rem_euclid(effective_spacing);
+    let r = dot_radius;
 
-    let mut commands = String::with_capacity(10000);
-
-    // Generate vertical lines
-    let mut x = offset_x;
-    while x < width + effective_spacing {
-        if !commands.is_empty() {
-            commands.push(' ');
-        }
-        commands.push_str(&format!("M {} 0 L {} {}", x, x, height));
-        x += effective_spacing;
-    }
-
-    // Generate horizontal lines
+    let mut commands = String::new();
     let mut y = offset_y;
-    while y < height + effective_spacing {
-        commands.push(' ');
-        commands.push_str(&format!("M 0 {} L {} {}", y, width, y));
+    while y <= height {
+        let mut x = offset_x;
+        while x <= width {
+            if !commands.is_empty() {
+                commands.push(' ');
+            }
+            commands.push_str(&format!(
+                "M {} {} A {} {} 0 1 0 {} {} A {} {} 0 1 0 {} {} Z",
+                x - r, y, r, r, x + r, y, r, r, x - r, y
+            ));
+            x += effective_spacing;
+        }
         y += effective_spacing;
     }
 
     commands
 }
 
+/// Smallest on-screen line spacing, in pixels, chosen by [`generate_adaptive_grid_commands`].
+/// The chosen spacing always lies in `[ADAPTIVE_GRID_MIN_PIXELS, 2 * ADAPTIVE_GRID_MIN_PIXELS)`.
+pub const ADAPTIVE_GRID_MIN_PIXELS: f32 = 16.0;
+
+/// Every n-th adaptive grid line is a major line.
+pub const ADAPTIVE_GRID_MAJOR_EVERY: i64 = 4;
+
+/// Grid lines from [`generate_adaptive_grid_commands`], split for two-tone rendering
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdaptiveGrid {
+    /// SVG path commands for the minor lines
+    pub minor: String,
+    /// SVG path commands for every [`ADAPTIVE_GRID_MAJOR_EVERY`]-th line
+    pub major: String,
+    /// World-space distance between neighbouring lines (a power-of-two multiple of the base spacing)
+    pub world_spacing: f32,
+}
+
+/// Generate grid lines whose density adapts to the zoom level
+///
+/// Picks `base_spacing * 2^k` (k may be negative) so lines are between
+/// [`ADAPTIVE_GRID_MIN_PIXELS`] and twice that apart on screen. Zooming out
+/// therefore doubles the world spacing instead of packing lines into a solid
+/// fill, and zooming in halves it. Lines on multiples of
+/// [`ADAPTIVE_GRID_MAJOR_EVERY`] world steps go into `major`, the rest into
+/// `minor`, so they can be stroked differently.
+///
+/// # Arguments
+/// * `width` - Canvas width in pixels
+/// * `height` - Canvas height in pixels
+/// * `zoom` - Current zoom level
+/// * `pan_x` - Pan offset X in pixels
+/// * `pan_y` - Pan offset Y in pixels
+/// * `base_spacing` - Grid spacing at zoom 1.0 (before adaptation)
+///
+/// Returns an empty grid for a non-positive `zoom` or `base_spacing`.
+pub fn generate_adaptive_grid_commands(
+    width: f32,
+    height: f32,
+    zoom: f32,
+    pan_x: f32,
+    pan_y: f32,
+    base_spacing: f32,
+) -> AdaptiveGrid {
+    if zoom <= 0.0 || base_spacing <= 0.0 || !zoom.is_finite() || !base_spacing.is_finite() {
+        return AdaptiveGrid::default();
+    }
+
+    let mut world_spacing = base_spacing;
+    while world_spacing * zoom < ADAPTIVE_GRID_MIN_PIXELS && world_spacing.is_finite() {
+        world_spacing *= 2.0;
+    }
+    while world_spacing * zoom >= 2.0 * ADAPTIVE_GRID_MIN_PIXELS && world_spacing.is_finite() {
+        world_spacing /= 2.0;
+    }
+    let effective_spacing = world_spacing * zoom;
+    // A zoom so extreme that the spacing over- or underflows has no drawable grid
+    if !world_spacing.is_finite() || !effective_spacing.is_finite() || effective_spacing <= 0.0 {
+        return AdaptiveGrid::default();
+    }
+
+    let mut grid = AdaptiveGrid {
+        world_spacing,
+        ..AdaptiveGrid::default()
+    };
+
+    // Vertical lines, then horizontal, each indexed by world grid step so
+    // major lines stay fixed in world space while panning
+    for vertical in [true, false] {
+        let (pan, extent, length) = if vertical { (pan_x, width, height) } else { (pan_y, height, width) };
+        let offset = pan.rem_euclid(effective_spacing);
+        let mut index = ((offset - pan) / effective_spacing).round() as i64;
+        let mut pos = offset;
+        while pos < extent + effective_spacing {
+            let target = if index.rem_euclid(ADAPTIVE_GRID_MAJOR_EVERY) == 0 {
+                &mut grid.major
+            } else {
+                &mut grid.minor
+            };
+            if !target.is_empty() {
+                target.push(' ');
+            }
+            if vertical {
+                target.push_str(&format!("M {} 0 L {} {}", pos, pos, length));
+            } else {
+                target.push_str(&format!("M 0 {} L {} {}", pos, length, pos));
+            }
+            pos += effective_spacing;
+            index += 1;
+        }
+    }
+
+    grid
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,6 +274,18 @@ mod tests {
     // Basic Grid Generation
     // ========================================================================
 
+    /// Count `(vertical, horizontal)` lines in grid commands
+    fn count_lines(commands: &str) -> (usize, usize) {
+        let lines: Vec<Vec<&str>> = commands
+            .split('M')
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        // "M x 0 L x h" is vertical: same x at both ends
+        let vertical = lines.iter().filter(|tokens| tokens[0] == tokens[3]).count();
+        (vertical, lines.len() - vertical)
+    }
+
     #[test]
     fn test_grid_commands() {
         let commands = generate_grid_commands(100.0, 100.0, 1.0, 0.0, 0.0, 24.0);
@@ -79,6 +302,31 @@ mod tests {
         assert!(commands.contains("L 100")); // Horizontal line to right edge
     }
 
+    #[test]
+    fn test_grid_commands_xy_independent_spacing() {
+        let commands = generate_grid_commands_xy(200.0, 200.0, 1.0, 0.0, 0.0, 100.0, 20.0);
+        // Lines run up to one spacing past the edge: x = 0..=200, y = 0..=200
+        assert_eq!(count_lines(&commands), (3, 11));
+        assert!(commands.contains("M 100 0 L 100 200"));
+        assert!(commands.contains("M 0 20 L 200 20"));
+        assert!(!commands.contains("M 20 0"));
+    }
+
+    #[test]
+    fn test_grid_commands_xy_square_matches_wrapper() {
+        assert_eq!(
+            generate_grid_commands_xy(300.0, 150.0, 1.5, 7.0, -3.0, 24.0, 24.0),
+            generate_grid_commands(300.0, 150.0, 1.5, 7.0, -3.0, 24.0)
+        );
+    }
+
+    #[test]
+    fn test_grid_commands_xy_skips_dense_direction_only() {
+        let commands = generate_grid_commands_xy(100.0, 100.0, 1.0, 0.0, 0.0, 2.0, 50.0);
+        assert_eq!(count_lines(&commands), (0, 3));
+        assert!(commands.starts_with("M 0 0 L 100 0"));
+    }
+
     #[test]
     fn test_grid_contains_horizontal_lines() {
         let commands = generate_grid_commands(100.0, 100.0, 1.0, 0.0, 0.0, 25.0);
@@ -228,4 +476,147 @@ mod tests {
         let commands = generate_grid_commands(100.0, 100.0, 1.0, 0.0, 0.0, 25.0);
         assert!(!commands.ends_with(' '));
     }
+
+    // ========================================================================
+    // Axis Highlighting
+    // ========================================================================
+
+    #[test]
+    fn test_grid_with_axes_origin_visible() {
+        let result = generate_grid_with_axes(200.0, 100.0, 1.0, 50.0, 30.0, 20.0);
+        assert_eq!(result.axes, "M 50 0 L 50 100 M 0 30 L 200 30");
+        assert_eq!(result.axes.matches("M ").count(), 2);
+        assert_eq!(result.grid, generate_grid_commands(200.0, 100.0, 1.0, 50.0, 30.0, 20.0));
+    }
+
+    #[test]
+    fn test_grid_with_axes_origin_far_away() {
+        let result = generate_grid_with_axes(200.0, 100.0, 1.0, -5000.0, 8000.0, 20.0);
+        assert!(result.axes.is_empty());
+        assert!(!result.grid.is_empty());
+    }
+
+    #[test]
+    fn test_grid_with_axes_single_axis_visible() {
+        // Origin above the canvas: only the vertical (x = 0) axis crosses it
+        let result = generate_grid_with_axes(200.0, 100.0, 1.0, 80.0, -40.0, 20.0);
+        assert_eq!(result.axes, "M 80 0 L 80 100");
+    }
+
+    // ========================================================================
+    // Dot Grid
+    // ========================================================================
+
+    #[test]
+    fn test_dot_grid_count_matches_viewport() {
+        // Dots at 0, 25, 50, 75, 100 on both axes
+        let commands = generate_dot_grid_commands(100.0, 100.0, 1.0, 0.0, 0.0, 25.0, 1.5);
+        assert_eq!(commands.matches("M ").count(), 25);
+
+        // 0, 25, ..., 200 horizontally (9) by 0, 25, 50 vertically (3)
+        let commands = generate_dot_grid_commands(200.0, 60.0, 1.0, 0.0, 0.0, 25.0, 1.5);
+        assert_eq!(commands.matches("M ").count(), 27);
+    }
+
+    #[test]
+    fn test_dot_grid_only_visible_dots() {
+        // Pan 10: dots at 10, 30, ..., 90 -> 5 per axis
+        let commands = generate_dot_grid_commands(100.0, 100.0, 1.0, 10.0, 10.0, 20.0, 1.0);
+        assert_eq!(commands.matches("M ").count(), 25);
+        assert!(commands.starts_with("M 9 10 A 1 1 0 1 0 11 10"));
+    }
+
+    #[test]
+    fn test_dot_grid_zoom_scales_spacing_not_radius() {
+        let commands = generate_dot_grid_commands(100.0, 100.0, 2.0, 0.0, 0.0, 25.0, 2.0);
+        // Effective spacing 50: dots at 0, 50, 100
+        assert_eq!(commands.matches("M ").count(), 9);
+        assert!(commands.contains("M 48 50 A 2 2 0 1 0 52 50"));
+    }
+
+    #[test]
+    fn test_dot_grid_too_dense_is_empty() {
+        assert!(generate_dot_grid_commands(100.0, 100.0, 0.1, 0.0, 0.0, 20.0, 1.0).is_empty());
+        assert!(generate_dot_grid_commands(100.0, 100.0, 1.0, 0.0, 0.0, 20.0, 0.0).is_empty());
+    }
+
+    // ========================================================================
+    // Adaptive Grid
+    // ========================================================================
+
+    #[test]
+    fn test_adaptive_grid_keeps_base_spacing_in_range() {
+        let grid = generate_adaptive_grid_commands(100.0, 100.0, 1.0, 0.0, 0.0, 24.0);
+        assert_eq!(grid.world_spacing, 24.0);
+    }
+
+    #[test]
+    fn test_adaptive_grid_zoom_out_doubles_world_spacing() {
+        let spacing = |zoom| generate_adaptive_grid_commands(800.0, 600.0, zoom, 0.0, 0.0, 24.0).world_spacing;
+        assert_eq!(spacing(0.5), 48.0);
+        assert_eq!(spacing(0.25), 96.0);
+        assert_eq!(spacing(0.1), 192.0);
+
+        let mut previous = spacing(1.0);
+        for zoom in [0.6, 0.3, 0.15, 0.05] {
+            let current = spacing(zoom);
+            let ratio = current / previous;
+            assert!(ratio >= 1.0 && (ratio.log2() - ratio.log2().round()).abs() < 1e-6);
+            previous = current;
+        }
+    }
+
+    #[test]
+    fn test_adaptive_grid_zoom_in_halves_world_spacing() {
+        let grid = generate_adaptive_grid_commands(100.0, 100.0, 2.0, 0.0, 0.0, 24.0);
+        assert_eq!(grid.world_spacing, 12.0);
+    }
+
+    #[test]
+    fn test_adaptive_grid_on_screen_spacing_stays_in_range() {
+        for step in 1..200 {
+            let zoom = step as f32 * 0.025;
+            let grid = generate_adaptive_grid_commands(100.0, 100.0, zoom, 0.0, 0.0, 24.0);
+            let pixels = grid.world_spacing * zoom;
+            assert!((ADAPTIVE_GRID_MIN_PIXELS..2.0 * ADAPTIVE_GRID_MIN_PIXELS).contains(&pixels));
+        }
+    }
+
+    #[test]
+    fn test_adaptive_grid_splits_major_and_minor() {
+        let grid = generate_adaptive_grid_commands(100.0, 100.0, 1.0, 0.0, 0.0, 24.0);
+        assert!(grid.major.contains("M 0 0 L 0 100"));
+        assert!(grid.major.contains("M 96 0 L 96 100"));
+        assert!(grid.minor.contains("M 24 0 L 24 100"));
+        assert!(grid.minor.contains("M 0 72 L 100 72"));
+        assert!(!grid.minor.contains("M 0 0 L 0 100"));
+        assert!(!grid.major.contains("M 24 0 L 24 100"));
+    }
+
+    #[test]
+    fn test_adaptive_grid_major_lines_follow_pan() {
+        // Panning by one minor step shifts which screen line is major
+        let grid = generate_adaptive_grid_commands(100.0, 100.0, 1.0, 24.0, 0.0, 24.0);
+        assert!(grid.major.contains("M 24 0 L 24 100"));
+        assert!(grid.minor.contains("M 0 0 L 0 100"));
+    }
+
+    #[test]
+    fn test_adaptive_grid_invalid_input_is_empty() {
+        assert_eq!(generate_adaptive_grid_commands(100.0, 100.0, 0.0, 0.0, 0.0, 24.0), AdaptiveGrid::default());
+        assert_eq!(generate_adaptive_grid_commands(100.0, 100.0, 1.0, 0.0, 0.0, 0.0), AdaptiveGrid::default());
+    }
+
+    #[test]
+    fn test_adaptive_grid_extreme_zoom_terminates() {
+        // The doubled world spacing overflows to infinity before reaching the
+        // minimum on-screen spacing; this used to loop forever
+        for zoom in [1e-39, f32::MIN_POSITIVE] {
+            let grid = generate_adaptive_grid_commands(100.0, 100.0, zoom, 0.0, 0.0, 24.0);
+            assert_eq!(grid, AdaptiveGrid::default());
+        }
+        // Huge zooms still produce a grid
+        let grid = generate_adaptive_grid_commands(100.0, 100.0, 1e30, 0.0, 0.0, 24.0);
+        assert!(!grid.minor.is_empty() || !grid.major.is_empty());
+    }
 }
@@ -1,16 +1,70 @@
-use crate::path::{distance_to_bezier, CubicBezier};
+use crate::path::{
+    closest_t_on_bezier, distance_to_line_segment_sq, distance_to_orthogonal_path, CubicBezier,
+    LinkShape, OrthogonalPath, WaypointPath, DEFAULT_BEZIER_MIN_OFFSET,
+};
+
+pub mod spatial_index;
+
+pub use spatial_index::NodeSpatialIndex;
 
 /// Trait for link geometry data needed for hit-testing
 pub trait LinkGeometry {
     fn id(&self) -> i32;
     fn start(&self) -> (f32, f32);
     fn end(&self) -> (f32, f32);
+    /// Points the link is routed through, in the same space as `start`/`end`
+    /// (default: none, a plain bezier)
+    fn waypoints(&self) -> &[(f32, f32)] {
+        &[]
+    }
+    /// Pin sides and bias of the drawn curve (default: a plain horizontal bezier)
+    fn shape(&self) -> LinkShape {
+        LinkShape::default()
+    }
+}
+
+/// Node edge a pin sits on, which decides the direction links leave it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PinSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// No fixed edge; links use the default horizontal curve
+    #[default]
+    Auto,
+}
+
+impl PinSide {
+    /// Unit vector pointing away from the node, or `None` for [`PinSide::Auto`]
+    pub fn direction(self) -> Option<(f32, f32)> {
+        match self {
+            PinSide::Left => Some((-1.0, 0.0)),
+            PinSide::Right => Some((1.0, 0.0)),
+            PinSide::Top => Some((0.0, -1.0)),
+            PinSide::Bottom => Some((0.0, 1.0)),
+            PinSide::Auto => None,
+        }
+    }
 }
 
 /// Trait for pin geometry data needed for hit-testing
 pub trait PinGeometry {
     fn id(&self) -> i32;
     fn position(&self) -> (f32, f32);
+
+    /// Node edge the pin sits on (default: [`PinSide::Auto`])
+    fn side(&self) -> PinSide {
+        PinSide::Auto
+    }
+
+    /// Hit radius for this pin, e.g. a larger touch target than the visual pin
+    ///
+    /// `None` (the default) uses the radius passed to [`find_pin_at`].
+    fn hit_radius(&self) -> Option<f32> {
+        None
+    }
 }
 
 /// Trait for node geometry data needed for selection
@@ -37,6 +91,25 @@ impl LinkGeometry for SimpleLinkGeometry {
     fn end(&self) -> (f32, f32) { (self.end_x, self.end_y) }
 }
 
+/// Link geometry that also carries the [`LinkShape`] of the drawn curve, as
+/// built by the cache from [`GeometryCache::link_shape`](crate::GeometryCache::link_shape)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ShapedLinkGeometry {
+    pub id: i32,
+    pub start_x: f32,
+    pub start_y: f32,
+    pub end_x: f32,
+    pub end_y: f32,
+    pub shape: LinkShape,
+}
+
+impl LinkGeometry for ShapedLinkGeometry {
+    fn id(&self) -> i32 { self.id }
+    fn start(&self) -> (f32, f32) { (self.start_x, self.start_y) }
+    fn end(&self) -> (f32, f32) { (self.end_x, self.end_y) }
+    fn shape(&self) -> LinkShape { self.shape }
+}
+
 /// Simple implementation of PinGeometry
 #[derive(Debug, Clone, Copy)]
 pub struct SimplePinGeometry {
@@ -50,8 +123,25 @@ impl PinGeometry for SimplePinGeometry {
     fn position(&self) -> (f32, f32) { (self.x, self.y) }
 }
 
-/// Simple implementation of NodeGeometry
+/// Pin geometry carrying the hit radius set with
+/// [`GeometryCache::set_pin_hit_radius`](crate::GeometryCache::set_pin_hit_radius)
 #[derive(Debug, Clone, Copy)]
+pub(crate) struct SizedPinGeometry {
+    pub id: i32,
+    pub x: f32,
+    pub y: f32,
+    pub hit_radius: Option<f32>,
+}
+
+impl PinGeometry for SizedPinGeometry {
+    fn id(&self) -> i32 { self.id }
+    fn position(&self) -> (f32, f32) { (self.x, self.y) }
+    fn hit_radius(&self) -> Option<f32> { self.hit_radius }
+}
+
+/// Simple implementation of NodeGeometry
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimpleNodeGeometry {
     pub id: i32,
     pub x: f32,
@@ -65,6 +155,21 @@ impl NodeGeometry for SimpleNodeGeometry {
     fn rect(&self) -> (f32, f32, f32, f32) { (self.x, self.y, self.width, self.height) }
 }
 
+/// Detailed result of [`find_link_at_detailed`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkHit {
+    /// ID of the closest link
+    pub id: i32,
+    /// Point on the link's curve closest to the query position
+    pub closest_x: f32,
+    pub closest_y: f32,
+    /// Curve parameter of the closest point, 0.0 at the start pin and 1.0 at
+    /// the end pin (legs of a waypoint route share the range equally)
+    pub t: f32,
+    /// Distance from the query position to the link
+    pub distance: f32,
+}
+
 /// Find a link at the given position
 ///
 /// Returns the ID of the closest link within hover_distance, or -1 if none.
@@ -84,19 +189,222 @@ where
     let mut closest_link_id: i32 = -1;
     let mut closest_distance = hover_distance;
 
+    for link in links {
+        let point = (mouse_x, mouse_y);
+        let distance =
+            link_distance(&link, point, zoom, bezier_min_offset, hit_samples, closest_distance);
+
+        if distance < closest_distance {
+            closest_distance = distance;
+            closest_link_id = link.id();
+        }
+    }
+
+    closest_link_id
+}
+
+/// Find all links at the given position, sorted by distance
+///
+/// Returns `(link_id, distance)` pairs for every link within hover_distance,
+/// closest first. Links at equal distance keep their iteration order.
+/// Useful for cycling through overlapping links on repeated clicks.
+pub fn find_links_at_sorted<L, I>(
+    mouse_x: f32,
+    mouse_y: f32,
+    links: I,
+    hover_distance: f32,
+    zoom: f32,
+    bezier_min_offset: f32,
+    hit_samples: usize,
+) -> Vec<(i32, f32)>
+where
+    L: LinkGeometry,
+    I: IntoIterator<Item = L>,
+{
+    let mut hits: Vec<(i32, f32)> = links
+        .into_iter()
+        .filter_map(|link| {
+            let point = (mouse_x, mouse_y);
+            let distance =
+                link_distance(&link, point, zoom, bezier_min_offset, hit_samples, hover_distance);
+            (distance < hover_distance).then(|| (link.id(), distance))
+        })
+        .collect();
+
+    // Stable sort so ties preserve input order, matching find_link_at
+    hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+    hits
+}
+
+/// Find the link at the given position along with the closest point on it
+///
+/// Same matching rules as [`find_link_at`], but returns where on the curve the
+/// position is closest, e.g. to show a hover marker or to insert a node on
+/// the link. Returns `None` if no link is within `hover_distance`.
+pub fn find_link_at_detailed<L, I>(
+    mouse_x: f32,
+    mouse_y: f32,
+    links: I,
+    hover_distance: f32,
+    zoom: f32,
+    bezier_min_offset: f32,
+    hit_samples: usize,
+) -> Option<LinkHit>
+where
+    L: LinkGeometry,
+    I: IntoIterator<Item = L>,
+{
+    let mut closest: Option<(L, f32, f32)> = None;
+    let mut closest_distance = hover_distance;
+
+    for link in links {
+        let point = (mouse_x, mouse_y);
+        let (t, distance) =
+            link_closest_t(&link, point, zoom, bezier_min_offset, hit_samples, closest_distance);
+
+        if distance < closest_distance {
+            closest_distance = distance;
+            closest = Some((link, t, distance));
+        }
+    }
+
+    let (link, t, distance) = closest?;
+    let (closest_x, closest_y) = link_route(&link, zoom, bezier_min_offset).eval(t);
+    Some(LinkHit {
+        id: link.id(),
+        closest_x,
+        closest_y,
+        t,
+        distance,
+    })
+}
+
+/// Distance from a point to a link's bezier, or to every leg when it has waypoints
+///
+/// Legs at least `max_distance` away are skipped (see [`link_closest_t`]).
+fn link_distance<L: LinkGeometry>(
+    link: &L,
+    point: (f32, f32),
+    zoom: f32,
+    bezier_min_offset: f32,
+    hit_samples: usize,
+    max_distance: f32,
+) -> f32 {
+    link_closest_t(link, point, zoom, bezier_min_offset, hit_samples, max_distance).1
+}
+
+/// Curve parameter of the closest point on a link and the distance to it
+///
+/// Legs whose control-point bounding box is at least `max_distance` from
+/// `point` are not sampled: the sampled polyline lies inside that box, so
+/// its distance could not be below `max_distance` anyway. If every leg is
+/// skipped the distance is `f32::MAX`.
+fn link_closest_t<L: LinkGeometry>(
+    link: &L,
+    point: (f32, f32),
+    zoom: f32,
+    bezier_min_offset: f32,
+    hit_samples: usize,
+    max_distance: f32,
+) -> (f32, f32) {
+    let route = link_route(link, zoom, bezier_min_offset);
+    let legs = route.segments.len() as f32;
+    candidate_legs(&route, point, max_distance)
+        .map(|(i, segment)| {
+            let (t, distance) = closest_t_on_bezier(point, segment, hit_samples);
+            ((i as f32 + t) / legs, distance)
+        })
+        .fold((0.0, f32::MAX), |best, hit| if hit.1 < best.1 { hit } else { best })
+}
+
+/// Legs of `route` (with their index) that could pass within `max_distance` of `point`
+fn candidate_legs(
+    route: &WaypointPath,
+    point: (f32, f32),
+    max_distance: f32,
+) -> impl Iterator<Item = (usize, &CubicBezier)> {
+    route
+        .segments
+        .iter()
+        .enumerate()
+        .filter(move |(_, segment)| hull_distance_sq(point, segment) < max_distance * max_distance)
+}
+
+/// Squared distance from `point` to the bounding box of a bezier's control points
+fn hull_distance_sq(point: (f32, f32), bezier: &CubicBezier) -> f32 {
+    let xs = [bezier.p0.0, bezier.p1.0, bezier.p2.0, bezier.p3.0];
+    let ys = [bezier.p0.1, bezier.p1.1, bezier.p2.1, bezier.p3.1];
+    let min_x = xs.iter().copied().fold(f32::INFINITY, f32::min);
+    let max_x = xs.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let min_y = ys.iter().copied().fold(f32::INFINITY, f32::min);
+    let max_y = ys.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let dx = (min_x - point.0).max(point.0 - max_x).max(0.0);
+    let dy = (min_y - point.1).max(point.1 - max_y).max(0.0);
+    dx * dx + dy * dy
+}
+
+/// A link's curve as a waypoint route (a single bezier leg when it has no waypoints)
+pub(crate) fn link_route<L: LinkGeometry>(
+    link: &L,
+    zoom: f32,
+    bezier_min_offset: f32,
+) -> WaypointPath {
+    let (start_x, start_y) = link.start();
+    let (end_x, end_y) = link.end();
+    let waypoints = link.waypoints();
+
+    if waypoints.is_empty() {
+        WaypointPath {
+            segments: vec![CubicBezier::from_link_shape(
+                (start_x, start_y),
+                (end_x, end_y),
+                link.shape(),
+                zoom,
+                bezier_min_offset,
+            )],
+        }
+    } else {
+        let (start, end) = ((start_x, start_y), (end_x, end_y));
+        let (start_dir, end_dir) = link.shape().pin_directions(start, end);
+        let offset = bezier_min_offset;
+        WaypointPath::from_points(start, start_dir, waypoints, end, end_dir, zoom, offset)
+    }
+}
+
+/// The loop drawn for a link with a [`LinkShape::self_loop`] shape, or
+/// `None` for any other link
+fn self_loop_curve<L: LinkGeometry>(link: &L, zoom: f32, loop_radius: f32) -> Option<CubicBezier> {
+    let shape = link.shape();
+    shape
+        .self_loop
+        .then(|| CubicBezier::from_link_shape(link.start(), link.end(), shape, zoom, loop_radius))
+}
+
+/// Find an orthogonally routed link at the given position
+///
+/// Same as [`find_link_at`], but measures distance against the route produced
+/// by [`generate_orthogonal_path`](crate::path::generate_orthogonal_path).
+/// Returns the ID of the closest link within hover_distance, or -1 if none.
+pub fn find_orthogonal_link_at<L, I>(
+    mouse_x: f32,
+    mouse_y: f32,
+    links: I,
+    hover_distance: f32,
+    zoom: f32,
+) -> i32
+where
+    L: LinkGeometry,
+    I: IntoIterator<Item = L>,
+{
+    let mut closest_link_id: i32 = -1;
+    let mut closest_distance = hover_distance;
+
     for link in links {
         let (start_x, start_y) = link.start();
         let (end_x, end_y) = link.end();
 
-        let bezier = CubicBezier::from_endpoints(
-            start_x,
-            start_y,
-            end_x,
-            end_y,
-            zoom,
-            bezier_min_offset,
-        );
-        let distance = distance_to_bezier((mouse_x, mouse_y), &bezier, hit_samples);
+        let route = OrthogonalPath::from_endpoints(start_x, start_y, end_x, end_y, zoom);
+        let distance = distance_to_orthogonal_path((mouse_x, mouse_y), &route);
 
         if distance < closest_distance {
             closest_distance = distance;
@@ -107,21 +415,45 @@ where
     closest_link_id
 }
 
+/// Find a straight link at the given position
+///
+/// Same as [`find_link_at`], but measures distance to the straight segment
+/// drawn by [`StraightLinePathProvider`](crate::StraightLinePathProvider).
+/// Returns the ID of the closest link within hover_distance, or -1 if none.
+pub fn find_straight_link_at<L, I>(mouse_x: f32, mouse_y: f32, links: I, hover_distance: f32) -> i32
+where
+    L: LinkGeometry,
+    I: IntoIterator<Item = L>,
+{
+    let mut closest_link_id: i32 = -1;
+    let mut closest_distance_sq = hover_distance * hover_distance;
+
+    for link in links {
+        let distance_sq = distance_to_line_segment_sq((mouse_x, mouse_y), link.start(), link.end());
+        if distance_sq < closest_distance_sq {
+            closest_distance_sq = distance_sq;
+            closest_link_id = link.id();
+        }
+    }
+
+    closest_link_id
+}
+
 /// Find a pin at the given position
 ///
-/// Returns the ID of the closest pin within hit_radius, or 0 if none.
+/// Returns the ID of the closest pin within hit_radius, or 0 if none. A pin
+/// with its own [`PinGeometry::hit_radius`] uses that instead of `hit_radius`.
 pub fn find_pin_at<P, I>(mouse_x: f32, mouse_y: f32, pins: I, hit_radius: f32) -> i32
 where
     P: PinGeometry,
     I: IntoIterator<Item = P>,
 {
-    let hit_radius_sq = hit_radius * hit_radius;
-
     for pin in pins {
         let (pin_x, pin_y) = pin.position();
         let dx = mouse_x - pin_x;
         let dy = mouse_y - pin_y;
-        if dx * dx + dy * dy <= hit_radius_sq {
+        let radius = pin.hit_radius().unwrap_or(hit_radius);
+        if dx * dx + dy * dy <= radius * radius {
             return pin.id();
         }
     }
@@ -154,7 +486,27 @@ where
         .collect()
 }
 
+/// Rule used by [`links_in_selection_box_with_mode`] to decide whether a
+/// link is inside a selection box
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkSelectionMode {
+    /// Either endpoint lies inside the box (the [`links_in_selection_box`] rule)
+    EitherEndpoint,
+    /// Both endpoints lie inside the box
+    BothEndpoints,
+    /// The sampled curve enters the box anywhere, even between the endpoints
+    CurveIntersects,
+}
+
 /// Find all links that intersect with a selection box
+///
+/// A link is selected when either endpoint lies inside the box. Links with
+/// a [`LinkShape::self_loop`] shape are also selected when the box overlaps
+/// the loop's bounding box, assuming zoom 1.0 and
+/// [`DEFAULT_BEZIER_MIN_OFFSET`] (a loop of
+/// [`DEFAULT_SELF_LOOP_RADIUS`](crate::path::DEFAULT_SELF_LOOP_RADIUS)).
+/// Use [`links_in_selection_box_with_mode`] with
+/// [`LinkSelectionMode::EitherEndpoint`] to pass the drawn zoom and offset.
 pub fn links_in_selection_box<L, I>(
     sel_x: f32,
     sel_y: f32,
@@ -166,24 +518,248 @@ where
     L: LinkGeometry,
     I: IntoIterator<Item = L>,
 {
-    links
+    links_in_selection_box_with_mode(
+        sel_x,
+        sel_y,
+        sel_width,
+        sel_height,
+        links,
+        LinkSelectionMode::EitherEndpoint,
+        1.0,
+        DEFAULT_BEZIER_MIN_OFFSET,
+        0,
+    )
+}
+
+/// Find all links inside a selection box under the given [`LinkSelectionMode`]
+///
+/// `zoom`, `bezier_min_offset` and `hit_samples` describe the curves as in
+/// [`find_link_at`]. [`LinkSelectionMode::EitherEndpoint`] uses the first two
+/// for the bounds of self-loops, which are drawn with the bezier offset as
+/// their radius; only [`LinkSelectionMode::CurveIntersects`] samples curves.
+#[allow(clippy::too_many_arguments)]
+pub fn links_in_selection_box_with_mode<L, I>(
+    sel_x: f32,
+    sel_y: f32,
+    sel_width: f32,
+    sel_height: f32,
+    links: I,
+    mode: LinkSelectionMode,
+    zoom: f32,
+    bezier_min_offset: f32,
+    hit_samples: usize,
+) -> Vec<i32>
+where
+    L: LinkGeometry,
+    I: IntoIterator<Item = L>,
+{
+    let in_box = |(x, y): (f32, f32)| {
+        x >= sel_x && x <= sel_x + sel_width && y >= sel_y && y <= sel_y + sel_height
+    };
+
+    let loop_radius = bezier_min_offset;
+    match mode {
+        LinkSelectionMode::EitherEndpoint => links
+            .into_iter()
+            .filter(|link| {
+                if in_box(link.start()) || in_box(link.end()) {
+                    return true;
+                }
+                let Some(loop_curve) = self_loop_curve(link, zoom, loop_radius) else {
+                    return false;
+                };
+                let (lx, ly, lw, lh) = loop_curve.bounding_box();
+                lx < sel_x + sel_width
+                    && lx + lw > sel_x
+                    && ly < sel_y + sel_height
+                    && ly + lh > sel_y
+            })
+            .map(|link| link.id())
+            .collect(),
+        LinkSelectionMode::BothEndpoints => links
+            .into_iter()
+            .filter(|link| in_box(link.start()) && in_box(link.end()))
+            .map(|link| link.id())
+            .collect(),
+        LinkSelectionMode::CurveIntersects => {
+            let samples = if hit_samples == 0 { 20 } else { hit_samples };
+            let rect = (sel_x, sel_y, sel_width, sel_height);
+            links
+                .into_iter()
+                .filter(|link| {
+                    link_route(link, zoom, bezier_min_offset)
+                        .segments
+                        .iter()
+                        .any(|segment| {
+                            let mut prev = segment.eval(0.0);
+                            if in_box(prev) {
+                                return true;
+                            }
+                            (1..=samples).any(|i| {
+                                let curr = segment.eval(i as f32 / samples as f32);
+                                let hit = in_box(curr) || segment_intersects_rect(prev, curr, rect);
+                                prev = curr;
+                                hit
+                            })
+                        })
+                })
+                .map(|link| link.id())
+                .collect()
+        }
+    }
+}
+
+/// Check whether a point lies inside a polygon (even-odd rule)
+///
+/// The polygon is closed implicitly from its last vertex back to the first.
+/// Polygons with fewer than three vertices contain nothing.
+pub fn point_in_polygon(point: (f32, f32), polygon: &[(f32, f32)]) -> bool {
+    if polygon.len() < 3 {
+        return false;
+    }
+
+    let (px, py) = point;
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (xi, yi) = polygon[i];
+        let (xj, yj) = polygon[j];
+        if (yi > py) != (yj > py) && px < (xj - xi) * (py - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Find all nodes whose center lies inside a lasso polygon
+///
+/// Uses [`point_in_polygon`], so self-intersecting lassos follow the
+/// even-odd rule.
+pub fn nodes_in_polygon<N, I>(polygon: &[(f32, f32)], nodes: I) -> Vec<i32>
+where
+    N: NodeGeometry,
+    I: IntoIterator<Item = N>,
+{
+    nodes
         .into_iter()
-        .filter(|link| {
-            let (start_x, start_y) = link.start();
-            let (end_x, end_y) = link.end();
+        .filter(|node| {
+            let (x, y, w, h) = node.rect();
+            point_in_polygon((x + w / 2.0, y + h / 2.0), polygon)
+        })
+        .map(|node| node.id())
+        .collect()
+}
 
-            let start_in_box = start_x >= sel_x && start_x <= sel_x + sel_width
-                && start_y >= sel_y && start_y <= sel_y + sel_height;
+/// Find all nodes whose whole rect lies inside a lasso polygon
+///
+/// Stricter variant of [`nodes_in_polygon`]: all four corners must be inside
+/// and no polygon edge may cut through the rect, so concave lassos that dip
+/// into a node do not select it.
+pub fn nodes_enclosed_by_polygon<N, I>(polygon: &[(f32, f32)], nodes: I) -> Vec<i32>
+where
+    N: NodeGeometry,
+    I: IntoIterator<Item = N>,
+{
+    nodes
+        .into_iter()
+        .filter(|node| {
+            let (x, y, w, h) = node.rect();
+            let corners = [(x, y), (x + w, y), (x + w, y + h), (x, y + h)];
+            corners.iter().all(|&corner| point_in_polygon(corner, polygon))
+                && !polygon_edges(polygon).any(|(a, b)| segment_intersects_rect(a, b, (x, y, w, h)))
+        })
+        .map(|node| node.id())
+        .collect()
+}
 
-            let end_in_box = end_x >= sel_x && end_x <= sel_x + sel_width
-                && end_y >= sel_y && end_y <= sel_y + sel_height;
+/// Find all links whose curve touches a lasso polygon
+///
+/// Each link's bezier (or waypoint route) is sampled at `samples` points per
+/// leg (0 uses the default of 20). A link is selected when a sample lies
+/// inside the polygon or a chord between samples crosses a polygon edge, so
+/// a link passing through the lasso is selected even when both of its
+/// endpoints are outside.
+pub fn links_in_polygon<L, I>(
+    polygon: &[(f32, f32)],
+    links: I,
+    zoom: f32,
+    bezier_min_offset: f32,
+    samples: usize,
+) -> Vec<i32>
+where
+    L: LinkGeometry,
+    I: IntoIterator<Item = L>,
+{
+    if polygon.len() < 3 {
+        return Vec::new();
+    }
+    let samples = if samples == 0 { 20 } else { samples };
 
-            start_in_box || end_in_box
+    links
+        .into_iter()
+        .filter(|link| {
+            link_route(link, zoom, bezier_min_offset)
+                .segments
+                .iter()
+                .any(|segment| {
+                    let mut prev = segment.eval(0.0);
+                    if point_in_polygon(prev, polygon) {
+                        return true;
+                    }
+                    (1..=samples).any(|i| {
+                        let curr = segment.eval(i as f32 / samples as f32);
+                        let hit = point_in_polygon(curr, polygon)
+                            || polygon_edges(polygon).any(|(a, b)| segments_intersect(prev, curr, a, b));
+                        prev = curr;
+                        hit
+                    })
+                })
         })
         .map(|link| link.id())
         .collect()
 }
 
+/// Edges of a closed polygon as `(from, to)` pairs
+fn polygon_edges(polygon: &[(f32, f32)]) -> impl Iterator<Item = ((f32, f32), (f32, f32))> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+/// Check whether segment `a`-`b` crosses any edge of an `(x, y, w, h)` rect
+fn segment_intersects_rect(a: (f32, f32), b: (f32, f32), (x, y, w, h): (f32, f32, f32, f32)) -> bool {
+    let corners = [(x, y), (x + w, y), (x + w, y + h), (x, y + h)];
+    (0..4).any(|i| segments_intersect(a, b, corners[i], corners[(i + 1) % 4]))
+}
+
+/// Check whether segments `a`-`b` and `c`-`d` intersect (touching counts)
+fn segments_intersect(a: (f32, f32), b: (f32, f32), c: (f32, f32), d: (f32, f32)) -> bool {
+    let cross = |o: (f32, f32), p: (f32, f32), q: (f32, f32)| {
+        (p.0 - o.0) * (q.1 - o.1) - (p.1 - o.1) * (q.0 - o.0)
+    };
+    let on_segment = |p: (f32, f32), q: (f32, f32), r: (f32, f32)| {
+        r.0 >= p.0.min(q.0) && r.0 <= p.0.max(q.0) && r.1 >= p.1.min(q.1) && r.1 <= p.1.max(q.1)
+    };
+
+    let d1 = cross(c, d, a);
+    let d2 = cross(c, d, b);
+    let d3 = cross(a, b, c);
+    let d4 = cross(a, b, d);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+
+    (d1 == 0.0 && on_segment(c, d, a))
+        || (d2 == 0.0 && on_segment(c, d, b))
+        || (d3 == 0.0 && on_segment(a, b, c))
+        || (d4 == 0.0 && on_segment(a, b, d))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,6 +825,46 @@ mod tests {
         assert_eq!(find_pin_at(50.1, 50.0, pins, 0.0), 0);
     }
 
+    /// Pin with an optional per-pin hit radius
+    struct TouchPin {
+        id: i32,
+        x: f32,
+        y: f32,
+        radius: Option<f32>,
+    }
+
+    impl PinGeometry for TouchPin {
+        fn id(&self) -> i32 { self.id }
+        fn position(&self) -> (f32, f32) { (self.x, self.y) }
+        fn hit_radius(&self) -> Option<f32> { self.radius }
+    }
+
+    #[test]
+    fn test_find_pin_at_uses_own_hit_radius() {
+        let pins = || {
+            vec![
+                TouchPin { id: 1, x: 0.0, y: 0.0, radius: None },
+                TouchPin { id: 2, x: 100.0, y: 0.0, radius: Some(30.0) },
+            ]
+        };
+
+        // 25px away: misses the default-radius pin, hits the large one
+        assert_eq!(find_pin_at(25.0, 0.0, pins(), 10.0), 0);
+        assert_eq!(find_pin_at(75.0, 0.0, pins(), 10.0), 2);
+        // The caller's radius still applies to pins without their own
+        assert_eq!(find_pin_at(5.0, 0.0, pins(), 10.0), 1);
+    }
+
+    #[test]
+    fn test_own_hit_radius_can_be_smaller_than_default() {
+        let pins = vec![TouchPin { id: 1, x: 0.0, y: 0.0, radius: Some(2.0) }];
+        assert_eq!(find_pin_at(5.0, 0.0, pins, 10.0), 0);
+
+        // A zero radius is a real radius, not "use the default"
+        let pins = vec![TouchPin { id: 1, x: 0.0, y: 0.0, radius: Some(0.0) }];
+        assert_eq!(find_pin_at(0.5, 0.0, pins, 10.0), 0);
+    }
+
     // ========================================================================
     // find_link_at() - Link Hit Testing (Core function)
     // ========================================================================
@@ -471,6 +1087,317 @@ mod tests {
         assert_eq!(result, 2);
     }
 
+    // ========================================================================
+    // find_links_at_sorted() - Ranked Link Hit Testing
+    // ========================================================================
+
+    #[test]
+    fn test_find_links_at_sorted_orders_by_distance() {
+        let links = vec![
+            SimpleLinkGeometry { id: 1, start_x: 0.0, start_y: 50.0, end_x: 100.0, end_y: 50.0 },
+            SimpleLinkGeometry { id: 2, start_x: 0.0, start_y: 55.0, end_x: 100.0, end_y: 55.0 },
+            SimpleLinkGeometry { id: 3, start_x: 0.0, start_y: 200.0, end_x: 100.0, end_y: 200.0 },
+        ];
+
+        // Click at y=53: link 2 is closer than link 1, link 3 is out of range
+        let hits = find_links_at_sorted(50.0, 53.0, links, 10.0, 1.0, 50.0, 20);
+        let ids: Vec<i32> = hits.iter().map(|&(id, _)| id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert!(hits[0].1 <= hits[1].1);
+    }
+
+    #[test]
+    fn test_find_links_at_sorted_first_matches_find_link_at() {
+        let links = vec![
+            SimpleLinkGeometry { id: 1, start_x: 0.0, start_y: 50.0, end_x: 100.0, end_y: 50.0 },
+            SimpleLinkGeometry { id: 2, start_x: 0.0, start_y: 50.0, end_x: 100.0, end_y: 50.0 },
+        ];
+
+        // Exact tie: input order is preserved, so the first entry agrees with find_link_at
+        let hits = find_links_at_sorted(50.0, 50.0, links.clone(), 10.0, 1.0, 50.0, 20);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].0, find_link_at(50.0, 50.0, links, 10.0, 1.0, 50.0, 20));
+        assert_eq!(hits[1].0, 2);
+    }
+
+    #[test]
+    fn test_find_links_at_sorted_miss() {
+        let links = vec![SimpleLinkGeometry {
+            id: 1,
+            start_x: 0.0,
+            start_y: 50.0,
+            end_x: 100.0,
+            end_y: 50.0,
+        }];
+
+        let hits = find_links_at_sorted(50.0, 200.0, links, 10.0, 1.0, 50.0, 20);
+        assert!(hits.is_empty());
+    }
+
+    // ========================================================================
+    // find_link_at() with waypoints - Routed Link Hit Testing
+    // ========================================================================
+
+    struct RoutedLink {
+        id: i32,
+        start: (f32, f32),
+        end: (f32, f32),
+        waypoints: Vec<(f32, f32)>,
+        shape: LinkShape,
+    }
+
+    impl LinkGeometry for &RoutedLink {
+        fn id(&self) -> i32 { self.id }
+        fn start(&self) -> (f32, f32) { self.start }
+        fn end(&self) -> (f32, f32) { self.end }
+        fn waypoints(&self) -> &[(f32, f32)] { &self.waypoints }
+        fn shape(&self) -> LinkShape { self.shape }
+    }
+
+    #[test]
+    fn test_find_link_at_samples_waypoint_legs() {
+        let links = vec![RoutedLink {
+            id: 7,
+            start: (0.0, 0.0),
+            end: (300.0, 0.0),
+            waypoints: vec![(150.0, -120.0)],
+            shape: LinkShape::default(),
+        }];
+
+        assert_eq!(find_link_at(150.0, -118.0, &links, 5.0, 1.0, 50.0, 20), 7);
+        // A plain bezier would pass through here, the routed link does not
+        assert_eq!(find_link_at(150.0, 0.0, &links, 5.0, 1.0, 50.0, 20), -1);
+        assert_eq!(find_links_at_sorted(150.0, -118.0, &links, 5.0, 1.0, 50.0, 20).len(), 1);
+    }
+
+    #[test]
+    fn test_find_link_at_waypoint_route_leaves_along_pin_sides() {
+        let mut link = RoutedLink {
+            id: 4,
+            start: (0.0, 0.0),
+            end: (0.0, 300.0),
+            waypoints: vec![(150.0, 150.0)],
+            shape: LinkShape::default().with_sides(PinSide::Bottom, PinSide::Top),
+        };
+        // Straight below the start pin: only a route leaving downwards passes here
+        let below = link_route(&&link, 1.0, 50.0).segments[0].eval(0.1);
+        assert!(below.1 > 0.0 && below.0.abs() < below.1, "{:?}", below);
+        assert_eq!(find_link_at(below.0, below.1, [&link], 2.0, 1.0, 50.0, 40), 4);
+
+        link.shape = LinkShape::default();
+        assert_eq!(find_link_at(below.0, below.1, [&link], 2.0, 1.0, 50.0, 40), -1);
+    }
+
+    // ========================================================================
+    // find_link_at() bounding-box early-out
+    // ========================================================================
+
+    /// Minimal xorshift generator so the test is reproducible without a rand dependency
+    fn xorshift(state: &mut u32) -> f32 {
+        *state ^= *state << 13;
+        *state ^= *state >> 17;
+        *state ^= *state << 5;
+        (*state % 10_000) as f32 / 10.0
+    }
+
+    /// `find_link_at` without the early-out: samples every link
+    fn find_link_at_exhaustive(point: (f32, f32), links: &[SimpleLinkGeometry], hover: f32) -> i32 {
+        let mut closest = (-1, hover);
+        for link in links {
+            let route = link_route(link, 1.0, 50.0);
+            let distance = route
+                .segments
+                .iter()
+                .map(|segment| closest_t_on_bezier(point, segment, 20).1)
+                .fold(f32::MAX, f32::min);
+            if distance < closest.1 {
+                closest = (link.id, distance);
+            }
+        }
+        closest.0
+    }
+
+    /// 300 short random links in a 1000x1000 area
+    fn random_links(rng: &mut u32) -> Vec<SimpleLinkGeometry> {
+        (0..300)
+            .map(|id| {
+                let (x, y) = (xorshift(rng), xorshift(rng));
+                SimpleLinkGeometry {
+                    id,
+                    start_x: x,
+                    start_y: y,
+                    end_x: x + xorshift(rng) / 5.0 - 50.0,
+                    end_y: y + xorshift(rng) / 5.0 - 50.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_link_at_early_out_matches_exhaustive_search() {
+        let mut rng = 0x2545_f491;
+        let links = random_links(&mut rng);
+        for _ in 0..200 {
+            let point = (xorshift(&mut rng), xorshift(&mut rng));
+            assert_eq!(
+                find_link_at(point.0, point.1, links.iter().copied(), 8.0, 1.0, 50.0, 20),
+                find_link_at_exhaustive(point, &links, 8.0),
+                "query at {:?}",
+                point
+            );
+        }
+    }
+
+    #[test]
+    fn test_candidate_legs_rejects_most_random_links() {
+        let mut rng = 0x2545_f491;
+        let links = random_links(&mut rng);
+        let routes: Vec<WaypointPath> =
+            links.iter().map(|link| link_route(link, 1.0, 50.0)).collect();
+        let queries = 200;
+        let mut candidates = 0;
+        for _ in 0..queries {
+            let point = (xorshift(&mut rng), xorshift(&mut rng));
+            candidates += routes
+                .iter()
+                .map(|route| candidate_legs(route, point, 8.0).count())
+                .sum::<usize>();
+        }
+
+        // Fewer than one leg in ten passes the hull test at hover distance
+        assert!(candidates * 10 < queries * links.len(), "{} candidate legs", candidates);
+    }
+
+    #[test]
+    fn test_find_links_at_sorted_skips_distant_links() {
+        let links: Vec<SimpleLinkGeometry> = (0..50)
+            .map(|i| SimpleLinkGeometry {
+                id: i,
+                start_x: 0.0,
+                start_y: i as f32 * 100.0,
+                end_x: 100.0,
+                end_y: i as f32 * 100.0,
+            })
+            .collect();
+
+        let candidates: usize = links
+            .iter()
+            .map(|link| candidate_legs(&link_route(link, 1.0, 50.0), (50.0, 502.0), 5.0).count())
+            .sum();
+        assert_eq!(candidates, 1);
+
+        let hits = find_links_at_sorted(50.0, 502.0, links, 5.0, 1.0, 50.0, 20);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, 5);
+    }
+
+    // ========================================================================
+    // find_link_at_detailed() - Closest Point Hit Testing
+    // ========================================================================
+
+    fn horizontal_link() -> Vec<SimpleLinkGeometry> {
+        vec![SimpleLinkGeometry {
+            id: 3,
+            start_x: 0.0,
+            start_y: 50.0,
+            end_x: 100.0,
+            end_y: 50.0,
+        }]
+    }
+
+    #[test]
+    fn test_find_link_at_detailed_midpoint() {
+        let hit = find_link_at_detailed(50.0, 53.0, horizontal_link(), 5.0, 1.0, 50.0, 20).unwrap();
+        assert_eq!(hit.id, 3);
+        assert!((hit.t - 0.5).abs() < 0.01, "t = {}", hit.t);
+        assert!((hit.closest_x - 50.0).abs() < 0.5);
+        assert!((hit.closest_y - 50.0).abs() < 1e-3);
+        assert!((hit.distance - 3.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_find_link_at_detailed_matches_find_link_at() {
+        let links = vec![
+            SimpleLinkGeometry { id: 1, start_x: 0.0, start_y: 0.0, end_x: 200.0, end_y: 0.0 },
+            SimpleLinkGeometry { id: 2, start_x: 0.0, start_y: 10.0, end_x: 200.0, end_y: 10.0 },
+        ];
+        let hit = find_link_at_detailed(100.0, 7.0, links.clone(), 10.0, 1.0, 50.0, 20).unwrap();
+        assert_eq!(hit.id, find_link_at(100.0, 7.0, links, 10.0, 1.0, 50.0, 20));
+        assert_eq!(hit.id, 2);
+    }
+
+    #[test]
+    fn test_find_link_at_detailed_miss() {
+        assert_eq!(find_link_at_detailed(50.0, 80.0, horizontal_link(), 5.0, 1.0, 50.0, 20), None);
+    }
+
+    #[test]
+    fn test_find_link_at_detailed_waypoint_route() {
+        let links = vec![RoutedLink {
+            id: 7,
+            start: (0.0, 0.0),
+            end: (300.0, 0.0),
+            waypoints: vec![(150.0, -120.0)],
+            shape: LinkShape::default(),
+        }];
+        // Right at the waypoint: the boundary between the two legs
+        let hit = find_link_at_detailed(150.0, -118.0, &links, 5.0, 1.0, 50.0, 20).unwrap();
+        assert!((hit.t - 0.5).abs() < 0.02, "t = {}", hit.t);
+        assert!((hit.closest_x - 150.0).abs() < 2.0);
+        assert!((hit.closest_y + 120.0).abs() < 2.0);
+    }
+
+    // ========================================================================
+    // find_orthogonal_link_at() - Orthogonal Link Hit Testing
+    // ========================================================================
+
+    #[test]
+    fn test_find_orthogonal_link_at_vertical_segment() {
+        let links = vec![SimpleLinkGeometry {
+            id: 1,
+            start_x: 0.0,
+            start_y: 0.0,
+            end_x: 100.0,
+            end_y: 100.0,
+        }];
+
+        // The vertical run sits at x=50; a bezier would not pass through (50, 20)
+        assert_eq!(find_orthogonal_link_at(52.0, 20.0, links.clone(), 5.0, 1.0), 1);
+        assert_eq!(find_orthogonal_link_at(70.0, 20.0, links, 5.0, 1.0), -1);
+    }
+
+    // ========================================================================
+    // find_straight_link_at() - Straight Link Hit Testing
+    // ========================================================================
+
+    #[test]
+    fn test_find_straight_link_at_matches_segment_distance() {
+        let links = vec![SimpleLinkGeometry {
+            id: 1,
+            start_x: 0.0,
+            start_y: 0.0,
+            end_x: 100.0,
+            end_y: 100.0,
+        }];
+
+        // (60, 40) is sqrt(200) ~ 14.1 from the diagonal
+        assert_eq!(find_straight_link_at(60.0, 40.0, links.clone(), 15.0), 1);
+        assert_eq!(find_straight_link_at(60.0, 40.0, links.clone(), 14.0), -1);
+        // Past the end the distance is to the endpoint, not the infinite line
+        assert_eq!(find_straight_link_at(110.0, 110.0, links.clone(), 14.0), -1);
+        assert_eq!(find_straight_link_at(105.0, 105.0, links, 8.0), 1);
+    }
+
+    #[test]
+    fn test_find_straight_link_at_picks_closest() {
+        let links = vec![
+            SimpleLinkGeometry { id: 1, start_x: 0.0, start_y: 0.0, end_x: 100.0, end_y: 0.0 },
+            SimpleLinkGeometry { id: 2, start_x: 0.0, start_y: 10.0, end_x: 100.0, end_y: 10.0 },
+        ];
+        assert_eq!(find_straight_link_at(50.0, 3.0, links.clone(), 20.0), 1);
+        assert_eq!(find_straight_link_at(50.0, 7.0, links, 20.0), 2);
+    }
+
     // ========================================================================
     // nodes_in_selection_box() - Box Selection
     // ========================================================================
@@ -593,6 +1520,233 @@ mod tests {
         assert!(selected.is_empty());
     }
 
+    /// Link from a pin at `(x, y)` back to itself, flagged as a self-loop
+    fn pin_loop(id: i32, x: f32, y: f32) -> ShapedLinkGeometry {
+        ShapedLinkGeometry {
+            id,
+            start_x: x,
+            start_y: y,
+            end_x: x,
+            end_y: y,
+            shape: LinkShape::default().with_self_loop(true),
+        }
+    }
+
+    #[test]
+    fn test_links_in_selection_box_self_loop_bounds() {
+        let links = vec![pin_loop(1, 50.0, 200.0)];
+
+        // Box covers the top of the loop but not the pin itself
+        let selected = links_in_selection_box(40.0, 110.0, 20.0, 20.0, links.clone());
+        assert_eq!(selected, vec![1]);
+
+        // Box beside the loop misses it
+        let selected = links_in_selection_box(200.0, 110.0, 20.0, 20.0, links);
+        assert!(selected.is_empty());
+
+        // Coincident endpoints alone are not a loop
+        let plain =
+            SimpleLinkGeometry { id: 1, start_x: 50.0, start_y: 200.0, end_x: 50.0, end_y: 200.0 };
+        assert!(links_in_selection_box(40.0, 110.0, 20.0, 20.0, [plain]).is_empty());
+    }
+
+    #[test]
+    fn test_find_link_at_self_loop() {
+        let links = vec![pin_loop(7, 0.0, 0.0)];
+
+        // Top of the loop sits 2 * min_offset above the pin
+        assert_eq!(find_link_at(0.0, -60.0, links.clone(), 5.0, 1.0, 30.0, 40), 7);
+        assert_eq!(find_link_at(0.0, -90.0, links, 5.0, 1.0, 30.0, 40), -1);
+    }
+
+    #[test]
+    fn test_find_link_at_same_node_loop() {
+        // Output pin on the right edge looping back to an input on the left edge
+        let shaped = ShapedLinkGeometry {
+            id: 3,
+            start_x: 100.0,
+            start_y: 25.0,
+            end_x: 0.0,
+            end_y: 25.0,
+            shape: LinkShape::default().with_self_loop(true),
+        };
+        let top = CubicBezier::self_loop_between((100.0, 25.0), (0.0, 25.0), 50.0, 1.0).eval(0.5);
+        assert!((top.0 - 50.0).abs() < 1e-3 && (top.1 + 75.0).abs() < 1e-3);
+
+        assert_eq!(find_link_at(top.0, top.1, [shaped], 5.0, 1.0, 50.0, 40), 3);
+        let plain =
+            SimpleLinkGeometry { id: 3, start_x: 100.0, start_y: 25.0, end_x: 0.0, end_y: 25.0 };
+        assert_eq!(find_link_at(top.0, top.1, [plain], 5.0, 1.0, 50.0, 40), -1);
+        let selected = links_in_selection_box(45.0, -80.0, 10.0, 10.0, [shaped]);
+        assert_eq!(selected, vec![3]);
+    }
+
+    #[test]
+    fn test_links_in_selection_box_self_loop_uses_zoom_and_offset() {
+        let links = [pin_loop(1, 0.0, 0.0)];
+        let select = |zoom: f32, offset: f32| {
+            links_in_selection_box_with_mode(
+                -5.0,
+                -95.0,
+                10.0,
+                10.0,
+                links,
+                LinkSelectionMode::EitherEndpoint,
+                zoom,
+                offset,
+                0,
+            )
+        };
+
+        // The default radius reaches 100 above the pin, a radius of 30 only 60
+        assert_eq!(links_in_selection_box(-5.0, -95.0, 10.0, 10.0, links), vec![1]);
+        assert!(select(1.0, 30.0).is_empty());
+        assert_eq!(select(2.0, 30.0), vec![1]);
+    }
+
+    // ========================================================================
+    // links_in_selection_box_with_mode() - Link Box Selection Modes
+    // ========================================================================
+
+    fn box_mode_hits(links: &[SimpleLinkGeometry], mode: LinkSelectionMode) -> Vec<i32> {
+        let mut hits =
+            links_in_selection_box_with_mode(0.0, 0.0, 100.0, 100.0, links.iter().copied(), mode, 1.0, 50.0, 20);
+        hits.sort();
+        hits
+    }
+
+    #[test]
+    fn test_box_modes_one_endpoint_inside() {
+        let links = [SimpleLinkGeometry { id: 1, start_x: 50.0, start_y: 50.0, end_x: 300.0, end_y: 50.0 }];
+
+        assert_eq!(box_mode_hits(&links, LinkSelectionMode::EitherEndpoint), vec![1]);
+        assert!(box_mode_hits(&links, LinkSelectionMode::BothEndpoints).is_empty());
+        assert_eq!(box_mode_hits(&links, LinkSelectionMode::CurveIntersects), vec![1]);
+    }
+
+    #[test]
+    fn test_box_modes_crossing_link_without_endpoints() {
+        // Passes straight through the box from left to right
+        let links = [SimpleLinkGeometry { id: 2, start_x: -100.0, start_y: 50.0, end_x: 200.0, end_y: 50.0 }];
+
+        assert!(box_mode_hits(&links, LinkSelectionMode::EitherEndpoint).is_empty());
+        assert!(box_mode_hits(&links, LinkSelectionMode::BothEndpoints).is_empty());
+        assert_eq!(box_mode_hits(&links, LinkSelectionMode::CurveIntersects), vec![2]);
+    }
+
+    #[test]
+    fn test_box_modes_contained_and_outside_links() {
+        let links = [
+            SimpleLinkGeometry { id: 3, start_x: 10.0, start_y: 10.0, end_x: 90.0, end_y: 90.0 },
+            SimpleLinkGeometry { id: 4, start_x: 200.0, start_y: 200.0, end_x: 400.0, end_y: 250.0 },
+        ];
+
+        for mode in [
+            LinkSelectionMode::EitherEndpoint,
+            LinkSelectionMode::BothEndpoints,
+            LinkSelectionMode::CurveIntersects,
+        ] {
+            assert_eq!(box_mode_hits(&links, mode), vec![3], "{:?}", mode);
+        }
+    }
+
+    // ========================================================================
+    // nodes_in_polygon() - Lasso Selection
+    // ========================================================================
+
+    fn square_node(id: i32, x: f32, y: f32) -> SimpleNodeGeometry {
+        SimpleNodeGeometry { id, x, y, width: 20.0, height: 20.0 }
+    }
+
+    #[test]
+    fn test_point_in_polygon_triangle() {
+        let triangle = [(0.0, 0.0), (100.0, 0.0), (50.0, 100.0)];
+        assert!(point_in_polygon((50.0, 30.0), &triangle));
+        assert!(!point_in_polygon((10.0, 80.0), &triangle));
+        assert!(!point_in_polygon((150.0, 30.0), &triangle));
+        // Degenerate polygons contain nothing
+        assert!(!point_in_polygon((0.0, 0.0), &[(0.0, 0.0), (10.0, 10.0)]));
+    }
+
+    #[test]
+    fn test_nodes_in_polygon_triangle() {
+        let triangle = [(0.0, 0.0), (200.0, 0.0), (100.0, 200.0)];
+        let nodes = vec![
+            square_node(1, 90.0, 40.0),  // center (100, 50): inside
+            square_node(2, 10.0, 150.0), // center (20, 160): outside, left of the slope
+            square_node(3, 0.0, -5.0),   // center (10, 5): inside near the corner
+        ];
+
+        let mut selected = nodes_in_polygon(&triangle, nodes);
+        selected.sort();
+        assert_eq!(selected, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_nodes_enclosed_by_polygon_requires_whole_rect() {
+        let triangle = [(0.0, 0.0), (200.0, 0.0), (100.0, 200.0)];
+        let nodes = vec![square_node(1, 90.0, 40.0), square_node(3, 0.0, -5.0)];
+
+        // Node 3's center is inside, but its rect pokes out of the triangle
+        assert_eq!(nodes_enclosed_by_polygon(&triangle, nodes), vec![1]);
+    }
+
+    #[test]
+    fn test_nodes_enclosed_by_concave_polygon() {
+        // U shape whose notch cuts into the middle of a wide node
+        let u_shape = [
+            (0.0, 0.0), (40.0, 0.0), (40.0, 60.0), (60.0, 60.0),
+            (60.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0),
+        ];
+        let wide = SimpleNodeGeometry { id: 1, x: 10.0, y: 40.0, width: 80.0, height: 10.0 };
+        assert!(nodes_enclosed_by_polygon(&u_shape, vec![wide]).is_empty());
+
+        let below_notch = SimpleNodeGeometry { id: 2, x: 10.0, y: 70.0, width: 80.0, height: 10.0 };
+        assert_eq!(nodes_enclosed_by_polygon(&u_shape, vec![below_notch]), vec![2]);
+    }
+
+    // ========================================================================
+    // links_in_polygon() - Link Lasso Selection
+    // ========================================================================
+
+    #[test]
+    fn test_links_in_polygon_crossing_link() {
+        // Horizontal link from (0, 50) to (300, 50), lasso around its middle only
+        let links = vec![SimpleLinkGeometry { id: 1, start_x: 0.0, start_y: 50.0, end_x: 300.0, end_y: 50.0 }];
+        let lasso = [(100.0, 0.0), (200.0, 0.0), (150.0, 100.0)];
+
+        assert!(!point_in_polygon((0.0, 50.0), &lasso));
+        assert!(!point_in_polygon((300.0, 50.0), &lasso));
+        assert_eq!(links_in_polygon(&lasso, links, 1.0, 50.0, 20), vec![1]);
+    }
+
+    #[test]
+    fn test_links_in_polygon_contained_and_missed() {
+        let links = vec![
+            SimpleLinkGeometry { id: 1, start_x: 20.0, start_y: 20.0, end_x: 80.0, end_y: 40.0 },
+            SimpleLinkGeometry { id: 2, start_x: 0.0, start_y: 300.0, end_x: 300.0, end_y: 300.0 },
+        ];
+        let square = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)];
+        assert_eq!(links_in_polygon(&square, links, 1.0, 50.0, 20), vec![1]);
+    }
+
+    #[test]
+    fn test_links_in_polygon_follows_curve_not_chord() {
+        // Waypoint route bulges up through the lasso; the straight chord does not
+        let links = vec![RoutedLink {
+            id: 7,
+            start: (0.0, 0.0),
+            end: (300.0, 0.0),
+            waypoints: vec![(150.0, -120.0)],
+            shape: LinkShape::default(),
+        }];
+        let lasso = [(130.0, -140.0), (170.0, -140.0), (170.0, -100.0), (130.0, -100.0)];
+        assert_eq!(links_in_polygon(&lasso, &links, 1.0, 50.0, 20), vec![7]);
+
+        let below = [(130.0, -20.0), (170.0, -20.0), (170.0, 20.0), (130.0, 20.0)];
+        assert!(links_in_polygon(&below, &links, 1.0, 50.0, 20).is_empty());
+    }
+
     // ========================================================================
     // Trait implementations
     // ========================================================================
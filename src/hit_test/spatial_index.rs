@@ -0,0 +1,358 @@
+//! Quadtree spatial index over node rectangles.
+//!
+//! Box selection over very large graphs spends most of its time rejecting
+//! nodes far away from the selection rectangle. [`NodeSpatialIndex`] buckets
+//! node AABBs into a quadtree so a query only visits the regions it overlaps,
+//! while applying exactly the same overlap test as
+//! [`nodes_in_selection_box`](super::nodes_in_selection_box).
+
+use super::NodeGeometry;
+
+/// Maximum items held by a leaf before it is split
+const MAX_ITEMS_PER_QUAD: usize = 8;
+/// Maximum subdivision depth (guards against many coincident rects)
+const MAX_DEPTH: u32 = 12;
+
+/// Axis-aligned bounds as `(min_x, min_y, max_x, max_y)`
+type Bounds = (f32, f32, f32, f32);
+
+#[derive(Debug, Clone)]
+struct Quad {
+    bounds: Bounds,
+    depth: u32,
+    /// Indices into `NodeSpatialIndex::entries` stored at this level
+    items: Vec<usize>,
+    /// Indices into `NodeSpatialIndex::quads` (NW, NE, SW, SE)
+    children: Option<[usize; 4]>,
+}
+
+impl Quad {
+    fn new(bounds: Bounds, depth: u32) -> Self {
+        Self {
+            bounds,
+            depth,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+}
+
+/// Quadtree of node rectangles for fast box queries.
+///
+/// Build it from any iterator of [`NodeGeometry`], then call
+/// [`query_box`](Self::query_box). Rebuilding via [`rebuild`](Self::rebuild)
+/// reuses the existing allocations.
+///
+/// # Example
+///
+/// ```
+/// use slint_node_editor::{NodeSpatialIndex, SimpleNodeGeometry};
+///
+/// let nodes = vec![
+///     SimpleNodeGeometry { id: 1, x: 0.0, y: 0.0, width: 50.0, height: 50.0 },
+///     SimpleNodeGeometry { id: 2, x: 500.0, y: 500.0, width: 50.0, height: 50.0 },
+/// ];
+/// let index = NodeSpatialIndex::build(nodes);
+/// assert_eq!(index.query_box(-10.0, -10.0, 100.0, 100.0), vec![1]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NodeSpatialIndex {
+    /// `(node_id, (x, y, width, height))` in insertion order
+    entries: Vec<(i32, (f32, f32, f32, f32))>,
+    quads: Vec<Quad>,
+}
+
+impl NodeSpatialIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build an index from node geometries.
+    pub fn build<N, I>(nodes: I) -> Self
+    where
+        N: NodeGeometry,
+        I: IntoIterator<Item = N>,
+    {
+        let mut index = Self::new();
+        index.rebuild(nodes);
+        index
+    }
+
+    /// Replace the indexed nodes, reusing allocated storage.
+    pub fn rebuild<N, I>(&mut self, nodes: I)
+    where
+        N: NodeGeometry,
+        I: IntoIterator<Item = N>,
+    {
+        self.entries.clear();
+        self.quads.clear();
+        self.entries
+            .extend(nodes.into_iter().map(|node| (node.id(), node.rect())));
+
+        let mut root = (f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for &(_, rect) in &self.entries {
+            let (min_x, min_y, max_x, max_y) = rect_bounds(rect);
+            if min_x.is_finite() && min_y.is_finite() && max_x.is_finite() && max_y.is_finite() {
+                root.0 = root.0.min(min_x);
+                root.1 = root.1.min(min_y);
+                root.2 = root.2.max(max_x);
+                root.3 = root.3.max(max_y);
+            }
+        }
+        if root.0 > root.2 {
+            root = (0.0, 0.0, 0.0, 0.0);
+        }
+
+        self.quads.push(Quad::new(root, 0));
+        for item in 0..self.entries.len() {
+            self.insert(0, item);
+        }
+    }
+
+    /// Number of indexed nodes.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the index holds no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Find all nodes overlapping a selection box.
+    ///
+    /// Uses the same overlap test as
+    /// [`nodes_in_selection_box`](super::nodes_in_selection_box) and returns
+    /// IDs in the order the nodes were supplied to [`build`](Self::build).
+    ///
+    /// # Arguments
+    /// * `x`, `y` - Top-left corner of the selection box
+    /// * `width`, `height` - Size of the selection box
+    pub fn query_box(&self, x: f32, y: f32, width: f32, height: f32) -> Vec<i32> {
+        if self.quads.is_empty() {
+            return Vec::new();
+        }
+
+        let query = rect_bounds((x, y, width, height));
+        let mut hits = Vec::new();
+        let mut stack = vec![0usize];
+        while let Some(quad_idx) = stack.pop() {
+            let quad = &self.quads[quad_idx];
+            // The root also holds rects that lie outside (or are non-finite)
+            if quad_idx != 0 && !bounds_touch(quad.bounds, query) {
+                continue;
+            }
+            for &item in &quad.items {
+                let (nx, ny, nw, nh) = self.entries[item].1;
+                if nx < x + width && nx + nw > x && ny < y + height && ny + nh > y {
+                    hits.push(item);
+                }
+            }
+            if let Some(children) = quad.children {
+                stack.extend(children);
+            }
+        }
+
+        hits.sort_unstable();
+        hits.into_iter().map(|item| self.entries[item].0).collect()
+    }
+
+    fn insert(&mut self, quad_idx: usize, item: usize) {
+        let item_bounds = rect_bounds(self.entries[item].1);
+
+        if let Some(children) = self.quads[quad_idx].children {
+            if let Some(&child) = children
+                .iter()
+                .find(|&&child| bounds_contain(self.quads[child].bounds, item_bounds))
+            {
+                self.insert(child, item);
+                return;
+            }
+        }
+
+        self.quads[quad_idx].items.push(item);
+
+        let quad = &self.quads[quad_idx];
+        if quad.children.is_none()
+            && quad.items.len() > MAX_ITEMS_PER_QUAD
+            && quad.depth < MAX_DEPTH
+        {
+            self.split(quad_idx);
+        }
+    }
+
+    fn split(&mut self, quad_idx: usize) {
+        let (min_x, min_y, max_x, max_y) = self.quads[quad_idx].bounds;
+        let depth = self.quads[quad_idx].depth + 1;
+        let mid_x = (min_x + max_x) / 2.0;
+        let mid_y = (min_y + max_y) / 2.0;
+
+        let first = self.quads.len();
+        self.quads.push(Quad::new((min_x, min_y, mid_x, mid_y), depth));
+        self.quads.push(Quad::new((mid_x, min_y, max_x, mid_y), depth));
+        self.quads.push(Quad::new((min_x, mid_y, mid_x, max_y), depth));
+        self.quads.push(Quad::new((mid_x, mid_y, max_x, max_y), depth));
+        self.quads[quad_idx].children = Some([first, first + 1, first + 2, first + 3]);
+
+        let items = std::mem::take(&mut self.quads[quad_idx].items);
+        for item in items {
+            self.insert(quad_idx, item);
+        }
+    }
+}
+
+/// Normalize `(x, y, w, h)` (possibly with negative size) into bounds
+fn rect_bounds((x, y, w, h): (f32, f32, f32, f32)) -> Bounds {
+    (x.min(x + w), y.min(y + h), x.max(x + w), y.max(y + h))
+}
+
+fn bounds_contain(outer: Bounds, inner: Bounds) -> bool {
+    inner.0 >= outer.0 && inner.1 >= outer.1 && inner.2 <= outer.2 && inner.3 <= outer.3
+}
+
+fn bounds_touch(a: Bounds, b: Bounds) -> bool {
+    a.0 <= b.2 && a.2 >= b.0 && a.1 <= b.3 && a.3 >= b.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hit_test::{nodes_in_selection_box, SimpleNodeGeometry};
+
+    /// Small deterministic LCG so the randomized tests are reproducible
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next_f32(&mut self) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 40) as f32) / ((1u64 << 24) as f32)
+        }
+
+        fn range(&mut self, min: f32, max: f32) -> f32 {
+            min + self.next_f32() * (max - min)
+        }
+    }
+
+    fn random_nodes(rng: &mut Lcg, count: i32) -> Vec<SimpleNodeGeometry> {
+        (1..=count)
+            .map(|id| SimpleNodeGeometry {
+                id,
+                x: rng.range(-5000.0, 5000.0),
+                y: rng.range(-5000.0, 5000.0),
+                width: rng.range(20.0, 300.0),
+                height: rng.range(20.0, 200.0),
+            })
+            .collect()
+    }
+
+    // ========================================================================
+    // query_box() - Basic Queries
+    // ========================================================================
+
+    #[test]
+    fn test_empty_index_returns_nothing() {
+        let index = NodeSpatialIndex::new();
+        assert!(index.is_empty());
+        assert!(index.query_box(0.0, 0.0, 100.0, 100.0).is_empty());
+    }
+
+    #[test]
+    fn test_query_box_finds_overlapping_nodes() {
+        let nodes = vec![
+            SimpleNodeGeometry { id: 1, x: 0.0, y: 0.0, width: 50.0, height: 50.0 },
+            SimpleNodeGeometry { id: 2, x: 100.0, y: 0.0, width: 50.0, height: 50.0 },
+            SimpleNodeGeometry { id: 3, x: 0.0, y: 100.0, width: 50.0, height: 50.0 },
+        ];
+        let index = NodeSpatialIndex::build(nodes);
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.query_box(25.0, 25.0, 100.0, 10.0), vec![1, 2]);
+        assert!(index.query_box(60.0, 60.0, 20.0, 20.0).is_empty());
+    }
+
+    #[test]
+    fn test_touching_edges_are_not_selected() {
+        let nodes = vec![SimpleNodeGeometry { id: 1, x: 0.0, y: 0.0, width: 50.0, height: 50.0 }];
+        let index = NodeSpatialIndex::build(nodes);
+        assert!(index.query_box(50.0, 0.0, 10.0, 10.0).is_empty());
+    }
+
+    #[test]
+    fn test_rebuild_replaces_contents() {
+        let mut index = NodeSpatialIndex::build(vec![SimpleNodeGeometry {
+            id: 1, x: 0.0, y: 0.0, width: 10.0, height: 10.0,
+        }]);
+        index.rebuild(vec![SimpleNodeGeometry {
+            id: 2, x: 1000.0, y: 1000.0, width: 10.0, height: 10.0,
+        }]);
+        assert!(index.query_box(0.0, 0.0, 20.0, 20.0).is_empty());
+        assert_eq!(index.query_box(995.0, 995.0, 20.0, 20.0), vec![2]);
+    }
+
+    #[test]
+    fn test_coincident_nodes_do_not_split_forever() {
+        let nodes: Vec<_> = (1..=100)
+            .map(|id| SimpleNodeGeometry { id, x: 5.0, y: 5.0, width: 10.0, height: 10.0 })
+            .collect();
+        let index = NodeSpatialIndex::build(nodes);
+        assert_eq!(index.query_box(0.0, 0.0, 20.0, 20.0).len(), 100);
+    }
+
+    // ========================================================================
+    // query_box() - Parity With Brute Force
+    // ========================================================================
+
+    #[test]
+    fn test_matches_brute_force_on_random_boxes() {
+        let mut rng = Lcg(0x5eed);
+        let nodes = random_nodes(&mut rng, 2_000);
+        let index = NodeSpatialIndex::build(nodes.iter().copied());
+
+        for _ in 0..500 {
+            let x = rng.range(-6000.0, 6000.0);
+            let y = rng.range(-6000.0, 6000.0);
+            let w = rng.range(0.0, 3000.0);
+            let h = rng.range(0.0, 3000.0);
+            assert_eq!(
+                index.query_box(x, y, w, h),
+                nodes_in_selection_box(x, y, w, h, nodes.iter().copied()),
+                "box ({x}, {y}, {w}, {h})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force_with_degenerate_boxes() {
+        let mut rng = Lcg(42);
+        let nodes = random_nodes(&mut rng, 500);
+        let index = NodeSpatialIndex::build(nodes.iter().copied());
+
+        for (x, y, w, h) in [
+            (0.0, 0.0, 0.0, 0.0),
+            (100.0, 100.0, -500.0, -500.0),
+            (-1.0e9, -1.0e9, 2.0e9, 2.0e9),
+            (f32::NAN, 0.0, 10.0, 10.0),
+        ] {
+            assert_eq!(
+                index.query_box(x, y, w, h),
+                nodes_in_selection_box(x, y, w, h, nodes.iter().copied())
+            );
+        }
+    }
+
+    #[test]
+    fn test_nodes_outside_root_bounds_still_found() {
+        let nodes = [
+            SimpleNodeGeometry { id: 1, x: 0.0, y: 0.0, width: 10.0, height: 10.0 },
+            SimpleNodeGeometry { id: 2, x: f32::NEG_INFINITY, y: 0.0, width: f32::INFINITY, height: 10.0 },
+        ];
+        let index = NodeSpatialIndex::build(nodes.iter().copied());
+        assert_eq!(
+            index.query_box(-5.0, -5.0, 20.0, 20.0),
+            nodes_in_selection_box(-5.0, -5.0, 20.0, 20.0, nodes.iter().copied())
+        );
+    }
+}
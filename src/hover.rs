@@ -0,0 +1,143 @@
+//! Hover bookkeeping for pins and links.
+//!
+//! Pointer-move handlers fire far more often than the hovered item changes.
+//! [`HoverState`] remembers the current pin and link under the cursor and
+//! records whether either actually changed, so the application only pushes
+//! new highlight properties to Slint on transitions.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use slint_node_editor::HoverState;
+//!
+//! let hover = Rc::new(RefCell::new(HoverState::new()));
+//! window.on_pointer_moved({
+//!     let hover = hover.clone();
+//!     let ctrl = ctrl.clone();
+//!     move |x, y| {
+//!         let mut hover = hover.borrow_mut();
+//!         let pin = ctrl.find_pin_at_screen(x, y, 8.0);
+//!         hover.set_hovered_pin((pin != 0).then_some(pin));
+//!         if hover.took_changed() {
+//!             window.set_hovered_pin(hover.hovered_pin().unwrap_or(0));
+//!         }
+//!     }
+//! });
+//! ```
+
+/// The pin and link currently under the pointer.
+#[derive(Debug, Default, Clone)]
+pub struct HoverState {
+    pin: Option<i32>,
+    link: Option<i32>,
+    /// Set by any transition, cleared by [`took_changed`](Self::took_changed)
+    changed: bool,
+}
+
+impl HoverState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hovered pin ID, if any
+    pub fn hovered_pin(&self) -> Option<i32> {
+        self.pin
+    }
+
+    /// Hovered link ID, if any
+    pub fn hovered_link(&self) -> Option<i32> {
+        self.link
+    }
+
+    /// Record the pin under the pointer (`None` when over no pin)
+    ///
+    /// Setting the same value again is not a change.
+    pub fn set_hovered_pin(&mut self, pin: Option<i32>) {
+        if self.pin != pin {
+            self.pin = pin;
+            self.changed = true;
+        }
+    }
+
+    /// Record the link under the pointer (`None` when over no link)
+    ///
+    /// Setting the same value again is not a change.
+    pub fn set_hovered_link(&mut self, link: Option<i32>) {
+        if self.link != link {
+            self.link = link;
+            self.changed = true;
+        }
+    }
+
+    /// Forget both hovered items, e.g. when the pointer leaves the editor
+    pub fn clear(&mut self) {
+        self.set_hovered_pin(None);
+        self.set_hovered_link(None);
+    }
+
+    /// Whether the hovered pin or link changed since the last call
+    ///
+    /// Resets the flag, so each transition is reported once.
+    pub fn took_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_hovers_nothing() {
+        let mut hover = HoverState::new();
+        assert_eq!(hover.hovered_pin(), None);
+        assert_eq!(hover.hovered_link(), None);
+        assert!(!hover.took_changed());
+    }
+
+    #[test]
+    fn test_transition_reported_once() {
+        let mut hover = HoverState::new();
+        hover.set_hovered_pin(Some(1001));
+        assert_eq!(hover.hovered_pin(), Some(1001));
+        assert!(hover.took_changed());
+        assert!(!hover.took_changed());
+    }
+
+    #[test]
+    fn test_repeated_identical_sets_are_not_changes() {
+        let mut hover = HoverState::new();
+        hover.set_hovered_link(Some(7));
+        hover.set_hovered_pin(None);
+        assert!(hover.took_changed());
+
+        for _ in 0..3 {
+            hover.set_hovered_link(Some(7));
+            hover.set_hovered_pin(None);
+        }
+        assert!(!hover.took_changed());
+    }
+
+    #[test]
+    fn test_changes_between_takes_coalesce() {
+        let mut hover = HoverState::new();
+        hover.set_hovered_pin(Some(1));
+        hover.set_hovered_pin(Some(2));
+        hover.set_hovered_link(Some(5));
+        assert!(hover.took_changed());
+        assert_eq!((hover.hovered_pin(), hover.hovered_link()), (Some(2), Some(5)));
+    }
+
+    #[test]
+    fn test_clear_reports_change_only_when_hovering() {
+        let mut hover = HoverState::new();
+        hover.clear();
+        assert!(!hover.took_changed());
+
+        hover.set_hovered_link(Some(3));
+        hover.took_changed();
+        hover.clear();
+        assert!(hover.took_changed());
+        assert_eq!(hover.hovered_link(), None);
+    }
+}
@@ -0,0 +1,111 @@
+//! Sequential ID allocation for nodes, pins and links.
+//!
+//! The library never invents IDs itself; the application owns its models and
+//! decides how IDs are assigned. [`IdGenerator`] replaces the usual
+//! `Rc<RefCell<i32>>` counter. It takes `&self`, so one `Rc<IdGenerator>` can
+//! be shared across Slint callbacks.
+//!
+//! # Example
+//!
+//! ```
+//! use slint_node_editor::IdGenerator;
+//!
+//! let ids = IdGenerator::new(1);
+//! assert_eq!(ids.next(), 1);
+//!
+//! // After loading a saved graph whose highest ID is 41
+//! ids.set_floor(42);
+//! assert_eq!(ids.next(), 42);
+//! ```
+
+use std::cell::Cell;
+use std::ops::Range;
+
+/// Hands out strictly increasing `i32` IDs.
+#[derive(Debug, Clone)]
+pub struct IdGenerator {
+    next: Cell<i32>,
+}
+
+impl Default for IdGenerator {
+    /// Starts at 1, leaving 0 free for the "no pin" / "no node" sentinel.
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl IdGenerator {
+    /// Create a generator whose first ID is `first`.
+    pub fn new(first: i32) -> Self {
+        Self { next: Cell::new(first) }
+    }
+
+    /// Return the next ID and advance.
+    pub fn next(&self) -> i32 {
+        let id = self.next.get();
+        self.next.set(id + 1);
+        id
+    }
+
+    /// The ID the next call to [`next`](Self::next) will return.
+    pub fn peek(&self) -> i32 {
+        self.next.get()
+    }
+
+    /// Allocate `count` consecutive IDs at once.
+    ///
+    /// Useful when pasting or importing a batch of nodes. A non-positive
+    /// `count` returns an empty range and allocates nothing.
+    pub fn reserve(&self, count: i32) -> Range<i32> {
+        let start = self.next.get();
+        let end = start + count.max(0);
+        self.next.set(end);
+        start..end
+    }
+
+    /// Make sure every future ID is at least `min`.
+    ///
+    /// Call with one past the highest ID of a loaded graph to avoid
+    /// collisions. A `min` at or below the next ID is a no-op, so IDs never go
+    /// backwards.
+    pub fn set_floor(&self, min: i32) {
+        if min > self.next.get() {
+            self.next.set(min);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_is_monotonic() {
+        let ids = IdGenerator::default();
+        let drawn: Vec<i32> = (0..5).map(|_| ids.next()).collect();
+        assert_eq!(drawn, vec![1, 2, 3, 4, 5]);
+        assert_eq!(ids.peek(), 6);
+    }
+
+    #[test]
+    fn test_reserve_allocates_consecutive_range() {
+        let ids = IdGenerator::new(10);
+        assert_eq!(ids.reserve(3), 10..13);
+        assert_eq!(ids.next(), 13);
+        assert!(ids.reserve(0).is_empty());
+        assert!(ids.reserve(-4).is_empty());
+        assert_eq!(ids.next(), 14);
+    }
+
+    #[test]
+    fn test_set_floor_raises_but_never_regresses() {
+        let ids = IdGenerator::new(1);
+        ids.set_floor(100);
+        assert_eq!(ids.next(), 100);
+
+        ids.set_floor(50);
+        assert_eq!(ids.next(), 101);
+        ids.set_floor(102);
+        assert_eq!(ids.next(), 102);
+    }
+}
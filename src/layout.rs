@@ -16,8 +16,8 @@ use crate::state::GeometryCache;
 
 /// Layout direction for the Sugiyama algorithm.
 ///
-/// Marked `#[non_exhaustive]` so additional directions (e.g. `RightToLeft`,
-/// `BottomToTop`) can be added in future versions without breaking callers.
+/// Marked `#[non_exhaustive]` so additional directions can be added in future
+/// versions without breaking callers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[non_exhaustive]
 pub enum Direction {
@@ -26,6 +26,56 @@ pub enum Direction {
     TopToBottom,
     /// Layers flow left to right.
     LeftToRight,
+    /// Layers flow right to left (mirror of [`LeftToRight`](Self::LeftToRight)).
+    RightToLeft,
+    /// Layers flow bottom to top (mirror of [`TopToBottom`](Self::TopToBottom)).
+    BottomToTop,
+}
+
+impl Direction {
+    /// Whether layers are stacked along the x axis.
+    pub(crate) fn is_horizontal(self) -> bool {
+        matches!(self, Direction::LeftToRight | Direction::RightToLeft)
+    }
+
+    /// Whether layers run against the axis and positions must be mirrored.
+    pub(crate) fn is_reversed(self) -> bool {
+        matches!(self, Direction::RightToLeft | Direction::BottomToTop)
+    }
+}
+
+/// Mirror positions along the layer axis (x if `horizontal`, else y).
+///
+/// `size` returns a node's `(width, height)`. The layout's extent along the
+/// axis is preserved, so a layout starting at 0 still starts at 0.
+///
+/// Returns the sum of the extent's ends; a point `a` on the axis mirrors to
+/// `sum - a`.
+pub(crate) fn mirror_layers(
+    positions: &mut [NodePosition],
+    horizontal: bool,
+    size: impl Fn(i32) -> (f64, f64),
+) -> f64 {
+    let axis_extent = |pos: &NodePosition| {
+        let (w, h) = size(pos.id);
+        if horizontal { (pos.x, w) } else { (pos.y, h) }
+    };
+    let (mut lo, mut hi) = (f64::INFINITY, f64::NEG_INFINITY);
+    for pos in positions.iter() {
+        let (start, len) = axis_extent(pos);
+        lo = lo.min(start);
+        hi = hi.max(start + len);
+    }
+    for pos in positions.iter_mut() {
+        let (start, len) = axis_extent(pos);
+        let mirrored = lo + hi - (start + len);
+        if horizontal {
+            pos.x = mirrored;
+        } else {
+            pos.y = mirrored;
+        }
+    }
+    lo + hi
 }
 
 /// A positioned node returned by [`sugiyama_layout`].
@@ -53,6 +103,26 @@ pub struct SugiyamaConfig {
     pub dummy_vertices: bool,
     /// Layout direction (default: [`Direction::TopToBottom`]).
     pub direction: Direction,
+    /// Whether crossing minimization runs the transpose refinement, which
+    /// repeatedly swaps neighbouring vertices after the barycenter sweeps
+    /// (default: `None`, which uses the `rust-sugiyama` default).
+    ///
+    /// `Some(false)` runs the sweeps alone, which is noticeably faster on
+    /// large graphs at the cost of more crossings.
+    pub transpose: Option<bool>,
+}
+
+/// Waypoints for one edge, returned by [`sugiyama_layout_with_routes`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeRoute {
+    /// Source node ID, as passed in `edges`.
+    pub source: i32,
+    /// Target node ID, as passed in `edges`.
+    pub target: i32,
+    /// Points the link should pass through, ordered from source to target.
+    ///
+    /// Empty for edges between adjacent layers.
+    pub waypoints: Vec<(f64, f64)>,
 }
 
 /// Compute Sugiyama hierarchical layout positions.
@@ -69,11 +139,216 @@ pub fn sugiyama_layout(
     node_sizes: &[(i32, (f64, f64))],
     config: &SugiyamaConfig,
 ) -> Vec<NodePosition> {
+    run_sugiyama(edges, node_sizes, config, false).0
+}
+
+/// Compute Sugiyama layout positions plus a polyline route for each edge.
+///
+/// Like [`sugiyama_layout`], but edges spanning several layers also get the
+/// positions of the dummy vertices the algorithm inserted in between. Drawing
+/// a link through these waypoints (e.g. with
+/// [`generate_waypoint_path`](crate::generate_waypoint_path)) keeps it in the
+/// gaps between nodes instead of cutting across intermediate layers.
+///
+/// Dummy vertices are always enabled for this call, whatever
+/// `config.dummy_vertices` says. `rust-sugiyama` does not report which edge a
+/// dummy belongs to, so each long edge claims, layer by layer, the free dummy
+/// closest to its previous point.
+///
+/// Returns one [`EdgeRoute`] per edge whose endpoints are both in
+/// `node_sizes`, in the order of `edges`.
+pub fn sugiyama_layout_with_routes(
+    edges: &[(i32, i32)],
+    node_sizes: &[(i32, (f64, f64))],
+    config: &SugiyamaConfig,
+) -> (Vec<NodePosition>, Vec<EdgeRoute>) {
+    let config = SugiyamaConfig {
+        dummy_vertices: true,
+        ..*config
+    };
+    run_sugiyama(edges, node_sizes, &config, true)
+}
+
+/// Re-run the Sugiyama layout while keeping `pinned` nodes where they are.
+///
+/// `pinned` holds `(node_id, (x, y))` positions the caller wants to keep,
+/// e.g. nodes the user has placed by hand. `rust-sugiyama` cannot fix
+/// vertices, so the whole graph is laid out first and then each connected
+/// component is translated by the average offset between its pinned nodes'
+/// laid-out and given positions. Pinned nodes are then set to exactly their
+/// given positions; the other nodes keep their layout positions relative to
+/// that anchor. Components without a pinned node use the average offset
+/// over all pinned nodes. Finally any unpinned node that overlaps a pinned
+/// node or an earlier unpinned node is moved to the nearest free spot, so
+/// pins that disagree with the layout never stack nodes on top of each other.
+///
+/// Pinned IDs missing from `node_sizes` are ignored. With nothing pinned this
+/// is the same as [`sugiyama_layout`].
+pub fn sugiyama_layout_incremental(
+    edges: &[(i32, i32)],
+    node_sizes: &[(i32, (f64, f64))],
+    pinned: &[(i32, (f64, f64))],
+    config: &SugiyamaConfig,
+) -> Vec<NodePosition> {
+    let mut positions = sugiyama_layout(edges, node_sizes, config);
+    let index: HashMap<i32, usize> =
+        positions.iter().enumerate().map(|(i, p)| (p.id, i)).collect();
+    let anchors: HashMap<i32, (f64, f64)> = pinned
+        .iter()
+        .copied()
+        .filter(|(id, _)| index.contains_key(id))
+        .collect();
+    if anchors.is_empty() {
+        return positions;
+    }
+
+    // Union-find over laid-out nodes to find connected components
+    let mut parent: Vec<usize> = (0..positions.len()).collect();
+    for (src, dst) in edges {
+        if let (Some(&a), Some(&b)) = (index.get(src), index.get(dst)) {
+            let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+            parent[ra] = rb;
+        }
+    }
+
+    // Sum of (given - laid out) per component, plus the overall sum
+    let mut offsets: HashMap<usize, (f64, f64, f64)> = HashMap::new();
+    let mut total = (0.0, 0.0, 0.0);
+    for (&id, &(x, y)) in &anchors {
+        let i = index[&id];
+        let (dx, dy) = (x - positions[i].x, y - positions[i].y);
+        let entry = offsets.entry(root(&mut parent, i)).or_insert((0.0, 0.0, 0.0));
+        *entry = (entry.0 + dx, entry.1 + dy, entry.2 + 1.0);
+        total = (total.0 + dx, total.1 + dy, total.2 + 1.0);
+    }
+
+    for (i, pos) in positions.iter_mut().enumerate() {
+        let (sum_x, sum_y, n) = offsets.get(&root(&mut parent, i)).copied().unwrap_or(total);
+        match anchors.get(&pos.id) {
+            Some(&(x, y)) => (pos.x, pos.y) = (x, y),
+            None => (pos.x, pos.y) = (pos.x + sum_x / n, pos.y + sum_y / n),
+        }
+    }
+
+    let spacing = if config.vertex_spacing > 0.0 { config.vertex_spacing } else { 10.0 };
+    separate_from_pinned(&mut positions, node_sizes, &anchors, spacing);
+    positions
+}
+
+/// Placement attempts per node in [`separate_from_pinned`] before it is left
+/// where the last attempt put it.
+const SEPARATION_ROUNDS: usize = 8;
+
+/// Move unpinned nodes off pinned nodes and off each other, keeping pins fixed.
+///
+/// Pinned nodes are placed first; unpinned nodes follow in layout order. A
+/// node that overlaps an already placed one (within `spacing`) is moved to
+/// the closest position just outside one of the rectangles it hits, like
+/// [`GraphLogic::resolve_overlaps`](crate::graph::GraphLogic::resolve_overlaps)
+/// but trying all four sides so a node wedged between two pins can escape.
+fn separate_from_pinned(
+    positions: &mut [NodePosition],
+    node_sizes: &[(i32, (f64, f64))],
+    pinned: &HashMap<i32, (f64, f64)>,
+    spacing: f64,
+) {
+    let mut sizes: HashMap<i32, (f64, f64)> = HashMap::new();
+    for &(id, size) in node_sizes {
+        sizes.entry(id).or_insert(size);
+    }
+    let rect = |pos: &NodePosition| {
+        let (w, h) = sizes.get(&pos.id).copied().unwrap_or((0.0, 0.0));
+        (pos.x, pos.y, w, h)
+    };
+    let overlaps = |a: (f64, f64, f64, f64), b: (f64, f64, f64, f64)| {
+        let pen_x = (a.0 + a.2 + spacing - b.0).min(b.0 + b.2 + spacing - a.0);
+        let pen_y = (a.1 + a.3 + spacing - b.1).min(b.1 + b.3 + spacing - a.1);
+        pen_x > 1e-6 && pen_y > 1e-6
+    };
+
+    let mut placed: Vec<(f64, f64, f64, f64)> = positions
+        .iter()
+        .filter(|pos| pinned.contains_key(&pos.id))
+        .map(rect)
+        .collect();
+    for pos in positions.iter_mut().filter(|pos| !pinned.contains_key(&pos.id)) {
+        let start = rect(pos);
+        let mut current = start;
+        for _ in 0..SEPARATION_ROUNDS {
+            let hits: Vec<_> = placed.iter().copied().filter(|&o| overlaps(current, o)).collect();
+            if hits.is_empty() {
+                break;
+            }
+            let (x, y, w, h) = current;
+            let candidates = hits.iter().flat_map(|&(ox, oy, ow, oh)| {
+                [
+                    (ox - w - spacing, y),
+                    (ox + ow + spacing, y),
+                    (x, oy - h - spacing),
+                    (x, oy + oh + spacing),
+                ]
+            });
+            let distance = |&(cx, cy): &(f64, f64)| (cx - start.0).hypot(cy - start.1);
+            let by_distance = |a: &(f64, f64), b: &(f64, f64)| distance(a).total_cmp(&distance(b));
+            let free = candidates
+                .clone()
+                .filter(|&(cx, cy)| placed.iter().all(|&o| !overlaps((cx, cy, w, h), o)))
+                .min_by(by_distance);
+            // With no free spot next to the hits, step to the nearest one and retry
+            let (done, (cx, cy)) = match free {
+                Some(spot) => (true, spot),
+                None => (false, candidates.min_by(by_distance).unwrap_or((x, y))),
+            };
+            current = (cx, cy, w, h);
+            if done {
+                break;
+            }
+        }
+        (pos.x, pos.y) = (current.0, current.1);
+        placed.push(current);
+    }
+}
+
+/// Union-find root of `i`, halving the path on the way.
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// `rust-sugiyama` configuration for `config`; unset fields keep the library defaults
+fn sugiyama_config(config: &SugiyamaConfig) -> rust_sugiyama::configure::Config {
+    let mut sg_config = rust_sugiyama::configure::Config {
+        dummy_vertices: config.dummy_vertices,
+        ..Default::default()
+    };
+    if config.vertex_spacing > 0.0 {
+        sg_config.vertex_spacing = config.vertex_spacing;
+    }
+    if config.minimum_length > 0 {
+        sg_config.minimum_length = config.minimum_length;
+    }
+    if let Some(transpose) = config.transpose {
+        sg_config.transpose = transpose;
+    }
+    sg_config
+}
+
+/// Shared implementation of [`sugiyama_layout`] and
+/// [`sugiyama_layout_with_routes`]; routes are only traced if `with_routes`.
+fn run_sugiyama(
+    edges: &[(i32, i32)],
+    node_sizes: &[(i32, (f64, f64))],
+    config: &SugiyamaConfig,
+    with_routes: bool,
+) -> (Vec<NodePosition>, Vec<EdgeRoute>) {
     if node_sizes.is_empty() {
-        return Vec::new();
+        return (Vec::new(), Vec::new());
     }
 
-    let horizontal = config.direction == Direction::LeftToRight;
+    let horizontal = config.direction.is_horizontal();
 
     // Build mapping: node_id -> sequential u32 index (first occurrence wins)
     let mut id_to_idx: HashMap<i32, u32> = HashMap::new();
@@ -114,22 +389,25 @@ pub fn sugiyama_layout(
         })
         .collect();
 
-    // Configure rust-sugiyama
-    let mut sg_config = rust_sugiyama::configure::Config {
-        dummy_vertices: config.dummy_vertices,
-        ..Default::default()
-    };
-    if config.vertex_spacing > 0.0 {
-        sg_config.vertex_spacing = config.vertex_spacing;
-    }
-    if config.minimum_length > 0 {
-        sg_config.minimum_length = config.minimum_length;
-    }
+    let sg_config = sugiyama_config(config);
 
     // Run layout — returns Vec<(Vec<(usize, (f64, f64))>, f64, f64)> (subgraphs)
     let subgraphs =
         rust_sugiyama::from_vertices_and_edges(&vertices, &mapped_edges, &sg_config);
 
+    let mut routes: Vec<EdgeRoute> = if with_routes {
+        mapped_edges
+            .iter()
+            .map(|&(src, dst)| EdgeRoute {
+                source: idx_to_id[src as usize],
+                target: idx_to_id[dst as usize],
+                waypoints: Vec::new(),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
     // Collect results from all subgraphs, translating indices back to node IDs.
     // For horizontal layout, swap x/y so layers run left-to-right.
     // Each subgraph is offset along the perpendicular axis so disconnected
@@ -165,17 +443,132 @@ pub fn sugiyama_layout(
             }
         }
 
+        let mut shift = 0.0;
         if start < results.len() {
             // Shift this subgraph so its top edge sits at the running offset
-            let shift = perpendicular_offset - min_perp;
+            shift = perpendicular_offset - min_perp;
             for pos in &mut results[start..] {
                 pos.y += shift;
             }
             perpendicular_offset += max_perp - min_perp + spacing;
         }
+
+        if with_routes {
+            for (edge, waypoints) in trace_dummy_routes(layout, idx_to_id.len(), &mapped_edges) {
+                routes[edge].waypoints = waypoints
+                    .into_iter()
+                    .map(|(x, y)| if horizontal { (y, x + shift) } else { (x, y + shift) })
+                    .collect();
+            }
+        }
     }
 
-    results
+    if config.direction.is_reversed() {
+        let mirror_sum = mirror_layers(&mut results, horizontal, |id| {
+            // vertices holds (layer-axis-swapped) sizes indexed by idx
+            let (a, b) = vertices[id_to_idx[&id] as usize].1;
+            if horizontal { (b, a) } else { (a, b) }
+        });
+        for point in routes.iter_mut().flat_map(|r| r.waypoints.iter_mut()) {
+            if horizontal {
+                point.0 = mirror_sum - point.0;
+            } else {
+                point.1 = mirror_sum - point.1;
+            }
+        }
+    }
+
+    (results, routes)
+}
+
+/// Assign the dummy vertices of one laid-out subgraph to the edges they break up.
+///
+/// Works in `rust-sugiyama` coordinates, where layers are stacked along y.
+/// Indices `>= node_count` are dummies. Returns `(edge_index, waypoints)` for
+/// every edge of this subgraph that crosses at least one dummy layer.
+fn trace_dummy_routes(
+    layout: &[(usize, (f64, f64))],
+    node_count: usize,
+    mapped_edges: &[(u32, u32)],
+) -> Vec<(usize, Vec<(f64, f64)>)> {
+    let mut nodes: HashMap<usize, (f64, f64)> = HashMap::new();
+    // Dummy layers sorted by y, each holding (x, claimed) slots
+    let mut layers: Vec<(f64, Vec<(f64, bool)>)> = Vec::new();
+    for &(idx, (x, y)) in layout {
+        if idx < node_count {
+            nodes.insert(idx, (x, y));
+        } else {
+            match layers.iter_mut().find(|(ly, _)| *ly == y) {
+                Some((_, slots)) => slots.push((x, false)),
+                None => layers.push((y, vec![(x, false)])),
+            }
+        }
+    }
+    layers.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut traced = Vec::new();
+    for (edge, &(src, dst)) in mapped_edges.iter().enumerate() {
+        let (Some(&(src_x, src_y)), Some(&(_, dst_y))) =
+            (nodes.get(&(src as usize)), nodes.get(&(dst as usize)))
+        else {
+            continue;
+        };
+        let (lo, hi) = (src_y.min(dst_y), src_y.max(dst_y));
+        let mut crossed: Vec<usize> =
+            (0..layers.len()).filter(|&i| layers[i].0 > lo && layers[i].0 < hi).collect();
+        if src_y > dst_y {
+            crossed.reverse();
+        }
+
+        let mut prev_x = src_x;
+        let mut waypoints = Vec::with_capacity(crossed.len());
+        for layer in crossed {
+            let (y, slots) = &mut layers[layer];
+            // Prefer a free slot; fall back to any if duplicates exhausted them
+            let nearest = |free_only: bool| {
+                slots
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &(_, claimed))| !(free_only && claimed))
+                    .min_by(|a, b| (a.1 .0 - prev_x).abs().total_cmp(&(b.1 .0 - prev_x).abs()))
+                    .map(|(i, _)| i)
+            };
+            if let Some(slot) = nearest(true).or_else(|| nearest(false)) {
+                slots[slot].1 = true;
+                prev_x = slots[slot].0;
+                waypoints.push((prev_x, *y));
+            }
+        }
+        if !waypoints.is_empty() {
+            traced.push((edge, waypoints));
+        }
+    }
+    traced
+}
+
+/// Shift `positions` by `(dx, dy)`.
+///
+/// Use together with [`normalize_positions`] to place a layout at a chosen
+/// origin in the editor.
+pub fn translate_positions(positions: &mut [NodePosition], dx: f64, dy: f64) {
+    for pos in positions {
+        pos.x += dx;
+        pos.y += dy;
+    }
+}
+
+/// Shift `positions` so the smallest `x` and the smallest `y` are both 0.
+///
+/// Layout algorithms return coordinates relative to their own origin, which
+/// may be negative or offset. An empty slice is left unchanged.
+pub fn normalize_positions(positions: &mut [NodePosition]) {
+    if positions.is_empty() {
+        return;
+    }
+    let (min_x, min_y) = positions
+        .iter()
+        .fold((f64::INFINITY, f64::INFINITY), |(x, y), p| (x.min(p.x), y.min(p.y)));
+    translate_positions(positions, -min_x, -min_y);
 }
 
 /// Compute Sugiyama layout using data from a [`GeometryCache`].
@@ -310,6 +703,214 @@ mod tests {
         assert_eq!(config.minimum_length, 0);
         assert!(!config.dummy_vertices);
         assert_eq!(config.direction, Direction::TopToBottom);
+        assert_eq!(config.transpose, None);
+    }
+
+    #[test]
+    fn test_transpose_sets_sugiyama_config() {
+        let library_default = rust_sugiyama::configure::Config::default().transpose;
+        assert_eq!(sugiyama_config(&SugiyamaConfig::default()).transpose, library_default);
+        for transpose in [false, true] {
+            let config = SugiyamaConfig {
+                transpose: Some(transpose),
+                ..Default::default()
+            };
+            assert_eq!(sugiyama_config(&config).transpose, transpose);
+        }
+
+        let sizes: Vec<(i32, (f64, f64))> = (1..=6).map(|id| (id, (80.0, 40.0))).collect();
+        let edges = vec![(1, 4), (1, 5), (2, 4), (2, 6), (3, 5), (3, 6)];
+        let config = SugiyamaConfig {
+            transpose: Some(false),
+            ..Default::default()
+        };
+        let pos = pos_map(sugiyama_layout(&edges, &sizes, &config));
+        assert_eq!(pos.len(), 6);
+        assert!(pos[&1].1 < pos[&4].1);
+    }
+
+    #[test]
+    fn test_right_to_left_places_source_right_of_target() {
+        let sizes = vec![(1, (100.0, 50.0)), (2, (100.0, 50.0))];
+        let config = SugiyamaConfig {
+            direction: Direction::RightToLeft,
+            ..Default::default()
+        };
+        let pos = pos_map(sugiyama_layout(&[(1, 2)], &sizes, &config));
+        assert!(pos[&1].0 > pos[&2].0, "source should be right of target");
+
+        // Same extent as the left-to-right layout, just mirrored
+        let ltr = SugiyamaConfig {
+            direction: Direction::LeftToRight,
+            ..Default::default()
+        };
+        let ltr_pos = pos_map(sugiyama_layout(&[(1, 2)], &sizes, &ltr));
+        assert_eq!(pos[&1].0, ltr_pos[&2].0);
+        assert_eq!(pos[&2].0, ltr_pos[&1].0);
+    }
+
+    #[test]
+    fn test_bottom_to_top_places_source_below_target() {
+        let sizes = vec![(1, (100.0, 50.0)), (2, (100.0, 80.0)), (3, (100.0, 50.0))];
+        let config = SugiyamaConfig {
+            direction: Direction::BottomToTop,
+            ..Default::default()
+        };
+        let pos = pos_map(sugiyama_layout(&[(1, 2), (2, 3)], &sizes, &config));
+        assert!(pos[&1].1 > pos[&2].1, "source should be below target");
+        assert!(pos[&2].1 > pos[&3].1);
+    }
+
+    #[test]
+    fn test_routes_long_edge_gets_waypoints() {
+        // 1 -> 2 -> 3 puts 3 two layers below 1, so 1 -> 3 needs a dummy
+        let sizes = vec![(1, (80.0, 40.0)), (2, (80.0, 40.0)), (3, (80.0, 40.0))];
+        let edges = vec![(1, 2), (2, 3), (1, 3)];
+        let (positions, routes) =
+            sugiyama_layout_with_routes(&edges, &sizes, &SugiyamaConfig::default());
+        assert_eq!(positions.len(), 3);
+        assert_eq!(routes.len(), 3);
+
+        let pos = pos_map(positions);
+        let long = &routes[2];
+        assert_eq!((long.source, long.target), (1, 3));
+        assert!(!long.waypoints.is_empty());
+        for &(_, y) in &long.waypoints {
+            assert!(y > pos[&1].1 && y < pos[&3].1);
+        }
+        // Edges between adjacent layers go straight
+        assert!(routes[0].waypoints.is_empty());
+        assert!(routes[1].waypoints.is_empty());
+    }
+
+    #[test]
+    fn test_routes_follow_reversed_direction() {
+        let sizes = vec![(1, (80.0, 40.0)), (2, (80.0, 40.0)), (3, (80.0, 40.0))];
+        let config = SugiyamaConfig {
+            direction: Direction::RightToLeft,
+            ..Default::default()
+        };
+        let (positions, routes) =
+            sugiyama_layout_with_routes(&[(1, 2), (2, 3), (1, 3)], &sizes, &config);
+
+        let pos = pos_map(positions);
+        let &(x, _) = routes[2].waypoints.first().unwrap();
+        assert!(x < pos[&1].0 && x > pos[&3].0);
+    }
+
+    #[test]
+    fn test_routes_skip_unknown_nodes() {
+        let sizes = vec![(1, (80.0, 40.0))];
+        let (_, routes) =
+            sugiyama_layout_with_routes(&[(1, 99)], &sizes, &SugiyamaConfig::default());
+        assert!(routes.is_empty());
+    }
+
+    #[test]
+    fn test_incremental_keeps_pinned_position() {
+        let sizes = vec![(1, (100.0, 50.0)), (2, (100.0, 50.0)), (3, (100.0, 50.0))];
+        let edges = vec![(1, 2), (1, 3)];
+        let config = SugiyamaConfig::default();
+        let full = pos_map(sugiyama_layout(&edges, &sizes, &config));
+
+        let pinned = vec![(1, (500.0, -300.0))];
+        let pos = pos_map(sugiyama_layout_incremental(&edges, &sizes, &pinned, &config));
+        assert_eq!(pos[&1], (500.0, -300.0));
+
+        // The rest of the component keeps its placement relative to the anchor
+        for id in [2, 3] {
+            assert!((pos[&id].0 - pos[&1].0 - (full[&id].0 - full[&1].0)).abs() < 1e-9);
+            assert!((pos[&id].1 - pos[&1].1 - (full[&id].1 - full[&1].1)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_incremental_multiple_pins_and_unknown_ids() {
+        let sizes = vec![(1, (100.0, 50.0)), (2, (100.0, 50.0)), (3, (100.0, 50.0))];
+        let edges = vec![(1, 2), (2, 3)];
+        let pinned = vec![(1, (0.0, 0.0)), (3, (40.0, 900.0)), (42, (1.0, 1.0))];
+        let result =
+            sugiyama_layout_incremental(&edges, &sizes, &pinned, &SugiyamaConfig::default());
+        assert_eq!(result.len(), 3);
+
+        let pos = pos_map(result);
+        assert_eq!(pos[&1], (0.0, 0.0));
+        assert_eq!(pos[&3], (40.0, 900.0));
+        assert!(!pos.contains_key(&42));
+    }
+
+    #[test]
+    fn test_incremental_two_pins_in_one_component_do_not_overlap() {
+        let sizes = vec![(1, (100.0, 50.0)), (2, (100.0, 50.0)), (3, (100.0, 50.0))];
+        let edges = vec![(1, 2), (2, 3)];
+        // Pinning both ends of the chain closer together than the layout
+        // spacing would put node 2 (shifted by the average offset) on both
+        let pinned = vec![(1, (0.0, 0.0)), (3, (0.0, 60.0))];
+        let result =
+            sugiyama_layout_incremental(&edges, &sizes, &pinned, &SugiyamaConfig::default());
+        assert_eq!(result.len(), 3);
+
+        let pos = pos_map(result);
+        assert_eq!(pos[&1], (0.0, 0.0));
+        assert_eq!(pos[&3], (0.0, 60.0));
+        for a in [1, 2, 3] {
+            for b in [1, 2, 3] {
+                if a < b {
+                    let (pa, pb) = (pos[&a], pos[&b]);
+                    let overlap = pa.0 < pb.0 + 100.0
+                        && pb.0 < pa.0 + 100.0
+                        && pa.1 < pb.1 + 50.0
+                        && pb.1 < pa.1 + 50.0;
+                    assert!(!overlap, "nodes {a} and {b} overlap: {pa:?} {pb:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_without_pins_matches_full_layout() {
+        let sizes = vec![(1, (100.0, 50.0)), (2, (100.0, 50.0))];
+        let config = SugiyamaConfig::default();
+        assert_eq!(
+            sugiyama_layout_incremental(&[(1, 2)], &sizes, &[], &config),
+            sugiyama_layout(&[(1, 2)], &sizes, &config)
+        );
+    }
+
+    #[test]
+    fn test_normalize_positions_moves_minimum_to_origin() {
+        let mut positions = vec![
+            NodePosition { id: 1, x: 35.0, y: -20.0 },
+            NodePosition { id: 2, x: -15.0, y: 40.0 },
+            NodePosition { id: 3, x: 5.0, y: 10.0 },
+        ];
+        normalize_positions(&mut positions);
+
+        let min_x = positions.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let min_y = positions.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+        assert_eq!((min_x, min_y), (0.0, 0.0));
+        // Relative placement is preserved
+        assert_eq!((positions[0].x, positions[0].y), (50.0, 0.0));
+        assert_eq!((positions[1].x, positions[1].y), (0.0, 60.0));
+    }
+
+    #[test]
+    fn test_normalize_sugiyama_output_then_translate() {
+        let sizes = vec![(1, (100.0, 50.0)), (2, (100.0, 50.0)), (3, (100.0, 50.0))];
+        let mut positions = sugiyama_layout(&[(1, 2), (1, 3)], &sizes, &SugiyamaConfig::default());
+        normalize_positions(&mut positions);
+        translate_positions(&mut positions, 80.0, 100.0);
+
+        let min_x = positions.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let min_y = positions.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+        assert_eq!((min_x, min_y), (80.0, 100.0));
+    }
+
+    #[test]
+    fn test_normalize_empty_is_noop() {
+        let mut positions: Vec<NodePosition> = Vec::new();
+        normalize_positions(&mut positions);
+        assert!(positions.is_empty());
     }
 
     #[test]
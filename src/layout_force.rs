@@ -0,0 +1,271 @@
+//! Force-directed (spring) graph layout.
+//!
+//! This module implements the Fruchterman–Reingold algorithm, which produces
+//! organic layouts for cyclic or undirected graphs where a layered
+//! [`sugiyama_layout`](crate::layout::sugiyama_layout) is a poor fit.
+//!
+//! Like the Sugiyama layout it uses `f64` coordinates and returns
+//! [`NodePosition`]s for the nodes' top-left corners. The initial placement is
+//! derived from [`ForceLayoutConfig::seed`], so the same input always
+//! produces the same output.
+//!
+//! Requires the `layout` feature to be enabled.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::layout::NodePosition;
+
+/// Configuration for [`force_directed_layout`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct ForceLayoutConfig {
+    /// Number of simulation steps (default: 300).
+    pub iterations: u32,
+    /// Preferred center-to-center distance between linked nodes (default: 200.0).
+    pub ideal_edge_length: f64,
+    /// Multiplier for the repulsive force between all node pairs (default: 1.0).
+    pub repulsion: f64,
+    /// Seed for the initial placement (default: 0).
+    pub seed: u64,
+}
+
+impl Default for ForceLayoutConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 300,
+            ideal_edge_length: 200.0,
+            repulsion: 1.0,
+            seed: 0,
+        }
+    }
+}
+
+/// Compute a force-directed layout.
+///
+/// Takes edges as `(source_node_id, target_node_id)` pairs and node sizes as
+/// `(node_id, (width, height))` pairs. Edge direction is ignored. The result
+/// is translated so the layout's bounding box starts at `(0, 0)`.
+///
+/// Duplicate node IDs in `node_sizes` are ignored (first occurrence wins);
+/// edges with unknown node IDs and self-loops are skipped.
+pub fn force_directed_layout(
+    edges: &[(i32, i32)],
+    node_sizes: &[(i32, (f64, f64))],
+    config: &ForceLayoutConfig,
+) -> Vec<NodePosition> {
+    let mut ids: Vec<i32> = Vec::with_capacity(node_sizes.len());
+    let mut sizes: Vec<(f64, f64)> = Vec::with_capacity(node_sizes.len());
+    let mut id_to_idx: HashMap<i32, usize> = HashMap::new();
+    for &(id, size) in node_sizes {
+        if let Entry::Vacant(entry) = id_to_idx.entry(id) {
+            entry.insert(ids.len());
+            ids.push(id);
+            sizes.push(size);
+        }
+    }
+
+    let n = ids.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let springs: Vec<(usize, usize)> = edges
+        .iter()
+        .filter_map(|&(src, dst)| {
+            let a = *id_to_idx.get(&src)?;
+            let b = *id_to_idx.get(&dst)?;
+            (a != b).then_some((a, b))
+        })
+        .collect();
+
+    let k = if config.ideal_edge_length > 0.0 {
+        config.ideal_edge_length
+    } else {
+        ForceLayoutConfig::default().ideal_edge_length
+    };
+    let repulsion = config.repulsion.max(0.0) * k * k;
+
+    // Initial placement: uniform in a square sized for the node count
+    let side = k * (n as f64).sqrt();
+    let mut rng = SplitMix64(config.seed);
+    let mut pos: Vec<(f64, f64)> = (0..n)
+        .map(|_| (rng.next_f64() * side, rng.next_f64() * side))
+        .collect();
+
+    let iterations = config.iterations.max(1);
+    let initial_temperature = side / 10.0;
+    let mut disp = vec![(0.0_f64, 0.0_f64); n];
+
+    for step in 0..iterations {
+        disp.iter_mut().for_each(|d| *d = (0.0, 0.0));
+
+        // Repulsion between every pair
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let (dx, dy, dist) = separation(pos[i], pos[j], i, j);
+                let force = repulsion / dist;
+                let (fx, fy) = (dx / dist * force, dy / dist * force);
+                disp[i].0 += fx;
+                disp[i].1 += fy;
+                disp[j].0 -= fx;
+                disp[j].1 -= fy;
+            }
+        }
+
+        // Attraction along edges
+        for &(a, b) in &springs {
+            let (dx, dy, dist) = separation(pos[a], pos[b], a, b);
+            let force = dist * dist / k;
+            let (fx, fy) = (dx / dist * force, dy / dist * force);
+            disp[a].0 -= fx;
+            disp[a].1 -= fy;
+            disp[b].0 += fx;
+            disp[b].1 += fy;
+        }
+
+        // Move, limited by a linearly cooling temperature
+        let temperature = initial_temperature * (1.0 - step as f64 / iterations as f64);
+        for (p, d) in pos.iter_mut().zip(&disp) {
+            let len = (d.0 * d.0 + d.1 * d.1).sqrt();
+            if len > 0.0 {
+                let step_len = len.min(temperature);
+                p.0 += d.0 / len * step_len;
+                p.1 += d.1 / len * step_len;
+            }
+        }
+    }
+
+    // Convert centers to top-left corners and normalize to the origin
+    let corners: Vec<(f64, f64)> = pos
+        .iter()
+        .zip(&sizes)
+        .map(|(&(cx, cy), &(w, h))| (cx - w / 2.0, cy - h / 2.0))
+        .collect();
+    let min_x = corners.iter().map(|c| c.0).fold(f64::INFINITY, f64::min);
+    let min_y = corners.iter().map(|c| c.1).fold(f64::INFINITY, f64::min);
+
+    ids.iter()
+        .zip(&corners)
+        .map(|(&id, &(x, y))| NodePosition {
+            id,
+            x: x - min_x,
+            y: y - min_y,
+        })
+        .collect()
+}
+
+/// Vector from `b` to `a` and its length, nudging coincident nodes apart
+/// in a deterministic direction.
+fn separation(a: (f64, f64), b: (f64, f64), i: usize, j: usize) -> (f64, f64, f64) {
+    let (mut dx, mut dy) = (a.0 - b.0, a.1 - b.1);
+    let mut dist = (dx * dx + dy * dy).sqrt();
+    if dist < 1e-6 {
+        let angle = (i * 31 + j * 17) as f64;
+        dx = angle.cos() * 0.01;
+        dy = angle.sin() * 0.01;
+        dist = 0.01;
+    }
+    (dx, dy, dist)
+}
+
+/// Small deterministic PRNG for the initial placement
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_f64(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos_map(positions: Vec<NodePosition>) -> HashMap<i32, (f64, f64)> {
+        positions.into_iter().map(|p| (p.id, (p.x, p.y))).collect()
+    }
+
+    fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    fn sizes(ids: &[i32]) -> Vec<(i32, (f64, f64))> {
+        ids.iter().map(|&id| (id, (80.0, 40.0))).collect()
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert!(force_directed_layout(&[], &[], &ForceLayoutConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_single_node_at_origin() {
+        let result = force_directed_layout(&[], &sizes(&[7]), &ForceLayoutConfig::default());
+        assert_eq!(result, vec![NodePosition { id: 7, x: 0.0, y: 0.0 }]);
+    }
+
+    #[test]
+    fn test_connected_nodes_closer_than_unconnected() {
+        // 1-2 and 3-4 are linked; the two pairs repel each other
+        let edges = vec![(1, 2), (3, 4)];
+        let result = force_directed_layout(&edges, &sizes(&[1, 2, 3, 4]), &ForceLayoutConfig::default());
+        let pos = pos_map(result);
+
+        let linked = distance(pos[&1], pos[&2]);
+        let unlinked = distance(pos[&1], pos[&3]);
+        assert!(
+            linked < unlinked,
+            "linked distance {} should be below unlinked distance {}",
+            linked,
+            unlinked
+        );
+    }
+
+    #[test]
+    fn test_same_seed_is_deterministic() {
+        let edges = vec![(1, 2), (2, 3), (3, 1)];
+        let config = ForceLayoutConfig {
+            seed: 42,
+            ..Default::default()
+        };
+        let a = force_directed_layout(&edges, &sizes(&[1, 2, 3]), &config);
+        let b = force_directed_layout(&edges, &sizes(&[1, 2, 3]), &config);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_different_seeds_differ() {
+        let edges = vec![(1, 2), (2, 3)];
+        let a = force_directed_layout(&edges, &sizes(&[1, 2, 3]), &ForceLayoutConfig::default());
+        let b = force_directed_layout(&edges, &sizes(&[1, 2, 3]), &ForceLayoutConfig {
+            seed: 99,
+            ..Default::default()
+        });
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_layout_is_normalized_to_origin() {
+        let edges = vec![(1, 2), (2, 3)];
+        let result = force_directed_layout(&edges, &sizes(&[1, 2, 3]), &ForceLayoutConfig::default());
+        let min_x = result.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let min_y = result.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+        assert!(min_x.abs() < 1e-9);
+        assert!(min_y.abs() < 1e-9);
+        assert!(result.iter().all(|p| p.x.is_finite() && p.y.is_finite()));
+    }
+
+    #[test]
+    fn test_unknown_edges_and_duplicate_ids() {
+        let node_sizes = vec![(1, (80.0, 40.0)), (1, (10.0, 10.0)), (2, (80.0, 40.0))];
+        let edges = vec![(1, 2), (1, 999), (2, 2)];
+        let result = force_directed_layout(&edges, &node_sizes, &ForceLayoutConfig::default());
+        assert_eq!(result.len(), 2);
+    }
+}
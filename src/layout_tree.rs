@@ -0,0 +1,322 @@
+//! Tidy tree layout for strict hierarchies.
+//!
+//! For graphs that are trees (file browsers, scene hierarchies, decision
+//! trees) this produces a compact Reingold–Tilford style layout: every depth
+//! level gets its own row, subtrees are packed as closely as `sibling_gap`
+//! allows, and each parent is centered over its children.
+//!
+//! Like the Sugiyama layout it uses `f64` coordinates and returns
+//! [`NodePosition`]s for the nodes' top-left corners.
+//!
+//! Requires the `layout` feature to be enabled.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::layout::{mirror_layers, Direction, NodePosition};
+
+/// Configuration for [`tree_layout`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct TreeLayoutConfig {
+    /// Space between consecutive depth levels (default: 60.0).
+    pub level_gap: f64,
+    /// Minimum space between neighbouring nodes on the same level (default: 20.0).
+    pub sibling_gap: f64,
+    /// Direction from root to leaves (default: [`Direction::TopToBottom`]).
+    pub direction: Direction,
+}
+
+impl Default for TreeLayoutConfig {
+    fn default() -> Self {
+        Self {
+            level_gap: 60.0,
+            sibling_gap: 20.0,
+            direction: Direction::TopToBottom,
+        }
+    }
+}
+
+/// Compute a tidy tree layout rooted at `root`.
+///
+/// Takes edges as `(parent_node_id, child_node_id)` pairs and node sizes as
+/// `(node_id, (width, height))` pairs. Children keep the order in which their
+/// edges appear. Only nodes reachable from `root` are positioned; the result
+/// is translated so the layout's bounding box starts at `(0, 0)`.
+///
+/// Returns an empty `Vec` if the input is not a tree: a node with two
+/// parents, a self-loop, an edge into `root`, or a `root` missing from
+/// `node_sizes`. Edges with unknown node IDs are skipped and duplicate node
+/// IDs in `node_sizes` are ignored (first occurrence wins).
+pub fn tree_layout(
+    root: i32,
+    edges: &[(i32, i32)],
+    node_sizes: &[(i32, (f64, f64))],
+    config: &TreeLayoutConfig,
+) -> Vec<NodePosition> {
+    let horizontal = config.direction.is_horizontal();
+
+    // (breadth, depth) extent of each node along the layout axes
+    let mut extents: HashMap<i32, (f64, f64)> = HashMap::new();
+    for &(id, (w, h)) in node_sizes {
+        if let Entry::Vacant(entry) = extents.entry(id) {
+            entry.insert(if horizontal { (h, w) } else { (w, h) });
+        }
+    }
+    if !extents.contains_key(&root) {
+        return Vec::new();
+    }
+
+    let mut children: HashMap<i32, Vec<i32>> = HashMap::new();
+    let mut has_parent: HashMap<i32, i32> = HashMap::new();
+    for &(parent, child) in edges {
+        if !extents.contains_key(&parent) || !extents.contains_key(&child) {
+            continue;
+        }
+        if parent == child || child == root || has_parent.insert(child, parent).is_some() {
+            return Vec::new();
+        }
+        children.entry(parent).or_default().push(child);
+    }
+
+    // Pre-order traversal with depths; iterative so deep chains can't overflow the stack
+    let mut order: Vec<(i32, usize)> = Vec::new();
+    let mut stack = vec![(root, 0usize)];
+    while let Some((id, depth)) = stack.pop() {
+        order.push((id, depth));
+        if let Some(kids) = children.get(&id) {
+            stack.extend(kids.iter().rev().map(|&kid| (kid, depth + 1)));
+        }
+    }
+
+    // Bottom-up: build each subtree's per-level contour (left, right) relative
+    // to its root's center, and each child's center offset from its parent.
+    let mut contours: HashMap<i32, Vec<(f64, f64)>> = HashMap::new();
+    let mut offsets: HashMap<i32, f64> = HashMap::new();
+    for &(id, _) in order.iter().rev() {
+        let half = extents[&id].0 / 2.0;
+        let kids = children.get(&id).map(Vec::as_slice).unwrap_or(&[]);
+
+        let mut merged: Vec<(f64, f64)> = Vec::new();
+        let mut placed: Vec<f64> = Vec::with_capacity(kids.len());
+        for kid in kids {
+            let contour = contours.remove(kid).unwrap_or_default();
+            let shift = if placed.is_empty() {
+                0.0
+            } else {
+                merged
+                    .iter()
+                    .zip(&contour)
+                    .map(|(&(_, right), &(left, _))| right + config.sibling_gap - left)
+                    .fold(f64::NEG_INFINITY, f64::max)
+            };
+            for (level, &(left, right)) in contour.iter().enumerate() {
+                match merged.get_mut(level) {
+                    Some(slot) => slot.1 = right + shift,
+                    None => merged.push((left + shift, right + shift)),
+                }
+            }
+            placed.push(shift);
+        }
+
+        let center = match (placed.first(), placed.last()) {
+            (Some(first), Some(last)) => (first + last) / 2.0,
+            _ => 0.0,
+        };
+        for (kid, pos) in kids.iter().zip(&placed) {
+            offsets.insert(*kid, pos - center);
+        }
+
+        let mut contour = Vec::with_capacity(merged.len() + 1);
+        contour.push((-half, half));
+        contour.extend(merged.iter().map(|&(l, r)| (l - center, r - center)));
+        contours.insert(id, contour);
+    }
+
+    // Row start along the depth axis: tallest node per level plus the gap
+    let max_depth = order.iter().map(|&(_, d)| d).max().unwrap_or(0);
+    let mut level_extent = vec![0.0_f64; max_depth + 1];
+    for &(id, depth) in &order {
+        level_extent[depth] = level_extent[depth].max(extents[&id].1);
+    }
+    let mut level_start = Vec::with_capacity(level_extent.len());
+    let mut cursor = 0.0;
+    for extent in &level_extent {
+        level_start.push(cursor);
+        cursor += extent + config.level_gap;
+    }
+
+    // Top-down: absolute centers along the breadth axis
+    let mut centers: HashMap<i32, f64> = HashMap::new();
+    centers.insert(root, 0.0);
+    for &(id, _) in &order {
+        let center = centers[&id];
+        if let Some(kids) = children.get(&id) {
+            for kid in kids {
+                centers.insert(*kid, center + offsets[kid]);
+            }
+        }
+    }
+
+    let min_breadth = order
+        .iter()
+        .map(|&(id, _)| centers[&id] - extents[&id].0 / 2.0)
+        .fold(f64::INFINITY, f64::min);
+
+    let mut positions: Vec<NodePosition> = order
+        .iter()
+        .map(|&(id, depth)| {
+            let breadth = centers[&id] - extents[&id].0 / 2.0 - min_breadth;
+            let depth_pos = level_start[depth];
+            let (x, y) = if horizontal {
+                (depth_pos, breadth)
+            } else {
+                (breadth, depth_pos)
+            };
+            NodePosition { id, x, y }
+        })
+        .collect();
+
+    if config.direction.is_reversed() {
+        mirror_layers(&mut positions, horizontal, |id| {
+            let (breadth, depth) = extents[&id];
+            if horizontal { (depth, breadth) } else { (breadth, depth) }
+        });
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos_map(positions: Vec<NodePosition>) -> HashMap<i32, (f64, f64)> {
+        positions.into_iter().map(|p| (p.id, (p.x, p.y))).collect()
+    }
+
+    fn sizes(ids: &[i32]) -> Vec<(i32, (f64, f64))> {
+        ids.iter().map(|&id| (id, (80.0, 40.0))).collect()
+    }
+
+    fn binary_tree() -> Vec<(i32, i32)> {
+        vec![(1, 2), (1, 3), (2, 4), (2, 5), (3, 6), (3, 7)]
+    }
+
+    #[test]
+    fn test_single_root() {
+        let result = tree_layout(1, &[], &sizes(&[1]), &TreeLayoutConfig::default());
+        assert_eq!(result, vec![NodePosition { id: 1, x: 0.0, y: 0.0 }]);
+    }
+
+    #[test]
+    fn test_balanced_binary_tree_is_symmetric() {
+        let result = tree_layout(
+            1,
+            &binary_tree(),
+            &sizes(&[1, 2, 3, 4, 5, 6, 7]),
+            &TreeLayoutConfig::default(),
+        );
+        let pos = pos_map(result);
+        assert_eq!(pos.len(), 7);
+
+        let root_x = pos[&1].0;
+        assert!((root_x - pos[&2].0 - (pos[&3].0 - root_x)).abs() < 1e-9);
+        assert!((root_x - pos[&4].0 - (pos[&7].0 - root_x)).abs() < 1e-9);
+        assert!((root_x - pos[&5].0 - (pos[&6].0 - root_x)).abs() < 1e-9);
+
+        // Leaves are packed with exactly the sibling gap between them
+        assert!((pos[&5].0 - pos[&4].0 - 100.0).abs() < 1e-9);
+        assert!((pos[&6].0 - pos[&5].0 - 100.0).abs() < 1e-9);
+        assert!((pos[&4].0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rows_follow_depth() {
+        let result = tree_layout(
+            1,
+            &binary_tree(),
+            &sizes(&[1, 2, 3, 4, 5, 6, 7]),
+            &TreeLayoutConfig::default(),
+        );
+        let pos = pos_map(result);
+        assert_eq!(pos[&1].1, 0.0);
+        assert_eq!(pos[&2].1, 100.0);
+        assert_eq!(pos[&3].1, 100.0);
+        assert_eq!(pos[&7].1, 200.0);
+    }
+
+    #[test]
+    fn test_parent_centered_over_children() {
+        // Uneven subtree: node 2 has three children, node 3 is a leaf
+        let edges = vec![(1, 2), (1, 3), (2, 4), (2, 5), (2, 6)];
+        let result = tree_layout(1, &edges, &sizes(&[1, 2, 3, 4, 5, 6]), &TreeLayoutConfig::default());
+        let pos = pos_map(result);
+        assert!((pos[&2].0 - pos[&5].0).abs() < 1e-9);
+        assert!(((pos[&2].0 + pos[&3].0) / 2.0 - pos[&1].0).abs() < 1e-9);
+        // Leaf 3 sits to the right of 2 without overlapping it
+        assert!(pos[&3].0 >= pos[&2].0 + 80.0 + 20.0 - 1e-9);
+    }
+
+    #[test]
+    fn test_left_to_right_swaps_axes() {
+        let config = TreeLayoutConfig {
+            direction: Direction::LeftToRight,
+            ..Default::default()
+        };
+        let result = tree_layout(1, &[(1, 2), (1, 3)], &sizes(&[1, 2, 3]), &config);
+        let pos = pos_map(result);
+        assert_eq!(pos[&1].0, 0.0);
+        assert_eq!(pos[&2].0, 140.0);
+        assert_eq!(pos[&2].0, pos[&3].0);
+        assert!((pos[&3].1 - pos[&2].1 - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_right_to_left_mirrors_depth_axis() {
+        let config = TreeLayoutConfig {
+            direction: Direction::RightToLeft,
+            ..Default::default()
+        };
+        let result = tree_layout(1, &[(1, 2), (1, 3)], &sizes(&[1, 2, 3]), &config);
+        let pos = pos_map(result);
+        assert_eq!(pos[&1].0, 140.0);
+        assert_eq!(pos[&2].0, 0.0);
+        assert_eq!(pos[&2].0, pos[&3].0);
+    }
+
+    #[test]
+    fn test_two_parents_is_rejected() {
+        let edges = vec![(1, 2), (1, 3), (2, 4), (3, 4)];
+        let result = tree_layout(1, &edges, &sizes(&[1, 2, 3, 4]), &TreeLayoutConfig::default());
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_edge_into_root_is_rejected() {
+        let edges = vec![(1, 2), (2, 1)];
+        let result = tree_layout(1, &edges, &sizes(&[1, 2]), &TreeLayoutConfig::default());
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_root_is_empty() {
+        let result = tree_layout(99, &[(1, 2)], &sizes(&[1, 2]), &TreeLayoutConfig::default());
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_unreachable_nodes_are_omitted() {
+        let result = tree_layout(1, &[(1, 2), (3, 4)], &sizes(&[1, 2, 3, 4]), &TreeLayoutConfig::default());
+        let ids: Vec<i32> = result.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_deep_chain_does_not_overflow() {
+        let ids: Vec<i32> = (0..2_000).collect();
+        let edges: Vec<(i32, i32)> = ids.windows(2).map(|w| (w[0], w[1])).collect();
+        let result = tree_layout(0, &edges, &sizes(&ids), &TreeLayoutConfig::default());
+        assert_eq!(result.len(), 2_000);
+        assert!(result.iter().all(|p| p.x == 0.0));
+    }
+}
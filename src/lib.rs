@@ -50,12 +50,27 @@
 //! This crate provides Rust helper functions for common operations:
 //!
 //! - [`generate_grid_commands`] - Generate SVG path for grid rendering
+//! - [`generate_adaptive_grid_commands`] - Zoom-adaptive grid with major/minor lines
 //! - [`generate_bezier_path`] - Generate SVG path for bezier curves
+//! - [`generate_bezier_path_biased`] - Bezier curves with vertical or automatic [`BezierBias`]
+//! - [`generate_bezier_path_dir`] - Bezier curves leaving and entering pins along explicit tangents
+//! - [`generate_orthogonal_path`] - Generate SVG path for Manhattan-routed links
+//! - [`generate_waypoint_path`] - Generate SVG path for links routed through waypoints
 //! - [`find_pin_at`] - Hit-test pins at screen coordinates
 //! - [`find_link_at`] - Hit-test links at screen coordinates
+//! - [`find_links_at_sorted`] - All links near a point, closest first
+//! - [`find_link_at_detailed`] - Link hit with the closest point and curve parameter
 //! - [`GeometryCache`] - Cache node and pin geometry for fast lookups
+//! - [`NodeSpatialIndex`] - Quadtree for box selection over large graphs
+//! - [`nodes_in_polygon`] - Lasso (freeform polygon) selection
 //! - [`SelectionManager`] - Manage selection state with O(1) lookups
 //! - [`GraphLogic`] - Helper for managing node graph state
+//! - [`LinkManager`] / [`LinkPathProvider`] - Link paths with switchable bezier, orthogonal or straight routing
+//! - [`compute_minimap_viewport_rect`] / [`minimap_click_to_pan`] - Minimap indicator and navigation math
+//! - [`to_dot`] - Export graph topology as GraphViz DOT for debugging
+//! - [`UndoStack`] / [`EditCommand`] - Undo/redo history (also built into the controller)
+//! - [`IdGenerator`] - Collision-free node, pin and link IDs
+//! - [`HoverState`] - Hovered pin/link tracking with change detection
 //!
 //! ## Limitations
 //!
@@ -77,32 +92,77 @@ pub mod selection;
 pub mod graph;
 pub mod tracking;
 pub mod links;
+pub mod minimap;
 pub mod controller;
 pub mod setup;
+pub mod export;
+pub mod undo;
+pub mod id;
+pub mod hover;
 #[cfg(feature = "layout")]
 pub mod layout;
+#[cfg(feature = "layout")]
+pub mod layout_force;
+#[cfg(feature = "layout")]
+pub mod layout_tree;
 
 // Re-export traits and functions
 pub use hit_test::{
-    find_link_at, find_pin_at, links_in_selection_box, nodes_in_selection_box, LinkGeometry,
-    NodeGeometry, PinGeometry, SimpleLinkGeometry, SimpleNodeGeometry,
+    find_link_at, find_link_at_detailed, find_links_at_sorted, find_orthogonal_link_at, find_pin_at,
+    find_straight_link_at, links_in_polygon, links_in_selection_box,
+    links_in_selection_box_with_mode,
+    nodes_enclosed_by_polygon, nodes_in_polygon, nodes_in_selection_box, point_in_polygon,
+    LinkGeometry, LinkHit, LinkSelectionMode, NodeGeometry, NodeSpatialIndex, PinGeometry, PinSide,
+    SimpleLinkGeometry, SimpleNodeGeometry,
+};
+pub use grid::{
+    generate_adaptive_grid_commands, generate_dot_grid_commands, generate_grid_commands,
+    generate_grid_commands_xy, generate_grid_with_axes, AdaptiveGrid, GridWithAxes,
 };
-pub use grid::generate_grid_commands;
-pub use path::{generate_bezier_path, generate_partial_bezier_path};
-pub use state::{GeometryCache, StoredPin};
+pub use path::{
+    bezier_end_tangent, distance_to_line_segment, generate_arrowhead, generate_bezier_path,
+    generate_bezier_path_biased, generate_bezier_path_dir, generate_link_shape_path,
+    generate_orthogonal_path, generate_partial_bezier_path, generate_self_loop_path,
+    generate_step_path, generate_waypoint_path, link_bounding_box, link_shape_bounding_box,
+    self_loop_bounds, BezierBias, CubicBezier, LinkShape, OrthogonalPath, WaypointPath,
+    DEFAULT_BEZIER_MIN_OFFSET, DEFAULT_SELF_LOOP_RADIUS,
+};
+pub use state::{GeometryCache, GraphSnapshot, StoredPin, TrackedMap};
 pub use selection::SelectionManager;
 pub use graph::{
-    GraphLogic, LinkModel, MovableNode, SimpleLink,
+    compute_alignment_guides, AlignMode, Axis, Guide, GraphLogic, LinkEnd, LinkModel, MovableNode,
+    SimpleLink,
     // Link validation framework
-    LinkValidator, BasicLinkValidator, NoDuplicatesValidator, CompositeValidator,
-    ValidationResult, ValidationError,
+    LinkValidator, BasicLinkValidator, NoDuplicatesValidator, MaxConnectionsValidator,
+    TypeValidator, AcyclicValidator, CompositeValidator,
+    ValidationCache, ValidationResult, ValidationError, ValidationMessage, validate_links_batch,
+    AsyncLinkValidator, ImmediateValidator,
 };
 pub use tracking::GeometryTracker;
-pub use links::LinkManager;
-pub use controller::NodeEditorController;
+pub use links::{
+    dash_offset_for_time, offset_parallel_links, BezierPathProvider, LinkManager, LinkPathProvider,
+    OrthogonalPathProvider, StraightLinePathProvider,
+};
+pub use minimap::{
+    compute_minimap_viewport_rect, minimap_click_to_pan, minimap_drag_to_pan_delta, minimap_scale,
+    minimap_to_world, world_to_minimap,
+};
+pub use controller::{clamp_pan, zoom_at_point, ClipboardId, NodeEditorController, PasteResult};
 pub use setup::NodeEditorSetup;
+pub use undo::{EditCommand, UndoStack};
+pub use id::IdGenerator;
+pub use hover::HoverState;
+pub use export::{to_dot, to_dot_with_labels};
+#[cfg(feature = "layout")]
+pub use layout::{
+    normalize_positions, sugiyama_layout, sugiyama_layout_from_cache, sugiyama_layout_incremental,
+    sugiyama_layout_with_routes, translate_positions, Direction, EdgeRoute, NodePosition,
+    SugiyamaConfig,
+};
+#[cfg(feature = "layout")]
+pub use layout_force::{force_directed_layout, ForceLayoutConfig};
 #[cfg(feature = "layout")]
-pub use layout::{sugiyama_layout, sugiyama_layout_from_cache, Direction, NodePosition, SugiyamaConfig};
+pub use layout_tree::{tree_layout, TreeLayoutConfig};
 
 /// Wire up all NodeEditor callbacks with a single macro call.
 ///
@@ -1,7 +1,9 @@
 //! Link management for the node editor.
 //!
 //! This module provides [`LinkManager`], which handles the synchronization
-//! between logical link data and visual link paths for rendering.
+//! between logical link data and visual link paths for rendering. How a path
+//! is drawn is decided by a [`LinkPathProvider`]: [`BezierPathProvider`] (the
+//! default), [`OrthogonalPathProvider`] or [`StraightLinePathProvider`].
 //!
 //! # Example
 //!
@@ -31,10 +33,15 @@
 //! ```
 
 use crate::graph::LinkModel;
-use crate::hit_test::NodeGeometry;
+use crate::hit_test::{NodeGeometry, PinSide};
+use crate::path::{
+    generate_link_shape_path, generate_orthogonal_path, BezierBias, LinkShape,
+    DEFAULT_BEZIER_MIN_OFFSET,
+};
 use crate::state::GeometryCache;
 use slint::{Model, SharedString, VecModel};
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 /// Internal trait for auto-syncing to Slint models.
@@ -76,6 +83,140 @@ where
     }
 }
 
+/// Routing style that turns a link's endpoints into SVG path commands.
+///
+/// [`LinkManager`] asks its provider for every link path, so swapping the
+/// provider with [`LinkManager::set_path_provider`] switches the routing
+/// style of all links at runtime. Links between two pins of the same node
+/// are always drawn as self-loops and never reach the provider.
+pub trait LinkPathProvider {
+    /// Path commands for a link from `start` to `end`.
+    ///
+    /// `sides` are the start and end pins' [`PinSide`]s, `zoom` the current
+    /// zoom level and `bezier_offset` the minimum bezier control point
+    /// offset. Providers ignore whatever does not apply to their style.
+    fn compute_path(
+        &self,
+        start: (f32, f32),
+        end: (f32, f32),
+        sides: (PinSide, PinSide),
+        zoom: f32,
+        bezier_offset: f32,
+    ) -> String;
+}
+
+/// Bezier curves as drawn by [`generate_bezier_path`](crate::generate_bezier_path),
+/// leaving pins along their [`PinSide`] when one is set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BezierPathProvider;
+
+impl LinkPathProvider for BezierPathProvider {
+    fn compute_path(
+        &self,
+        start: (f32, f32),
+        end: (f32, f32),
+        sides: (PinSide, PinSide),
+        zoom: f32,
+        bezier_offset: f32,
+    ) -> String {
+        let shape = LinkShape::default().with_sides(sides.0, sides.1);
+        generate_link_shape_path(start, end, shape, zoom, bezier_offset)
+    }
+}
+
+/// Manhattan routes as drawn by [`generate_orthogonal_path`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrthogonalPathProvider {
+    /// Radius of the rounded corners at zoom 1.0; 0.0 draws sharp corners
+    pub corner_radius: f32,
+}
+
+impl LinkPathProvider for OrthogonalPathProvider {
+    fn compute_path(
+        &self,
+        start: (f32, f32),
+        end: (f32, f32),
+        _sides: (PinSide, PinSide),
+        zoom: f32,
+        _bezier_offset: f32,
+    ) -> String {
+        generate_orthogonal_path(start.0, start.1, end.0, end.1, zoom, self.corner_radius)
+    }
+}
+
+/// Straight lines from pin to pin.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StraightLinePathProvider;
+
+impl LinkPathProvider for StraightLinePathProvider {
+    fn compute_path(
+        &self,
+        start: (f32, f32),
+        end: (f32, f32),
+        _sides: (PinSide, PinSide),
+        _zoom: f32,
+        _bezier_offset: f32,
+    ) -> String {
+        format!("M {} {} L {} {}", start.0, start.1, end.0, end.1)
+    }
+}
+
+/// Stroke dash offset for "marching ants" animation at time `t`.
+///
+/// Bind the result to the link's dash offset; as `t` grows the dashes move
+/// from the link's start towards its end at `speed` pixels per time unit.
+/// The result always lies in `[0, dash + gap)` so it never grows without
+/// bound. Returns 0.0 for a non-positive period.
+///
+/// # Arguments
+/// * `dash_pattern` - `(dash, gap)` lengths, e.g. from [`LinkModel::dash_pattern`]
+/// * `speed` - Movement speed in pixels per time unit (negative reverses)
+/// * `t` - Elapsed time, e.g. seconds since the animation started
+pub fn dash_offset_for_time(dash_pattern: (f32, f32), speed: f32, t: f32) -> f32 {
+    let period = dash_pattern.0 + dash_pattern.1;
+    if period <= 0.0 {
+        return 0.0;
+    }
+    let offset = (-speed * t).rem_euclid(period);
+    // rem_euclid can round up to exactly `period` for tiny negative inputs
+    if offset >= period { 0.0 } else { offset }
+}
+
+/// Perpendicular offsets that fan out links sharing the same endpoints.
+///
+/// Links are grouped by their endpoint pair regardless of direction. Within a
+/// group of `n` links (ordered by ID) the offsets are spaced `gap` apart and
+/// centered on zero, so three parallel links get `-gap, 0, +gap` and a lone
+/// link gets `0`. Offsets are relative to each link's own direction (positive
+/// is to the left of start→end in screen coordinates), with reversed links
+/// negated so the whole group fans out consistently.
+///
+/// # Arguments
+/// * `links` - `(link_id, start, end)` for each link, in any coordinate space
+/// * `gap` - Distance between neighbouring links in the same space
+#[allow(clippy::type_complexity)]
+pub fn offset_parallel_links(links: &[(i32, (f32, f32), (f32, f32))], gap: f32) -> HashMap<i32, f32> {
+    let key = |p: (f32, f32)| (p.0.to_bits(), p.1.to_bits());
+    let mut groups: HashMap<_, Vec<(i32, bool)>> = HashMap::new();
+    for &(id, start, end) in links {
+        let (a, b) = (key(start), key(end));
+        let reversed = b < a;
+        let pair = if reversed { (b, a) } else { (a, b) };
+        groups.entry(pair).or_default().push((id, reversed));
+    }
+
+    let mut offsets = HashMap::with_capacity(links.len());
+    for mut group in groups.into_values() {
+        group.sort_unstable_by_key(|&(id, _)| id);
+        let center = (group.len() - 1) as f32 / 2.0;
+        for (index, (id, reversed)) in group.into_iter().enumerate() {
+            let offset = (index as f32 - center) * gap;
+            offsets.insert(id, if reversed { -offset } else { offset });
+        }
+    }
+    offsets
+}
+
 /// Manages links and their visual paths for the node editor.
 ///
 /// `LinkManager` maintains a collection of links and automatically computes
@@ -115,6 +256,10 @@ pub struct LinkManager<L, N = crate::hit_test::SimpleNodeGeometry> {
     current_zoom: f32,
     /// Bezier curve offset
     bezier_offset: f32,
+    /// Routing style used for every link path without an override
+    path_provider: Box<dyn LinkPathProvider>,
+    /// Per-link routing style overrides, consulted before `path_provider`
+    link_styles: HashMap<i32, Box<dyn LinkPathProvider>>,
     /// Optional auto-sync to Slint model
     syncer: Option<Box<dyn ModelSyncer>>,
 }
@@ -145,7 +290,9 @@ where
             cache,
             paths: Rc::new(RefCell::new(Vec::new())),
             current_zoom: 1.0,
-            bezier_offset: 50.0,
+            bezier_offset: DEFAULT_BEZIER_MIN_OFFSET,
+            path_provider: Box::new(BezierPathProvider),
+            link_styles: HashMap::new(),
             syncer: None,
         }
     }
@@ -174,18 +321,20 @@ where
         self.links.push(link);
     }
 
-    /// Remove a link by ID.
+    /// Remove a link by ID, along with its routing style override.
     ///
     /// Returns `true` if a link was removed.
     pub fn remove(&mut self, id: i32) -> bool {
+        self.link_styles.remove(&id);
         let len_before = self.links.len();
         self.links.retain(|link| link.id() != id);
         self.links.len() != len_before
     }
 
-    /// Remove all links.
+    /// Remove all links and routing style overrides.
     pub fn clear(&mut self) {
         self.links.clear();
+        self.link_styles.clear();
         self.paths.borrow_mut().clear();
     }
 
@@ -216,6 +365,28 @@ where
         self.bezier_offset = offset;
     }
 
+    /// Replace the routing style (default: [`BezierPathProvider`]).
+    ///
+    /// Takes effect on the next call to [`update_paths`](Self::update_paths).
+    pub fn set_path_provider(&mut self, provider: Box<dyn LinkPathProvider>) {
+        self.path_provider = provider;
+    }
+
+    /// Route one link with its own style instead of the global provider.
+    ///
+    /// Replaces an earlier override for the same link. Takes effect on the
+    /// next call to [`update_paths`](Self::update_paths).
+    pub fn set_link_style(&mut self, link_id: i32, style: Box<dyn LinkPathProvider>) {
+        self.link_styles.insert(link_id, style);
+    }
+
+    /// Return a link to the global provider.
+    ///
+    /// Returns `true` if the link had an override.
+    pub fn clear_link_style(&mut self, link_id: i32) -> bool {
+        self.link_styles.remove(&link_id).is_some()
+    }
+
     /// Update all link paths based on current pin positions.
     ///
     /// Call this whenever:
@@ -233,15 +404,19 @@ where
         paths.clear();
 
         for link in &self.links {
-            if let Some(path) = cache.compute_link_path(
-                link.start_pin_id(),
-                link.end_pin_id(),
-                zoom,
-                self.bezier_offset,
-            ) {
+            let (start_pin, end_pin) = (link.start_pin_id(), link.end_pin_id());
+            if let Some((sx, sy, ex, ey)) = cache.resolve_link_endpoints(start_pin, end_pin) {
+                let shape = cache.link_shape(start_pin, end_pin, BezierBias::Horizontal);
+                let sides = (shape.start_side, shape.end_side);
+                let provider = self.link_styles.get(&link.id()).unwrap_or(&self.path_provider);
+                let path_commands = if shape.self_loop {
+                    generate_link_shape_path((sx, sy), (ex, ey), shape, zoom, self.bezier_offset)
+                } else {
+                    provider.compute_path((sx, sy), (ex, ey), sides, zoom, self.bezier_offset)
+                };
                 paths.push(LinkPathData {
                     id: link.id(),
-                    path_commands: path,
+                    path_commands,
                     color: link.color(),
                     line_width: link.line_width(),
                     status: link.status(),
@@ -269,6 +444,25 @@ where
     pub fn find_mut(&mut self, id: i32) -> Option<&mut L> {
         self.links.iter_mut().find(|l| l.id() == id)
     }
+
+    /// IDs of the links connecting `node_a` and `node_b`, in either direction.
+    ///
+    /// Pins are resolved to nodes through the manager's geometry cache; links
+    /// with unknown pins are skipped. Passing the same node twice returns its
+    /// self-loops. Useful for fanning out parallel links.
+    pub fn links_between_nodes(&self, node_a: i32, node_b: i32) -> Vec<i32> {
+        let cache = self.cache.borrow();
+        self.links
+            .iter()
+            .filter(|link| {
+                let start = cache.pin_positions.get(&link.start_pin_id()).map(|p| p.node_id);
+                let end = cache.pin_positions.get(&link.end_pin_id()).map(|p| p.node_id);
+                matches!((start, end), (Some(s), Some(e))
+                    if (s == node_a && e == node_b) || (s == node_b && e == node_a))
+            })
+            .map(|link| link.id())
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -380,6 +574,115 @@ mod tests {
         assert!(paths.is_empty());
     }
 
+    #[test]
+    fn test_update_paths_same_node_link_is_loop() {
+        let cache = setup_cache();
+        cache.borrow_mut().handle_pin_report(5, 1, 1, 0.0, 25.0); // Node 1 input
+        let mut manager = LinkManager::new(cache);
+        manager.set_path_provider(Box::new(OrthogonalPathProvider::default()));
+
+        manager.add(SimpleLink::new(1, 3, 5, Color::from_rgb_u8(255, 0, 0)));
+        manager.update_paths(1.0);
+
+        let shape = LinkShape::default().with_self_loop(true);
+        let expected = generate_link_shape_path((100.0, 25.0), (0.0, 25.0), shape, 1.0, 50.0);
+        assert_eq!(manager.paths.borrow()[0].path_commands, expected);
+    }
+
+    // ========================================================================
+    // Path providers
+    // ========================================================================
+
+    fn only_commands(path: &str, allowed: &[&str]) -> bool {
+        path.split(' ')
+            .filter(|token| token.parse::<f32>().is_err())
+            .all(|command| allowed.contains(&command))
+    }
+
+    #[test]
+    fn test_providers_start_with_move() {
+        let providers: [Box<dyn LinkPathProvider>; 4] = [
+            Box::new(BezierPathProvider),
+            Box::new(OrthogonalPathProvider::default()),
+            Box::new(OrthogonalPathProvider { corner_radius: 8.0 }),
+            Box::new(StraightLinePathProvider),
+        ];
+        for provider in &providers {
+            let path = provider.compute_path((100.0, 25.0), (200.0, 125.0), Default::default(), 1.0, 50.0);
+            assert!(path.starts_with("M "), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_orthogonal_provider_uses_only_lines_and_corners() {
+        for corner_radius in [0.0, 8.0] {
+            let provider = OrthogonalPathProvider { corner_radius };
+            // Forward and back-step routes
+            for end in [(200.0, 125.0), (-50.0, 300.0)] {
+                let path = provider.compute_path((100.0, 25.0), end, Default::default(), 1.0, 50.0);
+                assert!(only_commands(&path, &["M", "L", "Q"]), "{}", path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_straight_provider_is_single_segment() {
+        let path = StraightLinePathProvider.compute_path((1.0, 2.0), (3.0, 4.0), Default::default(), 1.0, 50.0);
+        assert_eq!(path, "M 1 2 L 3 4");
+    }
+
+    #[test]
+    fn test_set_path_provider_switches_style() {
+        let cache = setup_cache();
+        let mut manager = LinkManager::new(cache);
+        manager.add(SimpleLink::new(1, 3, 4, Color::from_rgb_u8(255, 0, 0)));
+
+        manager.update_paths(1.0);
+        assert!(manager.paths.borrow()[0].path_commands.contains(" C "));
+
+        manager.set_path_provider(Box::new(StraightLinePathProvider));
+        manager.update_paths(1.0);
+        assert_eq!(manager.paths.borrow()[0].path_commands, "M 100 25 L 200 125");
+    }
+
+    #[test]
+    fn test_link_style_overrides_global_provider() {
+        let cache = setup_cache();
+        let mut manager = LinkManager::new(cache);
+        for id in 1..=3 {
+            manager.add(SimpleLink::new(id, 3, 4, Color::from_rgb_u8(255, 0, 0)));
+        }
+        manager.set_link_style(2, Box::new(OrthogonalPathProvider::default()));
+        manager.update_paths(1.0);
+
+        {
+            let paths = manager.paths.borrow();
+            assert!(paths[0].path_commands.contains(" C "));
+            assert!(!paths[1].path_commands.contains(" C "));
+            assert!(only_commands(&paths[1].path_commands, &["M", "L", "Q"]));
+            assert!(paths[2].path_commands.contains(" C "));
+        }
+
+        assert!(manager.clear_link_style(2));
+        assert!(!manager.clear_link_style(2));
+        manager.update_paths(1.0);
+        assert!(manager.paths.borrow()[1].path_commands.contains(" C "));
+    }
+
+    #[test]
+    fn test_remove_drops_link_style() {
+        let cache = setup_cache();
+        let mut manager = LinkManager::new(cache);
+        manager.add(SimpleLink::new(1, 3, 4, Color::from_rgb_u8(255, 0, 0)));
+        manager.set_link_style(1, Box::new(StraightLinePathProvider));
+        manager.remove(1);
+
+        // A new link reusing the ID starts with the global style
+        manager.add(SimpleLink::new(1, 3, 4, Color::from_rgb_u8(255, 0, 0)));
+        manager.update_paths(1.0);
+        assert!(manager.paths.borrow()[0].path_commands.contains(" C "));
+    }
+
     #[test]
     fn test_find() {
         let cache = setup_cache();
@@ -446,4 +749,93 @@ mod tests {
         assert_eq!(manager.len(), 1);
         assert_eq!(manager.links()[0].label, "data flow");
     }
+
+    #[test]
+    fn test_dash_pattern_defaults_to_solid() {
+        let link = SimpleLink::new(1, 3, 4, Color::default());
+        assert_eq!(link.dash_pattern(), None);
+    }
+
+    #[test]
+    fn test_dash_offset_wraps_within_period() {
+        let pattern = (6.0, 4.0);
+        for step in 0..200 {
+            let t = step as f32 * 0.37;
+            let offset = dash_offset_for_time(pattern, 25.0, t);
+            assert!((0.0..10.0).contains(&offset), "offset {} at t={}", offset, t);
+            let reversed = dash_offset_for_time(pattern, -25.0, t);
+            assert!((0.0..10.0).contains(&reversed), "offset {} at t={}", reversed, t);
+        }
+    }
+
+    #[test]
+    fn test_dash_offset_moves_forward_and_repeats() {
+        let pattern = (6.0, 4.0);
+        assert_eq!(dash_offset_for_time(pattern, 10.0, 0.0), 0.0);
+        // Moving forward decreases the offset (mod the period)
+        assert!((dash_offset_for_time(pattern, 10.0, 0.2) - 8.0).abs() < 1e-4);
+        // One full period later the offset repeats
+        let a = dash_offset_for_time(pattern, 10.0, 0.35);
+        let b = dash_offset_for_time(pattern, 10.0, 1.35);
+        assert!((a - b).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_dash_offset_zero_period() {
+        assert_eq!(dash_offset_for_time((0.0, 0.0), 10.0, 3.0), 0.0);
+    }
+
+    #[test]
+    fn test_links_between_nodes_either_direction() {
+        let cache = setup_cache();
+        // Node 3 with pins 5 (input) and 6 (output)
+        cache.borrow_mut().update_node_rect(3, 400.0, 0.0, 100.0, 50.0);
+        cache.borrow_mut().handle_pin_report(5, 3, 1, 0.0, 25.0);
+        cache.borrow_mut().handle_pin_report(6, 3, 2, 100.0, 25.0);
+        let mut manager: LinkManager<SimpleLink> = LinkManager::new(cache);
+
+        manager.add(SimpleLink::new(1, 3, 4, Color::default()));
+        manager.add(SimpleLink::new(2, 3, 4, Color::default()));
+        manager.add(SimpleLink::new(3, 3, 5, Color::default()));
+
+        assert_eq!(manager.links_between_nodes(1, 2), vec![1, 2]);
+        assert_eq!(manager.links_between_nodes(2, 1), vec![1, 2]);
+        assert_eq!(manager.links_between_nodes(1, 3), vec![3]);
+        assert!(manager.links_between_nodes(2, 3).is_empty());
+    }
+
+    #[test]
+    fn test_links_between_nodes_skips_unknown_pins() {
+        let mut manager: LinkManager<SimpleLink> = LinkManager::new(setup_cache());
+        manager.add(SimpleLink::new(1, 3, 999, Color::default()));
+        assert!(manager.links_between_nodes(1, 2).is_empty());
+    }
+
+    #[test]
+    fn test_offset_parallel_links_three_fan_out_symmetrically() {
+        let (a, b) = ((0.0, 0.0), (200.0, 100.0));
+        let offsets = offset_parallel_links(&[(3, a, b), (1, a, b), (2, a, b)], 8.0);
+        assert_eq!(offsets[&1], -8.0);
+        assert_eq!(offsets[&2], 0.0);
+        assert_eq!(offsets[&3], 8.0);
+    }
+
+    #[test]
+    fn test_offset_parallel_links_single_and_pairs() {
+        let (a, b, c) = ((0.0, 0.0), (200.0, 100.0), (50.0, 300.0));
+        let offsets = offset_parallel_links(&[(1, a, b), (2, a, c), (3, a, c)], 10.0);
+        assert_eq!(offsets[&1], 0.0);
+        assert_eq!(offsets[&2], -5.0);
+        assert_eq!(offsets[&3], 5.0);
+    }
+
+    #[test]
+    fn test_offset_parallel_links_reversed_link_is_negated() {
+        let (a, b) = ((0.0, 0.0), (200.0, 0.0));
+        let offsets = offset_parallel_links(&[(1, a, b), (2, b, a)], 10.0);
+        // Opposite directions, so equal offsets in each link's own frame
+        // land on opposite sides in world space
+        assert_eq!(offsets[&1], -5.0);
+        assert_eq!(offsets[&2], -5.0);
+    }
 }
@@ -0,0 +1,281 @@
+//! Coordinate helpers for minimap overlays.
+//!
+//! A minimap shows the whole graph scaled down uniformly and centered in the
+//! minimap area, with a rectangle marking the part of the world the main
+//! viewport currently shows. These helpers map between the main viewport,
+//! world space and minimap space so applications can draw that rectangle and
+//! navigate when the minimap is clicked.
+//!
+//! Graph bounds are `(min_x, min_y, max_x, max_y)` in world coordinates, as
+//! returned by [`GeometryCache::graph_bounds`](crate::GeometryCache::graph_bounds).
+
+/// Compute the main viewport's visible region in minimap coordinates
+///
+/// # Arguments
+/// * `graph_bounds` - World-space bounds of the graph shown in the minimap
+/// * `viewport_w`, `viewport_h` - Main viewport size in pixels
+/// * `zoom` - Main viewport zoom level
+/// * `pan_x`, `pan_y` - Main viewport pan offset in pixels
+/// * `minimap_w`, `minimap_h` - Minimap size in pixels
+///
+/// # Returns
+/// `(x, y, width, height)` of the indicator rectangle in minimap pixels. The
+/// rectangle may extend past the minimap when the viewport shows more than the
+/// graph. Returns all zeros for a non-positive zoom.
+#[allow(clippy::too_many_arguments)]
+pub fn compute_minimap_viewport_rect(
+    graph_bounds: (f32, f32, f32, f32),
+    viewport_w: f32,
+    viewport_h: f32,
+    zoom: f32,
+    pan_x: f32,
+    pan_y: f32,
+    minimap_w: f32,
+    minimap_h: f32,
+) -> (f32, f32, f32, f32) {
+    if zoom <= 0.0 {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+    let (scale, offset_x, offset_y) = fit_transform(graph_bounds, minimap_w, minimap_h);
+
+    let visible_x = -pan_x / zoom;
+    let visible_y = -pan_y / zoom;
+    (
+        offset_x + visible_x * scale,
+        offset_y + visible_y * scale,
+        viewport_w / zoom * scale,
+        viewport_h / zoom * scale,
+    )
+}
+
+/// Compute the pan that centers the main viewport on a clicked minimap point
+///
+/// Inverse of [`compute_minimap_viewport_rect`]: the click is mapped back to
+/// world coordinates and the returned pan places that world point at the
+/// center of the main viewport at the current `zoom`.
+///
+/// # Returns
+/// `(pan_x, pan_y)` for the main viewport
+#[allow(clippy::too_many_arguments)]
+pub fn minimap_click_to_pan(
+    click_x: f32,
+    click_y: f32,
+    graph_bounds: (f32, f32, f32, f32),
+    viewport_w: f32,
+    viewport_h: f32,
+    zoom: f32,
+    minimap_w: f32,
+    minimap_h: f32,
+) -> (f32, f32) {
+    let (world_x, world_y) = minimap_to_world(click_x, click_y, graph_bounds, minimap_w, minimap_h);
+    (
+        viewport_w / 2.0 - world_x * zoom,
+        viewport_h / 2.0 - world_y * zoom,
+    )
+}
+
+/// Compute the uniform scale that fits the graph into the minimap
+///
+/// The smaller of the width and height ratios, so the whole graph is visible
+/// without distortion. Graph extents below one world unit are treated as one,
+/// and the result is always positive.
+pub fn minimap_scale(graph_bounds: (f32, f32, f32, f32), minimap_w: f32, minimap_h: f32) -> f32 {
+    let (min_x, min_y, max_x, max_y) = graph_bounds;
+    let content_w = (max_x - min_x).max(1.0);
+    let content_h = (max_y - min_y).max(1.0);
+    (minimap_w / content_w)
+        .min(minimap_h / content_h)
+        .max(f32::EPSILON)
+}
+
+/// Map a world-space point to minimap coordinates
+pub fn world_to_minimap(
+    world_x: f32,
+    world_y: f32,
+    graph_bounds: (f32, f32, f32, f32),
+    minimap_w: f32,
+    minimap_h: f32,
+) -> (f32, f32) {
+    let (scale, offset_x, offset_y) = fit_transform(graph_bounds, minimap_w, minimap_h);
+    (offset_x + world_x * scale, offset_y + world_y * scale)
+}
+
+/// Map a minimap point back to world coordinates
+///
+/// Inverse of [`world_to_minimap`].
+pub fn minimap_to_world(
+    minimap_x: f32,
+    minimap_y: f32,
+    graph_bounds: (f32, f32, f32, f32),
+    minimap_w: f32,
+    minimap_h: f32,
+) -> (f32, f32) {
+    let (scale, offset_x, offset_y) = fit_transform(graph_bounds, minimap_w, minimap_h);
+    ((minimap_x - offset_x) / scale, (minimap_y - offset_y) / scale)
+}
+
+/// Convert a drag on the minimap into a change of the main viewport's pan
+///
+/// Dragging the viewport indicator by `(dx, dy)` minimap pixels moves the
+/// visible region by `(dx, dy) / scale` world units, which is a pan change of
+/// the opposite sign scaled by `zoom`. Add the result to the pan captured when
+/// the drag started. `scale` is the value from [`minimap_scale`].
+///
+/// # Returns
+/// `(pan_dx, pan_dy)` in main viewport pixels
+pub fn minimap_drag_to_pan_delta(dx: f32, dy: f32, scale: f32, zoom: f32) -> (f32, f32) {
+    if scale <= 0.0 {
+        return (0.0, 0.0);
+    }
+    (-dx / scale * zoom, -dy / scale * zoom)
+}
+
+/// Uniform world-to-minimap transform as `(scale, offset_x, offset_y)`, so
+/// that `minimap = offset + world * scale` with the graph centered.
+fn fit_transform(
+    graph_bounds: (f32, f32, f32, f32),
+    minimap_w: f32,
+    minimap_h: f32,
+) -> (f32, f32, f32) {
+    let (min_x, min_y, max_x, max_y) = graph_bounds;
+    let scale = minimap_scale(graph_bounds, minimap_w, minimap_h);
+
+    let center_x = (min_x + max_x) / 2.0;
+    let center_y = (min_y + max_y) / 2.0;
+    (
+        scale,
+        minimap_w / 2.0 - center_x * scale,
+        minimap_h / 2.0 - center_y * scale,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: (f32, f32, f32, f32) = (0.0, 0.0, 1000.0, 500.0);
+
+    fn approx(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    // ========================================================================
+    // Viewport rect
+    // ========================================================================
+
+    #[test]
+    fn test_viewport_rect_matches_graph_when_fitted() {
+        // Viewport shows exactly the graph: 1000x500 world at zoom 1, no pan
+        let (x, y, w, h) =
+            compute_minimap_viewport_rect(BOUNDS, 1000.0, 500.0, 1.0, 0.0, 0.0, 200.0, 100.0);
+        assert!(approx(x, 0.0) && approx(y, 0.0));
+        assert!(approx(w, 200.0) && approx(h, 100.0));
+    }
+
+    #[test]
+    fn test_viewport_rect_follows_zoom_and_pan() {
+        // Zoom 2 shows a 500x250 world region starting at (250, 125)
+        let (x, y, w, h) =
+            compute_minimap_viewport_rect(BOUNDS, 1000.0, 500.0, 2.0, -500.0, -250.0, 200.0, 100.0);
+        assert!(approx(x, 50.0) && approx(y, 25.0));
+        assert!(approx(w, 100.0) && approx(h, 50.0));
+    }
+
+    #[test]
+    fn test_graph_is_centered_in_minimap() {
+        // Square minimap: graph is letterboxed vertically (scale 0.2, 100px tall)
+        let (_, y, _, h) =
+            compute_minimap_viewport_rect(BOUNDS, 1000.0, 500.0, 1.0, 0.0, 0.0, 200.0, 200.0);
+        assert!(approx(y, 50.0));
+        assert!(approx(h, 100.0));
+    }
+
+    #[test]
+    fn test_non_positive_zoom_is_empty_rect() {
+        let rect = compute_minimap_viewport_rect(BOUNDS, 800.0, 600.0, 0.0, 0.0, 0.0, 200.0, 100.0);
+        assert_eq!(rect, (0.0, 0.0, 0.0, 0.0));
+    }
+
+    // ========================================================================
+    // Click to pan
+    // ========================================================================
+
+    #[test]
+    fn test_click_at_center_centers_graph() {
+        let (viewport_w, viewport_h, zoom) = (800.0, 600.0, 1.5);
+        let (pan_x, pan_y) =
+            minimap_click_to_pan(100.0, 50.0, BOUNDS, viewport_w, viewport_h, zoom, 200.0, 100.0);
+
+        // Graph center (500, 250) lands on the viewport center
+        assert!(approx(500.0 * zoom + pan_x, viewport_w / 2.0));
+        assert!(approx(250.0 * zoom + pan_y, viewport_h / 2.0));
+    }
+
+    #[test]
+    fn test_click_centers_indicator_on_click() {
+        let (viewport_w, viewport_h, zoom) = (800.0, 600.0, 2.0);
+        let (pan_x, pan_y) =
+            minimap_click_to_pan(40.0, 70.0, BOUNDS, viewport_w, viewport_h, zoom, 200.0, 100.0);
+        let (x, y, w, h) = compute_minimap_viewport_rect(
+            BOUNDS, viewport_w, viewport_h, zoom, pan_x, pan_y, 200.0, 100.0,
+        );
+        assert!(approx(x + w / 2.0, 40.0));
+        assert!(approx(y + h / 2.0, 70.0));
+    }
+
+    #[test]
+    fn test_degenerate_bounds_do_not_produce_nan() {
+        let point = (100.0, 100.0, 100.0, 100.0);
+        let rect = compute_minimap_viewport_rect(point, 800.0, 600.0, 1.0, 0.0, 0.0, 200.0, 100.0);
+        let pan = minimap_click_to_pan(10.0, 10.0, point, 800.0, 600.0, 1.0, 200.0, 100.0);
+        assert!(rect.0.is_finite() && rect.1.is_finite() && rect.2.is_finite() && rect.3.is_finite());
+        assert!(pan.0.is_finite() && pan.1.is_finite());
+    }
+
+    // ========================================================================
+    // Scale, point mapping and dragging
+    // ========================================================================
+
+    #[test]
+    fn test_scale_is_min_of_ratios() {
+        // Width ratio 0.2, height ratio 0.4
+        assert!(approx(minimap_scale(BOUNDS, 200.0, 200.0), 0.2));
+        // Width ratio 0.4, height ratio 0.2
+        assert!(approx(minimap_scale(BOUNDS, 400.0, 100.0), 0.2));
+        // Offset bounds only depend on extents
+        assert!(approx(minimap_scale((-500.0, 100.0, 500.0, 600.0), 200.0, 200.0), 0.2));
+    }
+
+    #[test]
+    fn test_point_round_trip() {
+        let bounds = (-300.0, 50.0, 700.0, 450.0);
+        for &(x, y) in &[(-300.0, 50.0), (0.0, 0.0), (123.5, 321.25), (700.0, 450.0)] {
+            let (mx, my) = world_to_minimap(x, y, bounds, 240.0, 160.0);
+            let (wx, wy) = minimap_to_world(mx, my, bounds, 240.0, 160.0);
+            assert!(approx(wx, x) && approx(wy, y), "({}, {}) -> ({}, {})", x, y, wx, wy);
+        }
+    }
+
+    #[test]
+    fn test_world_to_minimap_corners() {
+        assert_eq!(world_to_minimap(0.0, 0.0, BOUNDS, 200.0, 100.0), (0.0, 0.0));
+        assert_eq!(world_to_minimap(1000.0, 500.0, BOUNDS, 200.0, 100.0), (200.0, 100.0));
+    }
+
+    #[test]
+    fn test_drag_moves_indicator_with_pointer() {
+        let (viewport_w, viewport_h, zoom) = (800.0, 600.0, 2.0);
+        let (pan_x, pan_y) = (-300.0, -100.0);
+        let scale = minimap_scale(BOUNDS, 200.0, 100.0);
+        let before = compute_minimap_viewport_rect(
+            BOUNDS, viewport_w, viewport_h, zoom, pan_x, pan_y, 200.0, 100.0,
+        );
+
+        let (dx, dy) = minimap_drag_to_pan_delta(15.0, -5.0, scale, zoom);
+        let after = compute_minimap_viewport_rect(
+            BOUNDS, viewport_w, viewport_h, zoom, pan_x + dx, pan_y + dy, 200.0, 100.0,
+        );
+        assert!(approx(after.0 - before.0, 15.0));
+        assert!(approx(after.1 - before.1, -5.0));
+    }
+}
@@ -1,7 +1,144 @@
+use crate::hit_test::PinSide;
+
+/// Axis along which bezier control points extend from a link's endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BezierBias {
+    /// Control points extend left/right, for pins on the sides of nodes
+    #[default]
+    Horizontal,
+    /// Control points extend up/down, for top-to-bottom flow diagrams
+    Vertical,
+    /// Pick per link from the dominant axis of the endpoint delta
+    Auto,
+}
+
+impl BezierBias {
+    /// Whether control points extend vertically for a link with delta `(dx, dy)`
+    fn is_vertical(self, dx: f32, dy: f32) -> bool {
+        match self {
+            BezierBias::Horizontal => false,
+            BezierBias::Vertical => true,
+            BezierBias::Auto => dy.abs() > dx.abs(),
+        }
+    }
+}
+
+/// Everything besides its endpoints that decides the curve drawn for a link
+///
+/// [`GeometryCache`](crate::GeometryCache) fills this in from the cached pins
+/// and hit testing reads it through
+/// [`LinkGeometry::shape`](crate::hit_test::LinkGeometry::shape), so a link
+/// is hit-tested against the same curve it is drawn with. Build one with
+/// `LinkShape::default()` and the `with_*` methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct LinkShape {
+    /// Node edge of the start pin
+    pub start_side: PinSide,
+    /// Node edge of the end pin
+    pub end_side: PinSide,
+    /// Axis of the control points at a [`PinSide::Auto`] end
+    pub bias: BezierBias,
+    /// Both pins are on the same node, so the link is drawn as a loop above
+    /// them (see [`CubicBezier::self_loop_between`]) instead of a bezier
+    pub self_loop: bool,
+}
+
+impl LinkShape {
+    /// Same shape with the given pin sides
+    pub fn with_sides(mut self, start_side: PinSide, end_side: PinSide) -> Self {
+        self.start_side = start_side;
+        self.end_side = end_side;
+        self
+    }
+
+    /// Same shape with the given bias
+    pub fn with_bias(mut self, bias: BezierBias) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    /// Same shape, drawn as a self-loop when `self_loop` is true
+    pub fn with_self_loop(mut self, self_loop: bool) -> Self {
+        self.self_loop = self_loop;
+        self
+    }
+
+    /// Whether a link from `start` to `end` is drawn as a loop: it is a
+    /// self-loop shape and both endpoints are finite
+    fn draws_loop(&self, start: (f32, f32), end: (f32, f32)) -> bool {
+        self.self_loop && all_finite(&[start.0, start.1, end.0, end.1])
+    }
+
+    /// Outward directions at the start and end pins of a link from `start`
+    /// to `end`, as taken by [`generate_bezier_path_dir`] and
+    /// [`generate_waypoint_path`]
+    ///
+    /// Each pin leaves along its side; a [`PinSide::Auto`] end uses the
+    /// direction the bias picks for the whole link.
+    pub fn pin_directions(&self, start: (f32, f32), end: (f32, f32)) -> ((f32, f32), (f32, f32)) {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        self.directions(dx, dy).unwrap_or_else(|| bias_directions(self.bias, dx, dy))
+    }
+
+    /// Tangent directions at the start and end pins for endpoint delta `(dx, dy)`
+    ///
+    /// Returns `None` when both sides are [`PinSide::Auto`], where the curve
+    /// is the plain biased one.
+    fn directions(&self, dx: f32, dy: f32) -> Option<((f32, f32), (f32, f32))> {
+        match (self.start_side.direction(), self.end_side.direction()) {
+            (None, None) => None,
+            (start_dir, end_dir) => {
+                let (auto_start, auto_end) = bias_directions(self.bias, dx, dy);
+                Some((start_dir.unwrap_or(auto_start), end_dir.unwrap_or(auto_end)))
+            }
+        }
+    }
+}
+
+/// Generate SVG path command for a link with the given [`LinkShape`]
+///
+/// With both sides [`PinSide::Auto`] this is exactly
+/// [`generate_bezier_path_biased`]; otherwise it is
+/// [`generate_bezier_path_dir`] with the sides' directions, and an `Auto` end
+/// uses the direction the bias would have picked. Self-loop shapes are drawn
+/// with [`CubicBezier::self_loop_between`], using `min_offset` as the loop
+/// radius; other links with coincident endpoints stay degenerate, as in
+/// [`generate_bezier_path`].
+pub fn generate_link_shape_path(
+    start: (f32, f32),
+    end: (f32, f32),
+    shape: LinkShape,
+    zoom: f32,
+    min_offset: f32,
+) -> String {
+    if shape.draws_loop(start, end) {
+        if !all_finite(&[zoom, min_offset]) {
+            return String::new();
+        }
+        return curve_path(&CubicBezier::self_loop_between(start, end, min_offset, zoom));
+    }
+    match shape.directions(end.0 - start.0, end.1 - start.1) {
+        None => generate_bezier_path_biased(
+            start.0,
+            start.1,
+            end.0,
+            end.1,
+            zoom,
+            min_offset,
+            shape.bias,
+        ),
+        Some((start_dir, end_dir)) => {
+            generate_bezier_path_dir(start, start_dir, end, end_dir, zoom, min_offset)
+        }
+    }
+}
+
 /// Generate SVG path command for a bezier link between two points
 ///
 /// Creates a horizontal-biased cubic bezier curve suitable for node connections.
-/// Control points extend horizontally from start and end points.
+/// Control points extend horizontally from start and end points. Use
+/// [`generate_bezier_path_biased`] for vertical layouts.
 ///
 /// # Arguments
 /// * `start_x`, `start_y` - Start point (pin center)
@@ -10,7 +147,8 @@
 /// * `min_offset` - Minimum control point offset (default: 50.0)
 ///
 /// # Returns
-/// SVG path command string (e.g., "M 10 20 C 60 20 90 80 140 80")
+/// SVG path command string (e.g., "M 10 20 C 60 20 90 80 140 80"), or an
+/// empty string if any input is NaN or infinite
 pub fn generate_bezier_path(
     start_x: f32,
     start_y: f32,
@@ -19,6 +157,75 @@ pub fn generate_bezier_path(
     zoom: f32,
     min_offset: f32,
 ) -> String {
+    generate_bezier_path_biased(
+        start_x,
+        start_y,
+        end_x,
+        end_y,
+        zoom,
+        min_offset,
+        BezierBias::Horizontal,
+    )
+}
+
+/// Generate SVG path command for a bezier link with control points along `bias`
+///
+/// Same as [`generate_bezier_path`], except that with [`BezierBias::Vertical`]
+/// (or [`BezierBias::Auto`] on a mostly vertical link) the control points are
+/// offset in y instead of x.
+pub fn generate_bezier_path_biased(
+    start_x: f32,
+    start_y: f32,
+    end_x: f32,
+    end_y: f32,
+    zoom: f32,
+    min_offset: f32,
+    bias: BezierBias,
+) -> String {
+    let (start_dir, end_dir) = bias_directions(bias, end_x - start_x, end_y - start_y);
+    generate_bezier_path_dir(
+        (start_x, start_y),
+        start_dir,
+        (end_x, end_y),
+        end_dir,
+        zoom,
+        min_offset,
+    )
+}
+
+/// Generate SVG path command for a bezier link with explicit tangent directions
+///
+/// The curve leaves `start` along `start_dir` and enters `end` coming from
+/// `end_dir`, so both are the directions pointing *away* from their node:
+/// `(1, 0)` for a pin on the right edge, `(0, -1)` for one on the top edge.
+/// Directions are normalized; a zero vector puts that control point on the
+/// endpoint. Each control point is offset by half the endpoint delta projected
+/// onto its direction, but at least `min_offset * zoom`.
+///
+/// # Arguments
+/// * `start`, `end` - Start and end points (pin centers)
+/// * `start_dir`, `end_dir` - Unit tangent vectors at the start and end pins
+/// * `zoom` - Current zoom level (affects control point offset)
+/// * `min_offset` - Minimum control point offset (default: 50.0)
+///
+/// # Returns
+/// SVG path command string, or an empty string if any input is NaN or infinite
+pub fn generate_bezier_path_dir(
+    start: (f32, f32),
+    start_dir: (f32, f32),
+    end: (f32, f32),
+    end_dir: (f32, f32),
+    zoom: f32,
+    min_offset: f32,
+) -> String {
+    let (start_x, start_y) = start;
+    let (end_x, end_y) = end;
+    if !all_finite(&[start_x, start_y, end_x, end_y, zoom, min_offset])
+        || !all_finite(&[start_dir.0, start_dir.1, end_dir.0, end_dir.1])
+    {
+        return String::new();
+    }
+
     // If distance is very small, use a straight line to avoid zig-zags
     let dx = end_x - start_x;
     let dy = end_y - start_y;
@@ -29,23 +236,12 @@ pub fn generate_bezier_path(
         return format!("M {} {} L {} {}", start_x, start_y, end_x, end_y);
     }
 
-    // Calculate control point offset (horizontal bezier)
-    let dist = dist_sq.sqrt();
-    let dx_abs = dx.abs();
-    let full_offset = (dx_abs * 0.5).max(min_offset * zoom);
-
     // Smoothly ramp up offset based on distance so the transition from the
     // linear fallback is seamless. At the threshold the offset is ~0 (nearly
     // linear) and it reaches the full value at 4× the threshold.
-    let ramp = ((dist - threshold) / (3.0 * threshold)).clamp(0.0, 1.0);
-    let offset = full_offset * ramp;
-
-    // Control points extend horizontally, following the direction of dx
-    let sign = if dx >= 0.0 { 1.0 } else { -1.0 };
-    let ctrl1_x = start_x + sign * offset;
-    let ctrl1_y = start_y;
-    let ctrl2_x = end_x - sign * offset;
-    let ctrl2_y = end_y;
+    let ramp = ((dist_sq.sqrt() - threshold) / (3.0 * threshold)).clamp(0.0, 1.0);
+    let (ctrl1_x, ctrl1_y) = control_point(start, start_dir, (dx, dy), zoom, min_offset, ramp);
+    let (ctrl2_x, ctrl2_y) = control_point(end, end_dir, (dx, dy), zoom, min_offset, ramp);
 
     // Generate SVG path: M (move to), C (cubic bezier)
     format!(
@@ -54,6 +250,37 @@ pub fn generate_bezier_path(
     )
 }
 
+/// Tangent directions at the start and end pins for a biased curve with
+/// endpoint delta `(dx, dy)`: along the bias axis, following the delta.
+fn bias_directions(bias: BezierBias, dx: f32, dy: f32) -> ((f32, f32), (f32, f32)) {
+    if bias.is_vertical(dx, dy) {
+        let sign = if dy >= 0.0 { 1.0 } else { -1.0 };
+        ((0.0, sign), (0.0, -sign))
+    } else {
+        let sign = if dx >= 0.0 { 1.0 } else { -1.0 };
+        ((sign, 0.0), (-sign, 0.0))
+    }
+}
+
+/// Control point for an endpoint with tangent `dir`, scaled by `ramp`
+fn control_point(
+    point: (f32, f32),
+    dir: (f32, f32),
+    delta: (f32, f32),
+    zoom: f32,
+    min_offset: f32,
+    ramp: f32,
+) -> (f32, f32) {
+    let len = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
+    if len <= f32::EPSILON {
+        return point;
+    }
+    let (ux, uy) = (dir.0 / len, dir.1 / len);
+    let along = (delta.0 * ux + delta.1 * uy).abs();
+    let offset = (along * 0.5).max(min_offset * zoom) * ramp;
+    (point.0 + ux * offset, point.1 + uy * offset)
+}
+
 /// Generate SVG path command for a partial bezier link (for animation)
 ///
 /// Uses de Casteljau's algorithm to compute the sub-curve from t=0 to t=progress.
@@ -67,7 +294,8 @@ pub fn generate_bezier_path(
 /// * `progress` - Animation progress from 0.0 to 1.0
 ///
 /// # Returns
-/// SVG path command string for the partial curve
+/// SVG path command string for the partial curve, or an empty string if any
+/// input is NaN or infinite
 pub fn generate_partial_bezier_path(
     start_x: f32,
     start_y: f32,
@@ -77,6 +305,10 @@ pub fn generate_partial_bezier_path(
     min_offset: f32,
     progress: f32,
 ) -> String {
+    if !all_finite(&[start_x, start_y, end_x, end_y, zoom, min_offset, progress]) {
+        return String::new();
+    }
+
     // Clamp progress to valid range
     let t = progress.clamp(0.0, 1.0);
 
@@ -141,6 +373,55 @@ fn lerp_point(a: (f32, f32), b: (f32, f32), t: f32) -> (f32, f32) {
     (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
 }
 
+/// Default minimum control point offset for bezier links (the `min_offset`
+/// and `bezier_min_offset` arguments)
+pub const DEFAULT_BEZIER_MIN_OFFSET: f32 = 50.0;
+
+/// Default loop radius for self-loop links, matching the default bezier offset
+///
+/// Used where no explicit radius is available, such as box selection.
+pub const DEFAULT_SELF_LOOP_RADIUS: f32 = DEFAULT_BEZIER_MIN_OFFSET;
+
+/// Generate SVG path command for a self-loop link (a node connecting to itself)
+///
+/// Draws a teardrop that leaves the pin up and to the right, rises `2 * loop_radius`
+/// above it and re-enters from the left. [`CubicBezier::from_link_shape`] builds
+/// the same curve for a [`LinkShape::self_loop`] shape using `min_offset` as the
+/// radius, so pass the bezier offset here to keep rendering and hit-testing aligned.
+///
+/// # Arguments
+/// * `pin_x`, `pin_y` - Pin center where the loop starts and ends
+/// * `loop_radius` - Loop size; the loop extends `2 * loop_radius` above the pin
+/// * `zoom` - Current zoom level (scales the loop radius)
+///
+/// # Returns
+/// SVG path command string (e.g., "M 0 0 C 100 -133.33 -100 -133.33 0 0")
+pub fn generate_self_loop_path(pin_x: f32, pin_y: f32, loop_radius: f32, zoom: f32) -> String {
+    curve_path(&CubicBezier::self_loop(pin_x, pin_y, loop_radius, zoom))
+}
+
+/// SVG path command drawing a single cubic bezier
+fn curve_path(curve: &CubicBezier) -> String {
+    format!(
+        "M {} {} C {} {} {} {} {} {}",
+        curve.p0.0, curve.p0.1, curve.p1.0, curve.p1.1,
+        curve.p2.0, curve.p2.1, curve.p3.0, curve.p3.1
+    )
+}
+
+/// Bounding box `(x, y, width, height)` of a self-loop drawn by [`generate_self_loop_path`]
+pub fn self_loop_bounds(pin_x: f32, pin_y: f32, loop_radius: f32, zoom: f32) -> (f32, f32, f32, f32) {
+    let r = loop_radius * zoom;
+    // The teardrop's widest point is r / sqrt(3) either side of the pin
+    let half_width = r / 3.0_f32.sqrt();
+    (pin_x - half_width, pin_y - 2.0 * r, 2.0 * half_width, 2.0 * r)
+}
+
+/// Whether every value is neither NaN nor infinite
+fn all_finite(values: &[f32]) -> bool {
+    values.iter().all(|v| v.is_finite())
+}
+
 /// Cubic bezier curve for distance calculations
 pub struct CubicBezier {
     pub p0: (f32, f32), // Start point
@@ -157,6 +438,14 @@ impl CubicBezier {
     /// * `end_x`, `end_y` - End point
     /// * `zoom` - Current zoom level
     /// * `min_offset` - Minimum control point offset (default: 50.0)
+    ///
+    /// Coincident endpoints give the same degenerate curve as
+    /// [`generate_bezier_path`]; use [`from_link_shape`](Self::from_link_shape)
+    /// with a [`LinkShape::self_loop`] shape for a loop.
+    ///
+    /// If any input is NaN or infinite the result is a zero-length curve at
+    /// whichever endpoint is finite (or the origin), so distance queries
+    /// stay well-defined.
     pub fn from_endpoints(
         start_x: f32,
         start_y: f32,
@@ -165,6 +454,70 @@ impl CubicBezier {
         zoom: f32,
         min_offset: f32,
     ) -> Self {
+        Self::from_endpoints_biased(
+            start_x,
+            start_y,
+            end_x,
+            end_y,
+            zoom,
+            min_offset,
+            BezierBias::Horizontal,
+        )
+    }
+
+    /// Create a bezier from endpoints using the same logic as [`generate_bezier_path_biased`]
+    pub fn from_endpoints_biased(
+        start_x: f32,
+        start_y: f32,
+        end_x: f32,
+        end_y: f32,
+        zoom: f32,
+        min_offset: f32,
+        bias: BezierBias,
+    ) -> Self {
+        let (start_dir, end_dir) = bias_directions(bias, end_x - start_x, end_y - start_y);
+        Self::from_endpoints_dir(
+            (start_x, start_y),
+            start_dir,
+            (end_x, end_y),
+            end_dir,
+            zoom,
+            min_offset,
+        )
+    }
+
+    /// Create a bezier using the same logic as [`generate_bezier_path_dir`]
+    ///
+    /// Non-finite inputs produce the same zero-length curve as
+    /// [`from_endpoints`](Self::from_endpoints).
+    pub fn from_endpoints_dir(
+        start: (f32, f32),
+        start_dir: (f32, f32),
+        end: (f32, f32),
+        end_dir: (f32, f32),
+        zoom: f32,
+        min_offset: f32,
+    ) -> Self {
+        let (start_x, start_y) = start;
+        let (end_x, end_y) = end;
+        if !all_finite(&[start_x, start_y, end_x, end_y, zoom, min_offset])
+            || !all_finite(&[start_dir.0, start_dir.1, end_dir.0, end_dir.1])
+        {
+            let point = if all_finite(&[start_x, start_y]) {
+                start
+            } else if all_finite(&[end_x, end_y]) {
+                end
+            } else {
+                (0.0, 0.0)
+            };
+            return CubicBezier {
+                p0: point,
+                p1: point,
+                p2: point,
+                p3: point,
+            };
+        }
+
         let dx = end_x - start_x;
         let dy = end_y - start_y;
         let dist_sq = dx * dx + dy * dy;
@@ -172,25 +525,79 @@ impl CubicBezier {
 
         if dist_sq < threshold * threshold {
             return CubicBezier {
-                p0: (start_x, start_y),
-                p1: (start_x, start_y),
-                p2: (end_x, end_y),
-                p3: (end_x, end_y),
+                p0: start,
+                p1: start,
+                p2: end,
+                p3: end,
             };
         }
 
-        let dist = dist_sq.sqrt();
-        let dx_abs = dx.abs();
-        let full_offset = (dx_abs * 0.5).max(min_offset * zoom);
-        let ramp = ((dist - threshold) / (3.0 * threshold)).clamp(0.0, 1.0);
-        let offset = full_offset * ramp;
+        let ramp = ((dist_sq.sqrt() - threshold) / (3.0 * threshold)).clamp(0.0, 1.0);
+        CubicBezier {
+            p0: start,
+            p1: control_point(start, start_dir, (dx, dy), zoom, min_offset, ramp),
+            p2: control_point(end, end_dir, (dx, dy), zoom, min_offset, ramp),
+            p3: end,
+        }
+    }
 
-        let sign = if dx >= 0.0 { 1.0 } else { -1.0 };
+    /// Create the curve drawn by [`generate_link_shape_path`]
+    pub fn from_link_shape(
+        start: (f32, f32),
+        end: (f32, f32),
+        shape: LinkShape,
+        zoom: f32,
+        min_offset: f32,
+    ) -> Self {
+        if shape.draws_loop(start, end) && all_finite(&[zoom, min_offset]) {
+            return Self::self_loop_between(start, end, min_offset, zoom);
+        }
+        match shape.directions(end.0 - start.0, end.1 - start.1) {
+            None => Self::from_endpoints_biased(
+                start.0,
+                start.1,
+                end.0,
+                end.1,
+                zoom,
+                min_offset,
+                shape.bias,
+            ),
+            Some((start_dir, end_dir)) => {
+                Self::from_endpoints_dir(start, start_dir, end, end_dir, zoom, min_offset)
+            }
+        }
+    }
+
+    /// Create the teardrop self-loop drawn by [`generate_self_loop_path`]
+    ///
+    /// # Arguments
+    /// * `pin_x`, `pin_y` - Pin center where the loop starts and ends
+    /// * `loop_radius` - Loop size; the loop extends `2 * loop_radius` above the pin
+    /// * `zoom` - Current zoom level
+    pub fn self_loop(pin_x: f32, pin_y: f32, loop_radius: f32, zoom: f32) -> Self {
+        Self::self_loop_between((pin_x, pin_y), (pin_x, pin_y), loop_radius, zoom)
+    }
+
+    /// Create a loop from `start` back to `end` for a link between two pins
+    /// of the same node
+    ///
+    /// Leaves `start` up and to the right and re-enters `end` from the upper
+    /// left, rising about `2 * loop_radius` above the pins. With `start == end`
+    /// this is [`CubicBezier::self_loop`].
+    pub fn self_loop_between(
+        start: (f32, f32),
+        end: (f32, f32),
+        loop_radius: f32,
+        zoom: f32,
+    ) -> Self {
+        let r = loop_radius * zoom;
+        // With both control points 8/3 r above a pin the curve peaks at 2r
+        let rise = r * 8.0 / 3.0;
         CubicBezier {
-            p0: (start_x, start_y),
-            p1: (start_x + sign * offset, start_y),
-            p2: (end_x - sign * offset, end_y),
-            p3: (end_x, end_y),
+            p0: start,
+            p1: (start.0 + 2.0 * r, start.1 - rise),
+            p2: (end.0 - 2.0 * r, end.1 - rise),
+            p3: end,
         }
     }
 
@@ -213,10 +620,493 @@ impl CubicBezier {
 
         (x, y)
     }
+
+    /// Tight axis-aligned bounding box `(x, y, width, height)` of the curve.
+    ///
+    /// Unlike the hull of the control points this only covers points the
+    /// curve actually passes through: besides the endpoints it evaluates the
+    /// curve where its derivative is zero on either axis.
+    pub fn bounding_box(&self) -> (f32, f32, f32, f32) {
+        let mut min = (self.p0.0.min(self.p3.0), self.p0.1.min(self.p3.1));
+        let mut max = (self.p0.0.max(self.p3.0), self.p0.1.max(self.p3.1));
+
+        let roots_x = derivative_roots(self.p0.0, self.p1.0, self.p2.0, self.p3.0);
+        let roots_y = derivative_roots(self.p0.1, self.p1.1, self.p2.1, self.p3.1);
+        for t in roots_x.into_iter().chain(roots_y).flatten() {
+            let (x, y) = self.eval(t);
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+
+        (min.0, min.1, max.0 - min.0, max.1 - min.1)
+    }
+}
+
+/// Parameters in (0, 1) where the derivative of a 1D cubic bezier is zero.
+fn derivative_roots(p0: f32, p1: f32, p2: f32, p3: f32) -> [Option<f32>; 2] {
+    // B'(t) / 3 = a t^2 + b t + c
+    let a = -p0 + 3.0 * p1 - 3.0 * p2 + p3;
+    let b = 2.0 * (p0 - 2.0 * p1 + p2);
+    let c = p1 - p0;
+    let inside = |t: f32| (t > 0.0 && t < 1.0).then_some(t);
+
+    if a.abs() < 1e-6 {
+        if b.abs() < 1e-6 {
+            return [None, None];
+        }
+        return [inside(-c / b), None];
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return [None, None];
+    }
+    let sqrt_d = discriminant.sqrt();
+    [inside((-b + sqrt_d) / (2.0 * a)), inside((-b - sqrt_d) / (2.0 * a))]
+}
+
+/// Bounding box `(x, y, width, height)` of the link curve drawn by [`generate_bezier_path`].
+///
+/// Uses the same control points as [`CubicBezier::from_endpoints`], so
+/// self-loops and non-finite input behave the same way. Links between pins
+/// with a fixed side need [`link_shape_bounding_box`] instead.
+pub fn link_bounding_box(
+    start: (f32, f32),
+    end: (f32, f32),
+    zoom: f32,
+    min_offset: f32,
+) -> (f32, f32, f32, f32) {
+    link_shape_bounding_box(start, end, LinkShape::default(), zoom, min_offset)
+}
+
+/// Bounding box `(x, y, width, height)` of the link curve drawn by [`generate_link_shape_path`].
+pub fn link_shape_bounding_box(
+    start: (f32, f32),
+    end: (f32, f32),
+    shape: LinkShape,
+    zoom: f32,
+    min_offset: f32,
+) -> (f32, f32, f32, f32) {
+    CubicBezier::from_link_shape(start, end, shape, zoom, min_offset).bounding_box()
+}
+
+/// Angle of the curve's direction of travel at its end point (t = 1.0)
+///
+/// Returns radians as from `atan2`, with 0.0 pointing along +x and π/2 along +y
+/// (screen down). Falls back to earlier control points when the end tangent
+/// is degenerate, e.g. for the straight-line fallback of short links.
+pub fn bezier_end_tangent(bezier: &CubicBezier) -> f32 {
+    let end = bezier.p3;
+    for from in [bezier.p2, bezier.p1, bezier.p0] {
+        let dx = end.0 - from.0;
+        let dy = end.1 - from.1;
+        if dx * dx + dy * dy > f32::EPSILON {
+            return dy.atan2(dx);
+        }
+    }
+    0.0
+}
+
+/// Generate SVG path command for a filled arrowhead triangle
+///
+/// The tip sits exactly at `(tip_x, tip_y)` and the arrow points along `angle`.
+/// Pair with [`bezier_end_tangent`] to place an arrow at the input pin
+/// pointing into the node.
+///
+/// # Arguments
+/// * `tip_x`, `tip_y` - Arrow tip (typically the input pin center)
+/// * `angle` - Direction the arrow points, in radians (0.0 = +x)
+/// * `size` - Length of the arrow from base to tip (base width is the same)
+///
+/// # Returns
+/// Closed SVG path command string (e.g., "M 100 50 L 90 45 L 90 55 Z")
+pub fn generate_arrowhead(tip_x: f32, tip_y: f32, angle: f32, size: f32) -> String {
+    let (sin, cos) = angle.sin_cos();
+    let base_x = tip_x - cos * size;
+    let base_y = tip_y - sin * size;
+    let half = size * 0.5;
+
+    // Base corners sit perpendicular to the arrow direction
+    let left = (base_x + sin * half, base_y - cos * half);
+    let right = (base_x - sin * half, base_y + cos * half);
+
+    format!(
+        "M {} {} L {} {} L {} {} Z",
+        tip_x, tip_y, left.0, left.1, right.0, right.1
+    )
+}
+
+/// Generate SVG path command for an orthogonal (Manhattan) link between two points
+///
+/// Routes the link Horizontal → Vertical → Horizontal with the vertical segment
+/// halfway between the endpoints. When the end lies behind the start
+/// (`end_x < start_x`), the route steps out of the source, doubles back and
+/// steps into the target instead of cutting across the source node.
+///
+/// # Arguments
+/// * `start_x`, `start_y` - Start point (pin center)
+/// * `end_x`, `end_y` - End point (pin center)
+/// * `zoom` - Current zoom level (scales the back-step margin and corner radius)
+/// * `corner_radius` - Radius for rounded corners (0.0 for sharp corners)
+///
+/// # Returns
+/// SVG path command string (e.g., "M 0 0 L 50 0 L 50 100 L 100 100")
+pub fn generate_orthogonal_path(
+    start_x: f32,
+    start_y: f32,
+    end_x: f32,
+    end_y: f32,
+    zoom: f32,
+    corner_radius: f32,
+) -> String {
+    let route = OrthogonalPath::from_endpoints(start_x, start_y, end_x, end_y, zoom);
+    route.to_svg(corner_radius * zoom)
+}
+
+/// Generate SVG path command for a step (staircase) link between two points
+///
+/// Always emits exactly three segments: horizontal, vertical, horizontal. The
+/// vertical segment sits at `start_x + (end_x - start_x) * step_ratio`, so the
+/// geometry depends only on the inputs and can be rebuilt for hit-testing with
+/// [`OrthogonalPath::step`]. Nothing in it has a fixed on-screen size, so
+/// unlike the bezier paths it takes no zoom.
+///
+/// # Arguments
+/// * `start_x`, `start_y` - Start point (pin center)
+/// * `end_x`, `end_y` - End point (pin center)
+/// * `step_ratio` - Position of the vertical segment, 0.0 (at start) to 1.0 (at end)
+///
+/// # Returns
+/// SVG path command string (e.g., "M 0 0 L 50 0 L 50 100 L 100 100")
+pub fn generate_step_path(
+    start_x: f32,
+    start_y: f32,
+    end_x: f32,
+    end_y: f32,
+    step_ratio: f32,
+) -> String {
+    OrthogonalPath::step(start_x, start_y, end_x, end_y, step_ratio).to_svg(0.0)
+}
+
+/// Orthogonal (Manhattan) route for distance calculations
+///
+/// Stores the corner points of the polyline produced by
+/// [`generate_orthogonal_path`]. Rounded corners are not part of the geometry;
+/// they deviate from the polyline by less than the corner radius.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrthogonalPath {
+    /// Polyline points from start to end (at least two)
+    pub points: Vec<(f32, f32)>,
+}
+
+impl OrthogonalPath {
+    /// Create a route from endpoints using the same logic as generate_orthogonal_path
+    ///
+    /// # Arguments
+    /// * `start_x`, `start_y` - Start point
+    /// * `end_x`, `end_y` - End point
+    /// * `zoom` - Current zoom level
+    pub fn from_endpoints(start_x: f32, start_y: f32, end_x: f32, end_y: f32, zoom: f32) -> Self {
+        let dy = end_y - start_y;
+        let margin = 20.0 * zoom;
+
+        if end_x >= start_x {
+            // Nearly colinear: a single straight segment
+            if dy.abs() < 1.0 * zoom {
+                return OrthogonalPath {
+                    points: vec![(start_x, start_y), (end_x, end_y)],
+                };
+            }
+
+            let mid_x = (start_x + end_x) / 2.0;
+            return OrthogonalPath {
+                points: vec![
+                    (start_x, start_y),
+                    (mid_x, start_y),
+                    (mid_x, end_y),
+                    (end_x, end_y),
+                ],
+            };
+        }
+
+        // Back-step: leave the source, run between the nodes (or below both
+        // when they share a row), then enter the target from its own side.
+        let out_x = start_x + margin;
+        let in_x = end_x - margin;
+        let mid_y = if dy.abs() < 2.0 * margin {
+            start_y.max(end_y) + 2.0 * margin
+        } else {
+            (start_y + end_y) / 2.0
+        };
+
+        OrthogonalPath {
+            points: vec![
+                (start_x, start_y),
+                (out_x, start_y),
+                (out_x, mid_y),
+                (in_x, mid_y),
+                (in_x, end_y),
+                (end_x, end_y),
+            ],
+        }
+    }
+
+    /// Create a three-segment step route using the same logic as generate_step_path
+    ///
+    /// Degenerate segments are kept, so the route always has four points.
+    pub fn step(start_x: f32, start_y: f32, end_x: f32, end_y: f32, step_ratio: f32) -> Self {
+        let step_x = start_x + (end_x - start_x) * step_ratio.clamp(0.0, 1.0);
+        OrthogonalPath {
+            points: vec![
+                (start_x, start_y),
+                (step_x, start_y),
+                (step_x, end_y),
+                (end_x, end_y),
+            ],
+        }
+    }
+
+    /// Iterator over the straight segments of the route
+    pub fn segments(&self) -> impl Iterator<Item = ((f32, f32), (f32, f32))> + '_ {
+        self.points.windows(2).map(|w| (w[0], w[1]))
+    }
+
+    /// Total length of the route
+    pub fn length(&self) -> f32 {
+        self.segments().map(|(a, b)| segment_length(a, b)).sum()
+    }
+
+    /// Evaluate the route at parameter t (0.0 to 1.0), proportional to arc length
+    pub fn eval(&self, t: f32) -> (f32, f32) {
+        let t = t.clamp(0.0, 1.0);
+        let total = self.length();
+        if total < f32::EPSILON {
+            return self.points[0];
+        }
+
+        let mut remaining = total * t;
+        for (a, b) in self.segments() {
+            let len = segment_length(a, b);
+            if remaining <= len && len > 0.0 {
+                return lerp_point(a, b, remaining / len);
+            }
+            remaining -= len;
+        }
+        self.points[self.points.len() - 1]
+    }
+
+    /// Build the SVG path, rounding each corner with a quadratic curve
+    fn to_svg(&self, corner_radius: f32) -> String {
+        let points = &self.points;
+        let (sx, sy) = points[0];
+        let mut path = format!("M {} {}", sx, sy);
+
+        for i in 1..points.len() - 1 {
+            let prev = points[i - 1];
+            let corner = points[i];
+            let next = points[i + 1];
+
+            // Clamp so adjacent corners never overlap on short segments
+            let r = corner_radius
+                .min(segment_length(prev, corner) / 2.0)
+                .min(segment_length(corner, next) / 2.0);
+
+            if r > 0.0 {
+                let before = move_towards(corner, prev, r);
+                let after = move_towards(corner, next, r);
+                path.push_str(&format!(
+                    " L {} {} Q {} {} {} {}",
+                    before.0, before.1, corner.0, corner.1, after.0, after.1
+                ));
+            } else {
+                path.push_str(&format!(" L {} {}", corner.0, corner.1));
+            }
+        }
+
+        let (ex, ey) = points[points.len() - 1];
+        path.push_str(&format!(" L {} {}", ex, ey));
+        path
+    }
+}
+
+/// Length of the segment between two points
+fn segment_length(a: (f32, f32), b: (f32, f32)) -> f32 {
+    let dx = b.0 - a.0;
+    let dy = b.1 - a.1;
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Point at `distance` from `from` in the direction of `to`
+fn move_towards(from: (f32, f32), to: (f32, f32), distance: f32) -> (f32, f32) {
+    let len = segment_length(from, to);
+    if len < f32::EPSILON {
+        return from;
+    }
+    lerp_point(from, to, distance / len)
+}
+
+/// Generate SVG path command for a link routed through waypoints
+///
+/// Chains one cubic bezier per leg (start → waypoint → … → end). The curve
+/// leaves the start pin along `start_dir` and enters the end pin against
+/// `end_dir`, even when a waypoint lies behind a pin. It passes through each
+/// waypoint with a tangent parallel to the line between its neighbours so
+/// the joins are smooth. Without waypoints this is identical to
+/// [`generate_bezier_path_dir`].
+///
+/// For the usual output-on-the-right, input-on-the-left link pass `(1.0, 0.0)`
+/// and `(-1.0, 0.0)`; [`LinkShape::pin_directions`] gives the directions for
+/// given pin sides and bias.
+///
+/// # Arguments
+/// * `start` - Start point (pin center)
+/// * `start_dir` - Unit vector pointing away from the start pin's node
+/// * `waypoints` - Intermediate points the link must pass through, in order
+/// * `end` - End point (pin center)
+/// * `end_dir` - Unit vector pointing away from the end pin's node
+/// * `zoom` - Current zoom level (affects the pin control point offset)
+/// * `min_offset` - Minimum control point offset at the pins (default: 50.0)
+///
+/// # Returns
+/// SVG path command string with one `C` command per leg
+pub fn generate_waypoint_path(
+    start: (f32, f32),
+    start_dir: (f32, f32),
+    waypoints: &[(f32, f32)],
+    end: (f32, f32),
+    end_dir: (f32, f32),
+    zoom: f32,
+    min_offset: f32,
+) -> String {
+    if waypoints.is_empty() {
+        return generate_bezier_path_dir(start, start_dir, end, end_dir, zoom, min_offset);
+    }
+
+    let route =
+        WaypointPath::from_points(start, start_dir, waypoints, end, end_dir, zoom, min_offset);
+    let mut path = format!("M {} {}", start.0, start.1);
+    for segment in &route.segments {
+        path.push_str(&format!(
+            " C {} {} {} {} {} {}",
+            segment.p1.0, segment.p1.1, segment.p2.0, segment.p2.1, segment.p3.0, segment.p3.1
+        ));
+    }
+    path
+}
+
+/// Link routed through waypoints, for distance calculations
+///
+/// Holds the bezier legs drawn by [`generate_waypoint_path`].
+pub struct WaypointPath {
+    /// One bezier per leg, from start to end (at least one)
+    pub segments: Vec<CubicBezier>,
+}
+
+impl WaypointPath {
+    /// Create a route using the same logic as [`generate_waypoint_path`]
+    ///
+    /// Without waypoints the single segment matches [`CubicBezier::from_endpoints_dir`].
+    pub fn from_points(
+        start: (f32, f32),
+        start_dir: (f32, f32),
+        waypoints: &[(f32, f32)],
+        end: (f32, f32),
+        end_dir: (f32, f32),
+        zoom: f32,
+        min_offset: f32,
+    ) -> Self {
+        if waypoints.is_empty() {
+            return WaypointPath {
+                segments: vec![CubicBezier::from_endpoints_dir(
+                    start, start_dir, end, end_dir, zoom, min_offset,
+                )],
+            };
+        }
+
+        let mut points = Vec::with_capacity(waypoints.len() + 2);
+        points.push(start);
+        points.extend_from_slice(waypoints);
+        points.push(end);
+        let last = points.len() - 1;
+
+        // Unit tangent (direction of travel) at each point: out of the start
+        // pin, into the end pin, Catmull-Rom style between
+        let unit = |(dx, dy): (f32, f32)| {
+            let len = (dx * dx + dy * dy).sqrt();
+            if len < f32::EPSILON || !len.is_finite() { (0.0, 0.0) } else { (dx / len, dy / len) }
+        };
+        let tangents: Vec<(f32, f32)> = (0..=last)
+            .map(|i| {
+                if i == 0 {
+                    unit(start_dir)
+                } else if i == last {
+                    unit((-end_dir.0, -end_dir.1))
+                } else {
+                    unit((points[i + 1].0 - points[i - 1].0, points[i + 1].1 - points[i - 1].1))
+                }
+            })
+            .collect();
+
+        let segments = (0..last)
+            .map(|i| {
+                let (a, b) = (points[i], points[i + 1]);
+                let len = segment_length(a, b);
+                // Pins use the usual bezier offset along their direction (capped by
+                // the leg); waypoints a third of the leg
+                let handle = |at: usize| {
+                    if at == 0 || at == last {
+                        let (tx, ty) = tangents[at];
+                        let along = ((b.0 - a.0) * tx + (b.1 - a.1) * ty).abs();
+                        (along * 0.5).max(min_offset * zoom).min(len)
+                    } else {
+                        len / 3.0
+                    }
+                };
+                let (h0, h1) = (handle(i), handle(i + 1));
+                CubicBezier {
+                    p0: a,
+                    p1: (a.0 + tangents[i].0 * h0, a.1 + tangents[i].1 * h0),
+                    p2: (b.0 - tangents[i + 1].0 * h1, b.1 - tangents[i + 1].1 * h1),
+                    p3: b,
+                }
+            })
+            .collect();
+
+        WaypointPath { segments }
+    }
+
+    /// Evaluate the route at parameter t (0.0 to 1.0)
+    ///
+    /// Each leg covers an equal share of t, so with `n` waypoints the
+    /// `k`-th waypoint is at `t = k / (n + 1)`.
+    pub fn eval(&self, t: f32) -> (f32, f32) {
+        let count = self.segments.len();
+        let scaled = t.clamp(0.0, 1.0) * count as f32;
+        let index = (scaled.floor() as usize).min(count - 1);
+        self.segments[index].eval(scaled - index as f32)
+    }
+}
+
+/// Distance from `point` to the line segment from `a` to `b`
+///
+/// Measured to the closest point on the segment, so beyond either end this is
+/// the distance to that endpoint rather than to the infinite line. A
+/// zero-length segment gives the distance to `a`. Useful for straight-link
+/// hit testing, lasso edge tests and snapping to guides.
+pub fn distance_to_line_segment(point: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    distance_to_line_segment_sq(point, a, b).sqrt()
 }
 
 /// Calculate squared distance from a point to a line segment
-fn distance_to_line_segment_sq(point: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+pub(crate) fn distance_to_line_segment_sq(point: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    project_onto_segment(point, a, b).1
+}
+
+/// Project a point onto a line segment
+///
+/// Returns the position of the closest point along the segment (0.0 at `a`,
+/// 1.0 at `b`) and the squared distance to it.
+fn project_onto_segment(point: (f32, f32), a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
     let ab = (b.0 - a.0, b.1 - a.1);
     let ap = (point.0 - a.0, point.1 - a.1);
 
@@ -224,7 +1114,7 @@ fn distance_to_line_segment_sq(point: (f32, f32), a: (f32, f32), b: (f32, f32))
 
     if ab_len_sq < f32::EPSILON {
         // Degenerate segment (a == b)
-        return ap.0 * ap.0 + ap.1 * ap.1;
+        return (0.0, ap.0 * ap.0 + ap.1 * ap.1);
     }
 
     // Project point onto line, clamped to segment
@@ -236,7 +1126,7 @@ fn distance_to_line_segment_sq(point: (f32, f32), a: (f32, f32), b: (f32, f32))
     // Distance squared from point to closest point
     let dx = point.0 - closest.0;
     let dy = point.1 - closest.1;
-    dx * dx + dy * dy
+    (t, dx * dx + dy * dy)
 }
 
 /// Calculate the minimum distance from a point to a cubic bezier curve
@@ -248,24 +1138,61 @@ fn distance_to_line_segment_sq(point: (f32, f32), a: (f32, f32), b: (f32, f32))
 /// * `bezier` - The bezier curve
 /// * `num_samples` - Number of samples for distance calculation (default: 20)
 pub fn distance_to_bezier(point: (f32, f32), bezier: &CubicBezier, num_samples: usize) -> f32 {
+    closest_t_on_bezier(point, bezier, num_samples).1
+}
+
+/// Find the curve parameter of the point on a cubic bezier closest to `point`
+///
+/// Uses the same sampling as [`distance_to_bezier`]: the point is projected
+/// onto each chord between samples and the projection is mapped back to a
+/// curve parameter. Evaluate the curve at the returned `t` to get the
+/// closest point.
+///
+/// # Returns
+/// `(t, distance)` with `t` in `[0.0, 1.0]`
+pub fn closest_t_on_bezier(point: (f32, f32), bezier: &CubicBezier, num_samples: usize) -> (f32, f32) {
     let num_samples = if num_samples == 0 { 20 } else { num_samples };
 
     let mut min_dist_sq = f32::MAX;
+    let mut closest_t = 0.0;
     let mut prev_point = bezier.eval(0.0);
 
     for i in 1..=num_samples {
         let t = i as f32 / num_samples as f32;
         let curr_point = bezier.eval(t);
 
-        let dist_sq = distance_to_line_segment_sq(point, prev_point, curr_point);
+        let (along, dist_sq) = project_onto_segment(point, prev_point, curr_point);
         if dist_sq < min_dist_sq {
             min_dist_sq = dist_sq;
+            closest_t = (i as f32 - 1.0 + along) / num_samples as f32;
         }
 
         prev_point = curr_point;
     }
 
-    min_dist_sq.sqrt()
+    (closest_t, min_dist_sq.sqrt())
+}
+
+/// Calculate the minimum distance from a point to an orthogonal route
+///
+/// Exact for the polyline since every segment is straight.
+pub fn distance_to_orthogonal_path(point: (f32, f32), route: &OrthogonalPath) -> f32 {
+    route
+        .segments()
+        .map(|(a, b)| distance_to_line_segment_sq(point, a, b))
+        .fold(f32::MAX, f32::min)
+        .sqrt()
+}
+
+/// Calculate the minimum distance from a point to a waypoint route
+///
+/// Samples every leg with `num_samples` as in [`distance_to_bezier`].
+pub fn distance_to_waypoint_path(point: (f32, f32), route: &WaypointPath, num_samples: usize) -> f32 {
+    route
+        .segments
+        .iter()
+        .map(|segment| distance_to_bezier(point, segment, num_samples))
+        .fold(f32::MAX, f32::min)
 }
 
 #[cfg(test)]
@@ -328,10 +1255,116 @@ mod tests {
         assert_ne!(path1, path2);
     }
 
+    #[test]
+    fn test_bezier_path_non_finite_inputs_are_empty() {
+        assert_eq!(generate_bezier_path(f32::NAN, 0.0, 100.0, 50.0, 1.0, 50.0), "");
+        assert_eq!(generate_bezier_path(0.0, 0.0, f32::INFINITY, 50.0, 1.0, 50.0), "");
+        assert_eq!(generate_bezier_path(0.0, 0.0, 100.0, f32::NEG_INFINITY, 1.0, 50.0), "");
+        assert_eq!(generate_bezier_path(0.0, 0.0, 100.0, 50.0, f32::NAN, 50.0), "");
+        assert_eq!(generate_partial_bezier_path(0.0, f32::NAN, 100.0, 50.0, 1.0, 50.0, 0.5), "");
+    }
+
+    // ========================================================================
+    // BezierBias - Vertical and Auto Control Points
+    // ========================================================================
+
+    #[test]
+    fn test_vertical_bias_offsets_control_points_in_y() {
+        let path = generate_bezier_path_biased(50.0, 0.0, 50.0, 200.0, 1.0, 50.0, BezierBias::Vertical);
+        assert_eq!(path, "M 50 0 C 50 100 50 100 50 200");
+
+        let bezier = CubicBezier::from_endpoints_biased(0.0, 0.0, 40.0, 200.0, 1.0, 50.0, BezierBias::Vertical);
+        assert_eq!(bezier.p1.0, 0.0);
+        assert!(bezier.p1.1 > 0.0);
+        assert_eq!(bezier.p2.0, 40.0);
+        assert!(bezier.p2.1 < 200.0);
+    }
+
+    #[test]
+    fn test_horizontal_bias_matches_default() {
+        let default = generate_bezier_path(0.0, 0.0, 200.0, 80.0, 1.0, 50.0);
+        let biased = generate_bezier_path_biased(0.0, 0.0, 200.0, 80.0, 1.0, 50.0, BezierBias::Horizontal);
+        assert_eq!(default, biased);
+        assert_ne!(
+            default,
+            generate_bezier_path_biased(0.0, 0.0, 200.0, 80.0, 1.0, 50.0, BezierBias::Vertical)
+        );
+    }
+
+    #[test]
+    fn test_auto_bias_follows_dominant_axis() {
+        // Mostly vertical: control points move in y
+        let tall = CubicBezier::from_endpoints_biased(0.0, 0.0, 30.0, 300.0, 1.0, 50.0, BezierBias::Auto);
+        assert_eq!(tall.p1.0, 0.0);
+        assert!(tall.p1.1 > 0.0);
+
+        // Mostly horizontal: control points move in x
+        let wide = CubicBezier::from_endpoints_biased(0.0, 0.0, 300.0, 30.0, 1.0, 50.0, BezierBias::Auto);
+        assert_eq!(wide.p1.1, 0.0);
+        assert!(wide.p1.0 > 0.0);
+    }
+
+    // ========================================================================
+    // generate_bezier_path_dir() - Explicit Tangent Directions
+    // ========================================================================
+
+    #[test]
+    fn test_bezier_dir_horizontal_matches_default() {
+        let default = generate_bezier_path(0.0, 0.0, 200.0, 80.0, 1.0, 50.0);
+        let explicit = generate_bezier_path_dir((0.0, 0.0), (1.0, 0.0), (200.0, 80.0), (-1.0, 0.0), 1.0, 50.0);
+        assert_eq!(default, explicit);
+    }
+
+    #[test]
+    fn test_bezier_dir_vertical_differs_from_default() {
+        let default = generate_bezier_path(0.0, 0.0, 200.0, 80.0, 1.0, 50.0);
+        // Output on the bottom edge, input on the top edge
+        let vertical = generate_bezier_path_dir((0.0, 0.0), (0.0, 1.0), (200.0, 80.0), (0.0, -1.0), 1.0, 50.0);
+        assert_ne!(default, vertical);
+        assert_eq!(vertical, "M 0 0 C 0 50 200 30 200 80");
+    }
+
+    #[test]
+    fn test_bezier_dir_mixed_edges() {
+        // Output on the right, input on the top: leaves along +x, enters from above
+        let bezier =
+            CubicBezier::from_endpoints_dir((0.0, 0.0), (1.0, 0.0), (200.0, 200.0), (0.0, -1.0), 1.0, 50.0);
+        assert_eq!(bezier.p1, (100.0, 0.0));
+        assert_eq!(bezier.p2, (200.0, 100.0));
+    }
+
+    #[test]
+    fn test_bezier_dir_normalizes_and_handles_zero_direction() {
+        let scaled = generate_bezier_path_dir((0.0, 0.0), (5.0, 0.0), (200.0, 0.0), (-3.0, 0.0), 1.0, 50.0);
+        let unit = generate_bezier_path_dir((0.0, 0.0), (1.0, 0.0), (200.0, 0.0), (-1.0, 0.0), 1.0, 50.0);
+        assert_eq!(scaled, unit);
+
+        let bezier =
+            CubicBezier::from_endpoints_dir((0.0, 0.0), (0.0, 0.0), (200.0, 0.0), (-1.0, 0.0), 1.0, 50.0);
+        assert_eq!(bezier.p1, (0.0, 0.0));
+
+        let nan = generate_bezier_path_dir((0.0, 0.0), (f32::NAN, 0.0), (200.0, 0.0), (-1.0, 0.0), 1.0, 50.0);
+        assert_eq!(nan, "");
+    }
+
     // ========================================================================
     // CubicBezier::from_endpoints() - Construction
     // ========================================================================
 
+    #[test]
+    fn test_from_endpoints_non_finite_is_degenerate() {
+        let bezier = CubicBezier::from_endpoints(10.0, 20.0, f32::NAN, 50.0, 1.0, 50.0);
+        for point in [bezier.p0, bezier.p1, bezier.p2, bezier.p3] {
+            assert_eq!(point, (10.0, 20.0));
+        }
+
+        let bezier = CubicBezier::from_endpoints(f32::INFINITY, 0.0, f32::NAN, 0.0, 1.0, 50.0);
+        assert_eq!(bezier.eval(0.5), (0.0, 0.0));
+
+        let distance = distance_to_bezier((3.0, 4.0), &bezier, 20);
+        assert!((distance - 5.0).abs() < 1e-4);
+    }
+
     #[test]
     fn test_bezier_from_endpoints_creates_correct_points() {
         let bezier = CubicBezier::from_endpoints(0.0, 0.0, 100.0, 100.0, 1.0, 50.0);
@@ -418,6 +1451,95 @@ mod tests {
         assert_eq!(bezier.eval(1.0), (50.0, 50.0));
     }
 
+    // ========================================================================
+    // CubicBezier::bounding_box() / link_bounding_box() - Tight Bounds
+    // ========================================================================
+
+    /// Bounding box of `samples + 1` evenly spaced points on the curve
+    fn sampled_bounds(bezier: &CubicBezier, samples: usize) -> (f32, f32, f32, f32) {
+        let points: Vec<(f32, f32)> =
+            (0..=samples).map(|i| bezier.eval(i as f32 / samples as f32)).collect();
+        let min_x = points.iter().map(|p| p.0).fold(f32::INFINITY, f32::min);
+        let min_y = points.iter().map(|p| p.1).fold(f32::INFINITY, f32::min);
+        let max_x = points.iter().map(|p| p.0).fold(f32::NEG_INFINITY, f32::max);
+        let max_y = points.iter().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max);
+        (min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+
+    fn assert_bounds_close(a: (f32, f32, f32, f32), b: (f32, f32, f32, f32), tolerance: f32) {
+        for (x, y) in [(a.0, b.0), (a.1, b.1), (a.2, b.2), (a.3, b.3)] {
+            assert!((x - y).abs() < tolerance, "{:?} vs {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_bounding_box_matches_dense_samples_for_looping_curve() {
+        // Tangents point away from the other end, so the curve overshoots
+        // both endpoints horizontally
+        let bezier = CubicBezier {
+            p0: (300.0, 100.0),
+            p1: (425.0, 100.0),
+            p2: (-75.0, 220.0),
+            p3: (50.0, 220.0),
+        };
+        let bounds = bezier.bounding_box();
+
+        assert_bounds_close(bounds, sampled_bounds(&bezier, 10_000), 0.05);
+        assert!(bounds.0 < 50.0);
+        assert!(bounds.0 + bounds.2 > 300.0);
+    }
+
+    #[test]
+    fn test_bounding_box_of_self_loop_matches_self_loop_bounds() {
+        let bezier = CubicBezier::self_loop(100.0, 100.0, 40.0, 1.0);
+        assert_bounds_close(bezier.bounding_box(), self_loop_bounds(100.0, 100.0, 40.0, 1.0), 0.01);
+    }
+
+    #[test]
+    fn test_bounding_box_straight_line_is_endpoint_box() {
+        let bezier = CubicBezier {
+            p0: (0.0, 0.0),
+            p1: (10.0, 5.0),
+            p2: (20.0, 10.0),
+            p3: (30.0, 15.0),
+        };
+        assert_bounds_close(bezier.bounding_box(), (0.0, 0.0, 30.0, 15.0), 1e-4);
+    }
+
+    #[test]
+    fn test_link_bounding_box_uses_link_curve() {
+        let bounds = link_bounding_box((0.0, 0.0), (200.0, 100.0), 1.0, 50.0);
+        let bezier = CubicBezier::from_endpoints(0.0, 0.0, 200.0, 100.0, 1.0, 50.0);
+
+        assert_eq!(bounds, bezier.bounding_box());
+        assert_bounds_close(bounds, (0.0, 0.0, 200.0, 100.0), 1e-3);
+    }
+
+    // ========================================================================
+    // distance_to_line_segment() - Point to Segment Distance
+    // ========================================================================
+
+    #[test]
+    fn test_distance_to_line_segment_on_segment_is_zero() {
+        assert_eq!(distance_to_line_segment((50.0, 50.0), (0.0, 0.0), (100.0, 100.0)), 0.0);
+        assert_eq!(distance_to_line_segment((0.0, 0.0), (0.0, 0.0), (100.0, 100.0)), 0.0);
+    }
+
+    #[test]
+    fn test_distance_to_line_segment_perpendicular() {
+        assert_eq!(distance_to_line_segment((40.0, 30.0), (0.0, 0.0), (100.0, 0.0)), 30.0);
+        assert_eq!(distance_to_line_segment((-7.0, 60.0), (0.0, 100.0), (0.0, 0.0)), 7.0);
+    }
+
+    #[test]
+    fn test_distance_to_line_segment_beyond_endpoint() {
+        // Nearest point is the end (100, 0), not (130, 0) on the infinite line
+        assert_eq!(distance_to_line_segment((130.0, 40.0), (0.0, 0.0), (100.0, 0.0)), 50.0);
+        assert_eq!(distance_to_line_segment((-3.0, -4.0), (0.0, 0.0), (100.0, 0.0)), 5.0);
+        // Degenerate segment
+        assert_eq!(distance_to_line_segment((3.0, 4.0), (0.0, 0.0), (0.0, 0.0)), 5.0);
+    }
+
     // ========================================================================
     // distance_to_bezier() - Distance Calculation
     // ========================================================================
@@ -460,6 +1582,19 @@ mod tests {
         assert!(dist > 90.0);
     }
 
+    #[test]
+    fn test_closest_t_on_horizontal_bezier() {
+        let bezier = CubicBezier::from_endpoints(0.0, 0.0, 100.0, 0.0, 1.0, 50.0);
+        let (t, dist) = closest_t_on_bezier((50.0, 5.0), &bezier, 20);
+        assert!((t - 0.5).abs() < 0.01, "t = {}", t);
+        assert!((dist - 5.0).abs() < 0.5);
+
+        let (t, _) = closest_t_on_bezier((-20.0, 0.0), &bezier, 20);
+        assert_eq!(t, 0.0);
+        let (t, _) = closest_t_on_bezier((130.0, 0.0), &bezier, 20);
+        assert_eq!(t, 1.0);
+    }
+
     #[test]
     fn test_distance_to_bezier_zero_samples_uses_default() {
         let bezier = CubicBezier::from_endpoints(0.0, 0.0, 100.0, 0.0, 1.0, 50.0);
@@ -520,6 +1655,350 @@ mod tests {
         assert!(dist < 1.0);
     }
 
+    // ========================================================================
+    // generate_self_loop_path() / CubicBezier::self_loop() - Self-Loops
+    // ========================================================================
+
+    #[test]
+    fn test_self_loop_path_starts_and_ends_at_pin() {
+        let path = generate_self_loop_path(10.0, 20.0, 30.0, 1.0);
+        assert!(path.starts_with("M 10 20 C"));
+        assert!(path.ends_with("10 20"));
+    }
+
+    #[test]
+    fn test_self_loop_peaks_at_twice_radius() {
+        let loop_curve = CubicBezier::self_loop(0.0, 0.0, 30.0, 1.0);
+        let top = loop_curve.eval(0.5);
+        assert!(top.0.abs() < 0.001);
+        assert!((top.1 + 60.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_self_loop_scales_with_zoom() {
+        let loop_curve = CubicBezier::self_loop(0.0, 0.0, 30.0, 2.0);
+        assert!((loop_curve.eval(0.5).1 + 120.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_coincident_endpoints_loop_only_for_self_loop_shape() {
+        // Without the shape flag hit-testing sees the degenerate curve that is drawn
+        let bezier = CubicBezier::from_endpoints(50.0, 50.0, 50.0, 50.0, 1.0, 30.0);
+        assert_eq!((bezier.p1, bezier.p2), ((50.0, 50.0), (50.0, 50.0)));
+        assert!(distance_to_bezier((50.0, -10.0), &bezier, 20) > 59.0);
+        let plain = LinkShape::default();
+        let path = generate_link_shape_path((50.0, 50.0), (50.0, 50.0), plain, 1.0, 30.0);
+        assert_eq!(path, generate_bezier_path(50.0, 50.0, 50.0, 50.0, 1.0, 30.0));
+
+        let shape = LinkShape::default().with_self_loop(true);
+        let bezier = CubicBezier::from_link_shape((50.0, 50.0), (50.0, 50.0), shape, 1.0, 30.0);
+        let expected = CubicBezier::self_loop(50.0, 50.0, 30.0, 1.0);
+        assert_eq!((bezier.p1, bezier.p2), (expected.p1, expected.p2));
+        assert!(distance_to_bezier((50.0, -10.0), &bezier, 20) < 1.0);
+        let looped = generate_link_shape_path((50.0, 50.0), (50.0, 50.0), shape, 1.0, 30.0);
+        assert_eq!(looped, generate_self_loop_path(50.0, 50.0, 30.0, 1.0));
+    }
+
+    #[test]
+    fn test_self_loop_bounds_contain_samples() {
+        let (bx, by, bw, bh) = self_loop_bounds(0.0, 0.0, 30.0, 1.0);
+        let loop_curve = CubicBezier::self_loop(0.0, 0.0, 30.0, 1.0);
+        for i in 0..=100 {
+            let (x, y) = loop_curve.eval(i as f32 / 100.0);
+            assert!(x >= bx - 0.01 && x <= bx + bw + 0.01, "x={} outside bounds", x);
+            assert!(y >= by - 0.01 && y <= by + bh + 0.01, "y={} outside bounds", y);
+        }
+        assert_eq!(bh, 60.0);
+    }
+
+    // ========================================================================
+    // bezier_end_tangent() / generate_arrowhead() - Directed Links
+    // ========================================================================
+
+    #[test]
+    fn test_end_tangent_horizontal_curve() {
+        let bezier = CubicBezier::from_endpoints(0.0, 50.0, 200.0, 50.0, 1.0, 50.0);
+        assert!(bezier_end_tangent(&bezier).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_end_tangent_vertical_curve() {
+        let bezier = CubicBezier {
+            p0: (0.0, 0.0),
+            p1: (0.0, 30.0),
+            p2: (0.0, 70.0),
+            p3: (0.0, 100.0),
+        };
+        let angle = bezier_end_tangent(&bezier);
+        assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_end_tangent_horizontal_at_input_for_offset_curve() {
+        // Control points extend horizontally, so the curve enters the input pin flat
+        let bezier = CubicBezier::from_endpoints(0.0, 0.0, 200.0, 100.0, 1.0, 50.0);
+        assert!(bezier_end_tangent(&bezier).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_end_tangent_degenerate_falls_back() {
+        let bezier = CubicBezier {
+            p0: (0.0, 0.0),
+            p1: (0.0, 0.0),
+            p2: (10.0, 10.0),
+            p3: (10.0, 10.0),
+        };
+        let angle = bezier_end_tangent(&bezier);
+        assert!((angle - std::f32::consts::FRAC_PI_4).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_arrowhead_pointing_right() {
+        let path = generate_arrowhead(100.0, 50.0, 0.0, 10.0);
+        assert_eq!(path, "M 100 50 L 90 45 L 90 55 Z");
+    }
+
+    #[test]
+    fn test_arrowhead_pointing_down() {
+        let path = generate_arrowhead(0.0, 0.0, std::f32::consts::FRAC_PI_2, 10.0);
+        let nums: Vec<f32> = path
+            .split(' ')
+            .filter_map(|t| t.parse::<f32>().ok())
+            .collect();
+        // Tip, then two base corners 10 units above the tip
+        assert_eq!(nums.len(), 6);
+        assert!((nums[3] + 10.0).abs() < 0.001);
+        assert!((nums[5] + 10.0).abs() < 0.001);
+        assert!((nums[2] - nums[4]).abs() > 9.9);
+    }
+
+    // ========================================================================
+    // generate_orthogonal_path() / OrthogonalPath
+    // ========================================================================
+
+    #[test]
+    fn test_orthogonal_path_forward_hvh() {
+        let path = generate_orthogonal_path(0.0, 0.0, 100.0, 80.0, 1.0, 0.0);
+        assert_eq!(path, "M 0 0 L 50 0 L 50 80 L 100 80");
+    }
+
+    #[test]
+    fn test_orthogonal_path_colinear_is_straight() {
+        let path = generate_orthogonal_path(0.0, 50.0, 100.0, 50.5, 1.0, 8.0);
+        assert_eq!(path, "M 0 50 L 100 50.5");
+        assert!(!path.contains(" Q "));
+    }
+
+    #[test]
+    fn test_orthogonal_path_rounded_corners() {
+        let path = generate_orthogonal_path(0.0, 0.0, 100.0, 80.0, 1.0, 10.0);
+        assert!(path.starts_with("M 0 0 L 40 0 Q 50 0 50 10"));
+        assert!(path.ends_with("L 100 80"));
+        assert_eq!(path.matches(" Q ").count(), 2);
+    }
+
+    #[test]
+    fn test_orthogonal_path_corner_radius_clamped() {
+        // Vertical run is 10 long, so radius is clamped to 5
+        let path = generate_orthogonal_path(0.0, 0.0, 100.0, 10.0, 1.0, 50.0);
+        assert!(path.contains("L 45 0 Q 50 0 50 5"));
+    }
+
+    #[test]
+    fn test_orthogonal_path_back_step() {
+        let route = OrthogonalPath::from_endpoints(200.0, 0.0, 0.0, 100.0, 1.0);
+        assert_eq!(route.points.len(), 6);
+        // Leaves the source to the right and enters the target from the left
+        assert_eq!(route.points[1], (220.0, 0.0));
+        assert_eq!(route.points[4], (-20.0, 100.0));
+        // Horizontal run sits between the two rows
+        assert_eq!(route.points[2].1, 50.0);
+    }
+
+    #[test]
+    fn test_orthogonal_path_back_step_same_row_routes_below() {
+        let route = OrthogonalPath::from_endpoints(200.0, 50.0, 0.0, 50.0, 1.0);
+        assert!(route.points[2].1 > 50.0);
+        assert_eq!(route.points[2].1, route.points[3].1);
+    }
+
+    #[test]
+    fn test_orthogonal_eval_endpoints_and_midpoint() {
+        let route = OrthogonalPath::from_endpoints(0.0, 0.0, 100.0, 100.0, 1.0);
+        assert_eq!(route.eval(0.0), (0.0, 0.0));
+        assert_eq!(route.eval(1.0), (100.0, 100.0));
+        // Total length 200: halfway is the middle of the vertical segment
+        assert_eq!(route.eval(0.5), (50.0, 50.0));
+    }
+
+    #[test]
+    fn test_orthogonal_eval_degenerate_point() {
+        let route = OrthogonalPath::from_endpoints(10.0, 10.0, 10.0, 10.0, 1.0);
+        assert_eq!(route.eval(0.5), (10.0, 10.0));
+    }
+
+    #[test]
+    fn test_distance_to_orthogonal_path() {
+        let route = OrthogonalPath::from_endpoints(0.0, 0.0, 100.0, 100.0, 1.0);
+        // On the vertical segment at x=50
+        assert!(distance_to_orthogonal_path((50.0, 30.0), &route) < 0.001);
+        // 5 units to the right of the vertical segment
+        assert!((distance_to_orthogonal_path((55.0, 30.0), &route) - 5.0).abs() < 0.001);
+    }
+
+    // ========================================================================
+    // generate_step_path() - Staircase Links
+    // ========================================================================
+
+    #[test]
+    fn test_step_path_ratio_half() {
+        let path = generate_step_path(0.0, 0.0, 100.0, 80.0, 0.5);
+        assert_eq!(path, "M 0 0 L 50 0 L 50 80 L 100 80");
+    }
+
+    #[test]
+    fn test_step_path_ratio_zero() {
+        // Vertical segment at the start: first segment has zero length
+        let path = generate_step_path(0.0, 0.0, 100.0, 80.0, 0.0);
+        assert_eq!(path, "M 0 0 L 0 0 L 0 80 L 100 80");
+    }
+
+    #[test]
+    fn test_step_path_ratio_one() {
+        // Vertical segment at the end: last segment has zero length
+        let path = generate_step_path(0.0, 0.0, 100.0, 80.0, 1.0);
+        assert_eq!(path, "M 0 0 L 100 0 L 100 80 L 100 80");
+    }
+
+    #[test]
+    fn test_step_path_same_y_keeps_three_segments() {
+        let path = generate_step_path(0.0, 50.0, 100.0, 50.0, 0.5);
+        assert_eq!(path, "M 0 50 L 50 50 L 50 50 L 100 50");
+        assert_eq!(path.matches(" L ").count(), 3);
+    }
+
+    #[test]
+    fn test_step_path_ratio_clamped() {
+        let over = generate_step_path(0.0, 0.0, 100.0, 80.0, 2.0);
+        let one = generate_step_path(0.0, 0.0, 100.0, 80.0, 1.0);
+        assert_eq!(over, one);
+    }
+
+    #[test]
+    fn test_step_route_matches_svg() {
+        let route = OrthogonalPath::step(0.0, 0.0, 100.0, 80.0, 0.25);
+        assert_eq!(route.segments().count(), 3);
+        assert_eq!(route.points[1], (25.0, 0.0));
+        assert!(distance_to_orthogonal_path((25.0, 40.0), &route) < 0.001);
+    }
+
+    // ========================================================================
+    // generate_waypoint_path() / WaypointPath - Routed Links
+    // ========================================================================
+
+    /// Route from an output pin on a right edge to an input pin on a left edge
+    fn horizontal_route(
+        start: (f32, f32),
+        waypoints: &[(f32, f32)],
+        end: (f32, f32),
+    ) -> WaypointPath {
+        WaypointPath::from_points(start, (1.0, 0.0), waypoints, end, (-1.0, 0.0), 1.0, 50.0)
+    }
+
+    #[test]
+    fn test_waypoint_path_without_waypoints_matches_bezier() {
+        let (right, left) = ((1.0, 0.0), (-1.0, 0.0));
+        assert_eq!(
+            generate_waypoint_path((0.0, 0.0), right, &[], (200.0, 80.0), left, 1.0, 50.0),
+            generate_bezier_path(0.0, 0.0, 200.0, 80.0, 1.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn test_waypoint_path_has_one_leg_per_waypoint_plus_one() {
+        let waypoints = [(100.0, -50.0), (200.0, 50.0)];
+        let (right, left) = ((1.0, 0.0), (-1.0, 0.0));
+        let path =
+            generate_waypoint_path((0.0, 0.0), right, &waypoints, (300.0, 0.0), left, 1.0, 50.0);
+        assert!(path.starts_with("M 0 0 C"));
+        assert!(path.ends_with("300 0"));
+        assert_eq!(path.matches(" C ").count(), 3);
+    }
+
+    #[test]
+    fn test_waypoint_path_passes_through_midpoint_at_half() {
+        let waypoint = (150.0, -120.0);
+        let route = horizontal_route((0.0, 0.0), &[waypoint], (300.0, 0.0));
+        assert_eq!(route.segments.len(), 2);
+
+        let mid = route.eval(0.5);
+        assert!((mid.0 - waypoint.0).abs() < 0.001);
+        assert!((mid.1 - waypoint.1).abs() < 0.001);
+        // Nearby parameters stay close to the waypoint
+        let near = route.eval(0.48);
+        assert!(((near.0 - waypoint.0).powi(2) + (near.1 - waypoint.1).powi(2)).sqrt() < 15.0);
+
+        assert_eq!(route.eval(0.0), (0.0, 0.0));
+        assert_eq!(route.eval(1.0), (300.0, 0.0));
+    }
+
+    #[test]
+    fn test_waypoint_path_is_smooth_at_waypoint() {
+        let route = horizontal_route((0.0, 0.0), &[(150.0, -120.0)], (300.0, 40.0));
+        let incoming = route.segments[0].p2;
+        let joint = route.segments[0].p3;
+        let outgoing = route.segments[1].p1;
+        // Incoming and outgoing handles are collinear through the waypoint
+        let cross = (joint.0 - incoming.0) * (outgoing.1 - joint.1)
+            - (joint.1 - incoming.1) * (outgoing.0 - joint.0);
+        assert!(cross.abs() < 0.01);
+    }
+
+    #[test]
+    fn test_waypoint_path_keeps_pin_directions_for_waypoints_behind_pins() {
+        // The waypoint is left of the start pin and right of the end pin
+        let route = horizontal_route((200.0, 0.0), &[(100.0, -100.0)], (150.0, 50.0));
+        let first = &route.segments[0];
+        let last = &route.segments[1];
+        assert!(first.p1.0 > first.p0.0);
+        assert_eq!(first.p1.1, first.p0.1);
+        assert!(last.p2.0 < last.p3.0);
+        assert_eq!(last.p2.1, last.p3.1);
+    }
+
+    #[test]
+    fn test_waypoint_path_follows_vertical_pin_sides() {
+        // Bottom pin of one node, around a waypoint, into the top pin of a node below
+        let shape = LinkShape::default().with_sides(PinSide::Bottom, PinSide::Top);
+        let (start, end) = ((0.0, 0.0), (0.0, 300.0));
+        let (start_dir, end_dir) = shape.pin_directions(start, end);
+        assert_eq!((start_dir, end_dir), ((0.0, 1.0), (0.0, -1.0)));
+
+        let route =
+            WaypointPath::from_points(start, start_dir, &[(80.0, 150.0)], end, end_dir, 1.0, 50.0);
+        let (first, last) = (&route.segments[0], &route.segments[1]);
+        assert_eq!(first.p1.0, 0.0);
+        assert!(first.p1.1 > 0.0);
+        assert_eq!(last.p2.0, 0.0);
+        assert!(last.p2.1 < 300.0);
+
+        let path =
+            generate_waypoint_path(start, start_dir, &[(80.0, 150.0)], end, end_dir, 1.0, 50.0);
+        assert!(path.starts_with(&format!("M 0 0 C 0 {}", first.p1.1)));
+
+        // An Auto side takes the direction of the bias instead of +x
+        let biased = LinkShape::default().with_bias(BezierBias::Vertical);
+        assert_eq!(biased.pin_directions(start, end), ((0.0, 1.0), (0.0, -1.0)));
+    }
+
+    #[test]
+    fn test_distance_to_waypoint_path() {
+        let route = horizontal_route((0.0, 0.0), &[(150.0, -120.0)], (300.0, 0.0));
+        assert!(distance_to_waypoint_path((150.0, -120.0), &route, 20) < 0.5);
+        // The straight line between the pins is far from the routed curve
+        assert!(distance_to_waypoint_path((150.0, 0.0), &route, 20) > 50.0);
+    }
+
     // ========================================================================
     // Property-based tests
     // ========================================================================
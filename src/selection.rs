@@ -1,9 +1,20 @@
 use std::collections::HashSet;
 use slint::{VecModel, Model};
 
+/// Selected item IDs with click, shift-click and range-click handling.
+///
+/// With the `serde` feature enabled this type implements
+/// `Serialize`/`Deserialize`, including the anchor and primary item. For a
+/// plain ID list use [`to_vec`](Self::to_vec) and [`from_vec`](Self::from_vec).
 #[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct SelectionManager {
     selected: HashSet<i32>,
+    /// Last item clicked without range extension; start of shift-click ranges
+    anchor: Option<i32>,
+    /// Most recently selected item; always a member of `selected` when set
+    primary: Option<i32>,
 }
 
 impl SelectionManager {
@@ -11,13 +22,33 @@ impl SelectionManager {
         Self::default()
     }
 
+    /// Create a selection of the given IDs, e.g. when restoring a session
+    ///
+    /// Duplicates are ignored. There is no range anchor and the lowest ID
+    /// becomes the [`primary`](Self::primary) item.
+    pub fn from_vec(ids: Vec<i32>) -> Self {
+        let mut selection = Self::new();
+        selection.replace_selection(ids);
+        selection
+    }
+
+    /// Selected IDs in ascending order, e.g. for saving a session
+    pub fn to_vec(&self) -> Vec<i32> {
+        let mut ids: Vec<i32> = self.selected.iter().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
     /// Handle selection of an item (e.g., node or link) based on interaction modifiers
     pub fn handle_interaction(&mut self, id: i32, shift_held: bool) {
+        self.anchor = Some(id);
         if shift_held {
             if self.selected.contains(&id) {
                 self.selected.remove(&id);
+                self.repair_primary();
             } else {
                 self.selected.insert(id);
+                self.primary = Some(id);
             }
         } else {
             if self.selected.len() == 1 && self.selected.contains(&id) {
@@ -25,12 +56,83 @@ impl SelectionManager {
             }
             self.selected.clear();
             self.selected.insert(id);
+            self.primary = Some(id);
+        }
+    }
+
+    /// Select the contiguous range between the anchor and `id`
+    ///
+    /// The anchor is the item last passed to [`handle_interaction`](Self::handle_interaction).
+    /// The selection is replaced by every ID between the anchor and `id`
+    /// (inclusive) in `ordered_ids`, in either direction. The anchor itself
+    /// is kept so further range clicks pivot around the same item.
+    ///
+    /// If there is no anchor, or either end is missing from `ordered_ids`,
+    /// this behaves like a plain click on `id`.
+    pub fn handle_range_interaction(&mut self, id: i32, ordered_ids: &[i32]) {
+        let anchor_pos = self
+            .anchor
+            .and_then(|anchor| ordered_ids.iter().position(|&x| x == anchor));
+        let target_pos = ordered_ids.iter().position(|&x| x == id);
+
+        match (anchor_pos, target_pos) {
+            (Some(a), Some(b)) => {
+                let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+                self.selected.clear();
+                self.selected.extend(ordered_ids[lo..=hi].iter().copied());
+                self.primary = Some(id);
+            }
+            _ => self.handle_interaction(id, false),
+        }
+    }
+
+    /// Add `id` if it is not selected, remove it if it is
+    ///
+    /// Unlike [`handle_interaction`](Self::handle_interaction), other
+    /// selected items and the range anchor are left untouched.
+    ///
+    /// Returns `true` if `id` is selected afterwards.
+    pub fn toggle(&mut self, id: i32) -> bool {
+        if self.selected.remove(&id) {
+            self.repair_primary();
+            false
+        } else {
+            self.selected.insert(id);
+            self.primary.get_or_insert(id);
+            true
+        }
+    }
+
+    /// Current range-selection anchor, if any
+    pub fn anchor(&self) -> Option<i32> {
+        self.anchor
+    }
+
+    /// The primary selected item, e.g. the reference for alignment or the
+    /// item shown in a property panel
+    ///
+    /// This is the item most recently selected through
+    /// [`handle_interaction`](Self::handle_interaction) or
+    /// [`handle_range_interaction`](Self::handle_range_interaction). When it
+    /// is deselected, the lowest remaining selected ID takes over; bulk
+    /// operations that keep it selected leave it unchanged. `None` if the
+    /// selection is empty.
+    pub fn primary(&self) -> Option<i32> {
+        self.primary
+    }
+
+    /// Move the primary to the lowest selected ID if it was deselected
+    fn repair_primary(&mut self) {
+        if !self.primary.is_some_and(|id| self.selected.contains(&id)) {
+            self.primary = self.selected.iter().min().copied();
         }
     }
 
     /// Clear the current selection
     pub fn clear(&mut self) {
         self.selected.clear();
+        self.anchor = None;
+        self.primary = None;
     }
 
     /// Replace the current selection with a new set of IDs
@@ -42,6 +144,53 @@ impl SelectionManager {
     {
         self.selected.clear();
         self.selected.extend(ids);
+        self.repair_primary();
+    }
+
+    /// Add all given IDs to the selection
+    pub fn union_with<I>(&mut self, ids: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.selected.extend(ids);
+        self.repair_primary();
+    }
+
+    /// Keep only the selected IDs that also appear in `ids`
+    pub fn intersect_with<I>(&mut self, ids: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let keep: HashSet<i32> = ids.into_iter().collect();
+        self.selected.retain(|id| keep.contains(id));
+        self.repair_primary();
+    }
+
+    /// Remove all given IDs from the selection
+    pub fn difference_with<I>(&mut self, ids: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        for id in ids {
+            self.selected.remove(&id);
+        }
+        self.repair_primary();
+    }
+
+    /// Invert the selection within `universe`
+    ///
+    /// Every ID in `universe` that was selected becomes unselected and vice
+    /// versa. Selected IDs outside `universe` are dropped.
+    pub fn invert<I>(&mut self, universe: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let previous = std::mem::take(&mut self.selected);
+        self.selected = universe
+            .into_iter()
+            .filter(|id| !previous.contains(id))
+            .collect();
+        self.repair_primary();
     }
 
     /// Check if an ID is selected
@@ -65,6 +214,45 @@ impl SelectionManager {
         }
     }
 
+    /// Sync the selection to a Slint VecModel by applying only the changes
+    ///
+    /// Rows whose ID is no longer selected (and duplicate rows) are removed and
+    /// newly selected IDs are appended in ascending order; rows that stay
+    /// selected are not touched. During a rubber-band drag this notifies Slint
+    /// about a few rows per frame instead of rebuilding the whole model.
+    pub fn sync_to_model_incremental(&self, model: &VecModel<i32>) {
+        let mut present: HashSet<i32> = HashSet::with_capacity(model.row_count());
+        let mut stale: Vec<usize> = Vec::new();
+        for row in 0..model.row_count() {
+            match model.row_data(row) {
+                Some(id) if self.selected.contains(&id) && present.insert(id) => {}
+                _ => stale.push(row),
+            }
+        }
+        // Remove from the back so earlier row indices stay valid
+        for row in stale.into_iter().rev() {
+            model.remove(row);
+        }
+
+        let mut added: Vec<i32> = self.selected.difference(&present).copied().collect();
+        added.sort_unstable();
+        for id in added {
+            model.push(id);
+        }
+    }
+
+    /// Compare this selection with an earlier one
+    ///
+    /// Returns `(added, removed)`: IDs selected here but not in `other`, and
+    /// IDs selected in `other` but not here, each sorted ascending.
+    pub fn diff(&self, other: &SelectionManager) -> (Vec<i32>, Vec<i32>) {
+        let mut added: Vec<i32> = self.selected.difference(&other.selected).copied().collect();
+        let mut removed: Vec<i32> = other.selected.difference(&self.selected).copied().collect();
+        added.sort_unstable();
+        removed.sort_unstable();
+        (added, removed)
+    }
+
     /// Sync the internal selection set from any Slint Model (e.g. after box selection)
     pub fn sync_from_model(&mut self, model: &dyn Model<Data = i32>) {
         self.selected.clear();
@@ -73,6 +261,7 @@ impl SelectionManager {
                 self.selected.insert(id);
             }
         }
+        self.repair_primary();
     }
 
     /// Get the number of selected items
@@ -224,6 +413,97 @@ mod tests {
         assert!(selection.is_empty());
     }
 
+    // ========================================================================
+    // toggle() - Modifier-Free Toggling
+    // ========================================================================
+
+    #[test]
+    fn test_toggle_twice_restores_selection() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([1, 2]);
+
+        assert!(selection.toggle(3));
+        assert!(selection.contains(3));
+        assert!(!selection.toggle(3));
+
+        assert_eq!(selection.len(), 2);
+        assert!(selection.contains(1) && selection.contains(2) && !selection.contains(3));
+    }
+
+    #[test]
+    fn test_toggle_keeps_others_and_anchor() {
+        let mut selection = SelectionManager::new();
+        selection.handle_interaction(1, false);
+
+        assert!(!selection.toggle(1));
+        selection.toggle(2);
+        assert!(!selection.contains(1));
+        assert!(selection.contains(2));
+        assert_eq!(selection.anchor(), Some(1));
+    }
+
+    #[test]
+    fn test_is_empty_tracks_len() {
+        let mut selection = SelectionManager::new();
+        assert!(selection.is_empty());
+        selection.toggle(5);
+        assert!(!selection.is_empty());
+        assert_eq!(selection.len(), 1);
+        selection.toggle(5);
+        assert!(selection.is_empty());
+        assert_eq!(selection.len(), 0);
+    }
+
+    // ========================================================================
+    // primary() - Most Recently Selected Item
+    // ========================================================================
+
+    #[test]
+    fn test_primary_follows_shift_click() {
+        let mut selection = SelectionManager::new();
+        assert_eq!(selection.primary(), None);
+
+        selection.handle_interaction(1, false);
+        assert_eq!(selection.primary(), Some(1));
+        selection.handle_interaction(2, true);
+        assert_eq!(selection.primary(), Some(2));
+    }
+
+    #[test]
+    fn test_primary_updates_when_deselected() {
+        let mut selection = SelectionManager::new();
+        selection.handle_interaction(5, false);
+        selection.handle_interaction(3, true);
+        selection.handle_interaction(9, true);
+
+        // Shift-click on the primary removes it; the lowest remaining ID takes over
+        selection.handle_interaction(9, true);
+        assert_eq!(selection.primary(), Some(3));
+        selection.toggle(3);
+        assert_eq!(selection.primary(), Some(5));
+        selection.toggle(5);
+        assert_eq!(selection.primary(), None);
+    }
+
+    #[test]
+    fn test_primary_survives_bulk_ops_that_keep_it() {
+        let mut selection = SelectionManager::new();
+        selection.handle_interaction(4, false);
+        selection.union_with([1, 2]);
+        assert_eq!(selection.primary(), Some(4));
+
+        selection.difference_with([4]);
+        assert_eq!(selection.primary(), Some(1));
+    }
+
+    #[test]
+    fn test_primary_cleared_by_clear() {
+        let mut selection = SelectionManager::new();
+        selection.handle_interaction(1, false);
+        selection.clear();
+        assert_eq!(selection.primary(), None);
+    }
+
     // ========================================================================
     // clear() - Selection Clearing
     // ========================================================================
@@ -248,6 +528,71 @@ mod tests {
         assert!(selection.is_empty());
     }
 
+    #[test]
+    fn test_clear_resets_anchor() {
+        let mut selection = SelectionManager::new();
+        selection.handle_interaction(1, false);
+        assert_eq!(selection.anchor(), Some(1));
+
+        selection.clear();
+        assert_eq!(selection.anchor(), None);
+    }
+
+    // ========================================================================
+    // handle_range_interaction() - Shift-Click Ranges
+    // ========================================================================
+
+    #[test]
+    fn test_range_forward() {
+        let mut selection = SelectionManager::new();
+        let order = [10, 20, 30, 40, 50];
+        selection.handle_interaction(20, false);
+        selection.handle_range_interaction(40, &order);
+
+        assert_eq!(sorted(&selection), vec![20, 30, 40]);
+        assert_eq!(selection.anchor(), Some(20));
+    }
+
+    #[test]
+    fn test_range_backward() {
+        let mut selection = SelectionManager::new();
+        let order = [10, 20, 30, 40, 50];
+        selection.handle_interaction(40, false);
+        selection.handle_range_interaction(10, &order);
+
+        assert_eq!(sorted(&selection), vec![10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn test_range_pivots_around_anchor() {
+        let mut selection = SelectionManager::new();
+        let order = [1, 2, 3, 4, 5];
+        selection.handle_interaction(3, false);
+        selection.handle_range_interaction(5, &order);
+        selection.handle_range_interaction(1, &order);
+
+        assert_eq!(sorted(&selection), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_range_anchor_missing_from_order_acts_as_click() {
+        let mut selection = SelectionManager::new();
+        selection.handle_interaction(99, false);
+        selection.handle_range_interaction(3, &[1, 2, 3, 4]);
+
+        assert_eq!(sorted(&selection), vec![3]);
+        assert_eq!(selection.anchor(), Some(3));
+    }
+
+    #[test]
+    fn test_range_without_anchor_acts_as_click() {
+        let mut selection = SelectionManager::new();
+        selection.handle_range_interaction(2, &[1, 2, 3]);
+
+        assert_eq!(sorted(&selection), vec![2]);
+        assert_eq!(selection.anchor(), Some(2));
+    }
+
     // ========================================================================
     // replace_selection() - Box Selection Sync
     // ========================================================================
@@ -305,6 +650,83 @@ mod tests {
         assert_eq!(selection.len(), count_before);
     }
 
+    // ========================================================================
+    // union_with() / intersect_with() / difference_with() / invert()
+    // ========================================================================
+
+    fn sorted(selection: &SelectionManager) -> Vec<i32> {
+        let mut items: Vec<i32> = selection.iter().copied().collect();
+        items.sort();
+        items
+    }
+
+    #[test]
+    fn test_union_with_adds_items() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection(vec![1, 2]);
+        selection.union_with(vec![2, 3, 4]);
+
+        assert_eq!(sorted(&selection), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_intersect_with_keeps_common_items() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection(vec![1, 2, 3]);
+        selection.intersect_with(vec![2, 3, 4]);
+
+        assert_eq!(sorted(&selection), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_intersect_with_disjoint_produces_empty() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection(vec![1, 2, 3]);
+        selection.intersect_with(vec![4, 5]);
+
+        assert!(selection.is_empty());
+        assert!(!selection.contains(1));
+    }
+
+    #[test]
+    fn test_difference_with_removes_items() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection(vec![1, 2, 3]);
+        selection.difference_with(vec![2, 9]);
+
+        assert_eq!(sorted(&selection), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_invert_against_larger_universe() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection(vec![2, 4]);
+        selection.invert(1..=6);
+
+        assert_eq!(sorted(&selection), vec![1, 3, 5, 6]);
+        assert!(!selection.contains(2));
+        assert!(selection.contains(5));
+    }
+
+    #[test]
+    fn test_invert_drops_ids_outside_universe() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection(vec![1, 100]);
+        selection.invert(vec![1, 2, 2]);
+
+        assert_eq!(sorted(&selection), vec![2]);
+    }
+
+    #[test]
+    fn test_invert_twice_restores_selection_within_universe() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection(vec![1, 3]);
+        selection.invert(1..=4);
+        selection.invert(1..=4);
+
+        assert_eq!(sorted(&selection), vec![1, 3]);
+    }
+
     // ========================================================================
     // iter() - Iteration
     // ========================================================================
@@ -326,6 +748,50 @@ mod tests {
         assert_eq!(selection.iter().count(), 0);
     }
 
+    // ========================================================================
+    // to_vec() / from_vec() - Persistence
+    // ========================================================================
+
+    #[test]
+    fn test_to_vec_is_sorted() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([42, 7, 19, 3]);
+        assert_eq!(selection.to_vec(), vec![3, 7, 19, 42]);
+    }
+
+    #[test]
+    fn test_vec_round_trip() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection([5, 1, 9]);
+
+        let restored = SelectionManager::from_vec(selection.to_vec());
+        assert_eq!(restored.to_vec(), vec![1, 5, 9]);
+        assert_eq!(restored.primary(), Some(1));
+        assert_eq!(restored.anchor(), None);
+    }
+
+    #[test]
+    fn test_from_vec_ignores_duplicates() {
+        let selection = SelectionManager::from_vec(vec![2, 2, 1]);
+        assert_eq!(selection.len(), 2);
+        assert_eq!(selection.to_vec(), vec![1, 2]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_selection_json_round_trip() {
+        let mut selection = SelectionManager::new();
+        selection.handle_interaction(4, false);
+        selection.handle_interaction(8, true);
+
+        let json = serde_json::to_string(&selection).unwrap();
+        let loaded: SelectionManager = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.to_vec(), vec![4, 8]);
+        assert_eq!(loaded.anchor(), Some(8));
+        assert_eq!(loaded.primary(), Some(8));
+    }
+
     // ========================================================================
     // sync_to_model() - Export to VecModel
     // ========================================================================
@@ -368,6 +834,63 @@ mod tests {
         assert_eq!(model.row_count(), 0);
     }
 
+    // ========================================================================
+    // diff() / sync_to_model_incremental() - Change Tracking
+    // ========================================================================
+
+    fn model_values(model: &VecModel<i32>) -> Vec<i32> {
+        (0..model.row_count()).filter_map(|i| model.row_data(i)).collect()
+    }
+
+    #[test]
+    fn test_diff_reports_added_and_removed() {
+        let mut before = SelectionManager::new();
+        before.replace_selection(vec![1, 2, 3]);
+        let mut after = SelectionManager::new();
+        after.replace_selection(vec![5, 3, 4, 1]);
+
+        assert_eq!(after.diff(&before), (vec![4, 5], vec![2]));
+        assert_eq!(before.diff(&after), (vec![2], vec![4, 5]));
+    }
+
+    #[test]
+    fn test_diff_of_equal_selections_is_empty() {
+        let mut a = SelectionManager::new();
+        a.replace_selection(vec![7, 8]);
+        let mut b = SelectionManager::new();
+        b.replace_selection(vec![8, 7]);
+
+        assert_eq!(a.diff(&b), (vec![], vec![]));
+        assert_eq!(SelectionManager::new().diff(&SelectionManager::new()), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_sync_to_model_incremental_keeps_unchanged_rows() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection(vec![1, 2, 3]);
+        let model: Rc<VecModel<i32>> = Rc::new(VecModel::from(vec![3, 1, 2]));
+
+        selection.difference_with([1]);
+        selection.union_with([9, 4]);
+        selection.sync_to_model_incremental(&model);
+
+        // Surviving rows keep their order, additions are appended sorted
+        assert_eq!(model_values(&model), vec![3, 2, 4, 9]);
+    }
+
+    #[test]
+    fn test_sync_to_model_incremental_drops_duplicates_and_stale() {
+        let mut selection = SelectionManager::new();
+        selection.replace_selection(vec![2]);
+        let model: Rc<VecModel<i32>> = Rc::new(VecModel::from(vec![2, 10, 2, 20]));
+
+        selection.sync_to_model_incremental(&model);
+        assert_eq!(model_values(&model), vec![2]);
+
+        SelectionManager::new().sync_to_model_incremental(&model);
+        assert_eq!(model.row_count(), 0);
+    }
+
     // ========================================================================
     // sync_from_model() - Import from Model
     // ========================================================================
@@ -1,11 +1,17 @@
-use std::collections::HashMap;
+use std::collections::hash_map::{self, HashMap};
+use std::hash::Hash;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::graph::{LinkModel, SimpleLink};
 use crate::hit_test::{
-    find_link_at, find_pin_at, links_in_selection_box, nodes_in_selection_box, SimpleLinkGeometry,
-    SimpleNodeGeometry, SimplePinGeometry, NodeGeometry,
+    find_link_at, find_pin_at, link_route, links_in_polygon, links_in_selection_box, nodes_in_polygon,
+    nodes_in_selection_box, LinkGeometry, NodeSpatialIndex, PinSide, ShapedLinkGeometry,
+    SimpleLinkGeometry, SimpleNodeGeometry, SimplePinGeometry, SizedPinGeometry, NodeGeometry,
 };
-use crate::path::generate_bezier_path;
+use crate::path::{generate_link_shape_path, generate_orthogonal_path, BezierBias, LinkShape};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StoredPin {
     pub node_id: i32,
     pub pin_type: i32,
@@ -13,19 +19,275 @@ pub struct StoredPin {
     pub rel_y: f32,
 }
 
+/// Plain-data copy of a graph's geometry and topology for saving and loading.
+///
+/// Produced by [`GeometryCache::snapshot`] and applied with
+/// [`GeometryCache::restore`]. The cache does not know about links, so
+/// `links` is empty in a fresh snapshot; fill it with
+/// [`with_links`](Self::with_links). With the `serde` feature enabled this
+/// type (and the types it contains) implements `Serialize`/`Deserialize`.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphSnapshot {
+    /// Node rectangles in world space, sorted by ID
+    pub nodes: Vec<SimpleNodeGeometry>,
+    /// `(pin_id, pin)` pairs, sorted by pin ID
+    pub pins: Vec<(i32, StoredPin)>,
+    /// Links between pins
+    pub links: Vec<SimpleLink>,
+    /// `(pin_id, side)` for pins with a side other than [`PinSide::Auto`],
+    /// sorted by pin ID
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pin_sides: Vec<(i32, PinSide)>,
+}
+
+impl GraphSnapshot {
+    /// Replace the snapshot's links with copies of the given link models.
+    pub fn with_links<I, L>(mut self, links: I) -> Self
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+    {
+        self.links = links
+            .into_iter()
+            .map(|link| SimpleLink {
+                id: link.id(),
+                start_pin_id: link.start_pin_id(),
+                end_pin_id: link.end_pin_id(),
+                color: link.color(),
+                line_width: link.line_width(),
+                status: link.status(),
+            })
+            .collect();
+        self
+    }
+}
+
+/// Uniform-grid bucket index over absolute pin positions.
+///
+/// Maps each cell of side `cell_size` to the pins whose absolute position
+/// falls inside it, so point queries only visit nearby buckets.
+struct PinIndex {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<i32>>,
+    /// Cell each indexed pin currently lives in
+    pin_cells: HashMap<i32, (i32, i32)>,
+    /// Versions of `(node_rects, pin_positions)` the buckets reflect
+    versions: (u64, u64),
+}
+
+impl PinIndex {
+    fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            pin_cells: HashMap::new(),
+            versions: (0, 0),
+        }
+    }
+
+    fn cell(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x / self.cell_size).floor() as i32,
+            (y / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Put a pin into the bucket for `(x, y)`, moving it if already indexed.
+    fn place(&mut self, pin_id: i32, x: f32, y: f32) {
+        let cell = self.cell(x, y);
+        if self.pin_cells.get(&pin_id) == Some(&cell) {
+            return;
+        }
+        self.unplace(pin_id);
+        self.cells.entry(cell).or_default().push(pin_id);
+        self.pin_cells.insert(pin_id, cell);
+    }
+
+    /// Take a pin out of its bucket.
+    fn unplace(&mut self, pin_id: i32) {
+        if let Some(cell) = self.pin_cells.remove(&pin_id) {
+            if let Some(bucket) = self.cells.get_mut(&cell) {
+                bucket.retain(|&id| id != pin_id);
+                if bucket.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+}
+
+/// Source of [`TrackedMap`] versions, shared so a replaced map never reuses one.
+static NEXT_MAP_VERSION: AtomicU64 = AtomicU64::new(1);
+
+/// `HashMap` that records when it is modified.
+///
+/// [`GeometryCache`] keeps its node and pin maps in `TrackedMap`s so they
+/// can still be read and written directly: every mutating method gives the
+/// map a new [`version`](Self::version), and the cache rebuilds any index
+/// whose version no longer matches. Read-only `HashMap` methods are
+/// available through `Deref`.
+#[derive(Debug, Clone)]
+pub struct TrackedMap<K, V> {
+    map: HashMap<K, V>,
+    version: u64,
+}
+
+impl<K, V> Default for TrackedMap<K, V> {
+    fn default() -> Self {
+        Self {
+            map: HashMap::new(),
+            version: NEXT_MAP_VERSION.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+impl<K, V> TrackedMap<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Changes whenever the map may have been modified; never reused by another map.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    fn touch(&mut self) {
+        self.version = NEXT_MAP_VERSION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// See [`HashMap::clear`].
+    pub fn clear(&mut self) {
+        self.touch();
+        self.map.clear();
+    }
+
+    /// See [`HashMap::retain`].
+    pub fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.touch();
+        self.map.retain(f);
+    }
+}
+
+impl<K: Eq + Hash, V> TrackedMap<K, V> {
+    /// See [`HashMap::insert`].
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.touch();
+        self.map.insert(key, value)
+    }
+
+    /// See [`HashMap::remove`].
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let removed = self.map.remove(key);
+        if removed.is_some() {
+            self.touch();
+        }
+        removed
+    }
+
+    /// See [`HashMap::get_mut`]; counts as a modification.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.touch();
+        self.map.get_mut(key)
+    }
+
+    /// See [`HashMap::entry`]; counts as a modification.
+    pub fn entry(&mut self, key: K) -> hash_map::Entry<'_, K, V> {
+        self.touch();
+        self.map.entry(key)
+    }
+
+    /// See [`HashMap::reserve`].
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+}
+
+impl<K, V> Deref for TrackedMap<K, V> {
+    type Target = HashMap<K, V>;
+
+    fn deref(&self) -> &HashMap<K, V> {
+        &self.map
+    }
+}
+
+/// Compares contents only, not versions
+impl<K: Eq + Hash, V: PartialEq> PartialEq for TrackedMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K: Eq + Hash, V> FromIterator<(K, V)> for TrackedMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self { map: iter.into_iter().collect(), ..Self::default() }
+    }
+}
+
+impl<K: Eq + Hash, V> Extend<(K, V)> for TrackedMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.touch();
+        self.map.extend(iter);
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a TrackedMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = hash_map::Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
 /// Helper struct to manage spatial state of the editor (node rects and pin positions)
 /// 
 /// Generic over N to allow using specialized node types that implement NodeGeometry.
+///
+/// An optional pin spatial index can be enabled with
+/// [`build_pin_index`](Self::build_pin_index). The report handlers,
+/// [`update_node`](Self::update_node), [`remove_node`](Self::remove_node) and
+/// [`remove_pin`](Self::remove_pin) keep it up to date incrementally.
+///
+/// Every index also follows direct writes to the [`TrackedMap`]s
+/// `node_rects` and `pin_positions`. The node-to-pins reverse index behind
+/// [`node_pins`](Self::node_pins) and the node quadtree for
+/// [`nodes_in_selection_box_indexed`](Self::nodes_in_selection_box_indexed)
+/// are rebuilt the next time they are needed. A stale pin index is ignored
+/// by queries, which scan every pin instead, and rebuilt by the next report
+/// handler or [`update_node`](Self::update_node) call.
 pub struct GeometryCache<N = SimpleNodeGeometry> {
-    pub node_rects: HashMap<i32, N>,
-    pub pin_positions: HashMap<i32, StoredPin>,
+    pub node_rects: TrackedMap<i32, N>,
+    pub pin_positions: TrackedMap<i32, StoredPin>,
+    /// Pin IDs per node in ascending order, maintained alongside `pin_positions`
+    node_pins: HashMap<i32, Vec<i32>>,
+    /// Version of `pin_positions` that `node_pins` reflects
+    node_pins_version: u64,
+    /// Sides set with [`set_pin_side`](Self::set_pin_side); absent means [`PinSide::Auto`]
+    pin_sides: HashMap<i32, PinSide>,
+    /// Radii set with [`set_pin_hit_radius`](Self::set_pin_hit_radius)
+    pin_hit_radii: HashMap<i32, f32>,
+    /// Largest value in `pin_hit_radii` (0.0 when empty), to widen indexed pin queries
+    max_pin_hit_radius: f32,
+    pin_index: Option<PinIndex>,
+    node_index: NodeSpatialIndex,
+    /// Version of `node_rects` the quadtree was built from, `None` to force a rebuild
+    node_index_version: Option<u64>,
 }
 
 impl<N> Default for GeometryCache<N> {
     fn default() -> Self {
         Self {
-            node_rects: HashMap::new(),
-            pin_positions: HashMap::new(),
+            node_rects: TrackedMap::new(),
+            pin_positions: TrackedMap::new(),
+            node_pins: HashMap::new(),
+            node_pins_version: 0,
+            pin_sides: HashMap::new(),
+            pin_hit_radii: HashMap::new(),
+            max_pin_hit_radius: 0.0,
+            pin_index: None,
+            node_index: NodeSpatialIndex::new(),
+            node_index_version: None,
         }
     }
 }
@@ -34,6 +296,316 @@ impl<N> GeometryCache<N> {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Remove every node rect and pin, e.g. for a "new document" action.
+    ///
+    /// An active pin index stays active (empty, with the same cell size).
+    pub fn clear(&mut self) {
+        self.node_rects.clear();
+        self.pin_positions.clear();
+        self.node_pins.clear();
+        self.node_pins_version = self.pin_positions.version();
+        self.pin_sides.clear();
+        self.pin_hit_radii.clear();
+        self.max_pin_hit_radius = 0.0;
+        let versions = self.pin_map_versions();
+        if let Some(index) = &mut self.pin_index {
+            *index = PinIndex::new(index.cell_size);
+            index.versions = versions;
+        }
+    }
+
+    /// Number of cached node rects.
+    pub fn node_count(&self) -> usize {
+        self.node_rects.len()
+    }
+
+    /// Number of cached pins.
+    pub fn pin_count(&self) -> usize {
+        self.pin_positions.len()
+    }
+
+    /// Remove a node's rect and every pin that belongs to it.
+    ///
+    /// Returns the number of pins removed.
+    pub fn remove_node(&mut self, id: i32) -> usize {
+        self.sync_node_pins();
+        let before = self.pin_map_versions();
+        self.node_rects.remove(&id);
+        let pins = self.node_pins.remove(&id).unwrap_or_default();
+        for &pin_id in &pins {
+            self.pin_positions.remove(&pin_id);
+            self.pin_sides.remove(&pin_id);
+            self.forget_hit_radius(pin_id);
+        }
+        self.node_pins_version = self.pin_positions.version();
+        let after = self.pin_map_versions();
+        if let Some(index) = &mut self.pin_index {
+            for &pin_id in &pins {
+                index.unplace(pin_id);
+            }
+            if index.versions == before {
+                index.versions = after;
+            }
+        }
+        pins.len()
+    }
+
+    /// Remove a single pin.
+    ///
+    /// Returns `true` if the pin was present.
+    pub fn remove_pin(&mut self, pin_id: i32) -> bool {
+        self.sync_node_pins();
+        let before = self.pin_map_versions();
+        match self.pin_positions.remove(&pin_id) {
+            Some(pin) => {
+                self.unlink_pin(pin_id, pin.node_id);
+                self.node_pins_version = self.pin_positions.version();
+                self.pin_sides.remove(&pin_id);
+                self.forget_hit_radius(pin_id);
+                let after = self.pin_map_versions();
+                if let Some(index) = &mut self.pin_index {
+                    index.unplace(pin_id);
+                    if index.versions == before {
+                        index.versions = after;
+                    }
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove every pin of `node_id` that is not listed in `keep`.
+    ///
+    /// Use this when a node's pin set changes at runtime, so pins that no
+    /// longer exist stop being hit-tested. Returns the removed pin IDs in
+    /// ascending order, e.g. to drop links attached to them.
+    pub fn prune_pins_for_node(&mut self, node_id: i32, keep: &[i32]) -> Vec<i32> {
+        self.sync_node_pins();
+        let stale: Vec<i32> = self
+            .node_pins
+            .get(&node_id)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|pin_id| !keep.contains(pin_id))
+            .collect();
+        for &pin_id in &stale {
+            self.remove_pin(pin_id);
+        }
+        stale
+    }
+
+    /// IDs of the pins reported for `node_id`, in ascending order.
+    ///
+    /// Returns an empty list for unknown nodes or nodes without pins. Reads
+    /// the reverse index, or scans `pin_positions` if it was written directly
+    /// since the index was last brought up to date.
+    pub fn node_pins(&self, node_id: i32) -> Vec<i32> {
+        if self.node_pins_version == self.pin_positions.version() {
+            return self.node_pins.get(&node_id).cloned().unwrap_or_default();
+        }
+        let mut pins: Vec<i32> = self
+            .pin_positions
+            .iter()
+            .filter(|(_, pin)| pin.node_id == node_id)
+            .map(|(&pin_id, _)| pin_id)
+            .collect();
+        pins.sort_unstable();
+        pins
+    }
+
+    /// Node edge `pin_id` sits on; [`PinSide::Auto`] unless set with
+    /// [`set_pin_side`](Self::set_pin_side).
+    pub fn pin_side(&self, pin_id: i32) -> PinSide {
+        self.pin_sides.get(&pin_id).copied().unwrap_or_default()
+    }
+
+    /// Set the node edge a cached pin sits on
+    ///
+    /// Links attached to the pin leave or enter it along that edge's normal.
+    /// The side is kept across later [`handle_pin_report`](Self::handle_pin_report)
+    /// calls for the same pin and dropped when the pin is removed. Returns
+    /// `false` if the pin is not cached.
+    pub fn set_pin_side(&mut self, pin_id: i32, side: PinSide) -> bool {
+        if !self.pin_positions.contains_key(&pin_id) {
+            return false;
+        }
+        match side {
+            PinSide::Auto => self.pin_sides.remove(&pin_id),
+            side => self.pin_sides.insert(pin_id, side),
+        };
+        true
+    }
+
+    /// Hit radius set for `pin_id` with [`set_pin_hit_radius`](Self::set_pin_hit_radius).
+    pub fn pin_hit_radius(&self, pin_id: i32) -> Option<f32> {
+        self.pin_hit_radii.get(&pin_id).copied()
+    }
+
+    /// Give a cached pin its own hit radius, e.g. a larger touch target
+    ///
+    /// [`find_pin_at`](Self::find_pin_at) and the controller's
+    /// [`find_pin_at_screen`](crate::NodeEditorController::find_pin_at_screen)
+    /// use it instead of the radius they are called with, in the same units.
+    /// `None` goes back to the caller's radius. The radius is dropped when
+    /// the pin is removed. Returns `false` if the pin is not cached.
+    pub fn set_pin_hit_radius(&mut self, pin_id: i32, radius: Option<f32>) -> bool {
+        if !self.pin_positions.contains_key(&pin_id) {
+            return false;
+        }
+        match radius {
+            Some(radius) => {
+                self.forget_hit_radius(pin_id);
+                self.pin_hit_radii.insert(pin_id, radius);
+                self.max_pin_hit_radius = self.max_pin_hit_radius.max(radius);
+            }
+            None => self.forget_hit_radius(pin_id),
+        }
+        true
+    }
+
+    /// Drop `pin_id`'s hit radius, recomputing the maximum if it was the largest.
+    fn forget_hit_radius(&mut self, pin_id: i32) {
+        if let Some(radius) = self.pin_hit_radii.remove(&pin_id) {
+            if radius >= self.max_pin_hit_radius {
+                self.max_pin_hit_radius = self.pin_hit_radii.values().copied().fold(0.0, f32::max);
+            }
+        }
+    }
+
+    /// Shape of the curve drawn for a link: its pins' sides (see
+    /// [`pin_side`](Self::pin_side)), `bias`, and whether both pins sit on
+    /// the same node.
+    ///
+    /// The `compute_link_path*` methods draw links with this shape and the
+    /// cache's link queries hit-test against it. Return it from
+    /// [`LinkGeometry::shape`] in your own link geometry to do the same.
+    pub fn link_shape(&self, start_pin: i32, end_pin: i32, bias: BezierBias) -> LinkShape {
+        let node = |pin_id| self.pin_positions.get(&pin_id).map(|pin| pin.node_id);
+        let same_node = matches!((node(start_pin), node(end_pin)), (Some(s), Some(e)) if s == e);
+        LinkShape::default()
+            .with_sides(self.pin_side(start_pin), self.pin_side(end_pin))
+            .with_bias(bias)
+            .with_self_loop(same_node)
+    }
+
+    /// ID of the node that owns `pin_id`, or `None` if the pin is unknown.
+    pub fn pin_node(&self, pin_id: i32) -> Option<i32> {
+        self.pin_positions.get(&pin_id).map(|pin| pin.node_id)
+    }
+
+    /// Number of links in `links` attached to `pin_id`.
+    ///
+    /// Returns 0 for pins that are not cached, matching
+    /// [`connection_counts`](Self::connection_counts). A link with both ends on
+    /// the pin counts once.
+    pub fn pin_connection_count<I, L>(&self, pin_id: i32, links: I) -> usize
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+    {
+        if !self.pin_positions.contains_key(&pin_id) {
+            return 0;
+        }
+        links
+            .into_iter()
+            .filter(|link| link.start_pin_id() == pin_id || link.end_pin_id() == pin_id)
+            .count()
+    }
+
+    /// Link count for every cached pin, for connected/unconnected pin styling.
+    ///
+    /// Every cached pin has an entry (0 when unconnected); link ends on
+    /// pins that are not cached are ignored.
+    pub fn connection_counts<I, L>(&self, links: I) -> HashMap<i32, usize>
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+    {
+        let mut counts: HashMap<i32, usize> =
+            self.pin_positions.keys().map(|&pin_id| (pin_id, 0)).collect();
+        for link in links {
+            let (start, end) = (link.start_pin_id(), link.end_pin_id());
+            if let Some(count) = counts.get_mut(&start) {
+                *count += 1;
+            }
+            if end != start {
+                if let Some(count) = counts.get_mut(&end) {
+                    *count += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Record `pin_id` under `node_id` in the reverse index.
+    fn link_pin(&mut self, pin_id: i32, node_id: i32) {
+        let pins = self.node_pins.entry(node_id).or_default();
+        if let Err(pos) = pins.binary_search(&pin_id) {
+            pins.insert(pos, pin_id);
+        }
+    }
+
+    /// Drop `pin_id` from `node_id`'s entry in the reverse index.
+    fn unlink_pin(&mut self, pin_id: i32, node_id: i32) {
+        if let Some(pins) = self.node_pins.get_mut(&node_id) {
+            pins.retain(|&id| id != pin_id);
+            if pins.is_empty() {
+                self.node_pins.remove(&node_id);
+            }
+        }
+    }
+
+    /// Recompute the reverse index from `pin_positions`.
+    fn rebuild_node_pins(&mut self) {
+        self.node_pins.clear();
+        for (&pin_id, pin) in &self.pin_positions {
+            self.node_pins.entry(pin.node_id).or_default().push(pin_id);
+        }
+        for pins in self.node_pins.values_mut() {
+            pins.sort_unstable();
+        }
+        self.node_pins_version = self.pin_positions.version();
+    }
+
+    /// Rebuild the reverse index if `pin_positions` was written directly since it was updated.
+    fn sync_node_pins(&mut self) {
+        if self.node_pins_version != self.pin_positions.version() {
+            self.rebuild_node_pins();
+        }
+    }
+
+    /// Current versions of `(node_rects, pin_positions)`.
+    fn pin_map_versions(&self) -> (u64, u64) {
+        (self.node_rects.version(), self.pin_positions.version())
+    }
+
+    /// The pin index, unless the maps were written directly since it was updated.
+    fn fresh_pin_index(&self) -> Option<&PinIndex> {
+        self.pin_index
+            .as_ref()
+            .filter(|index| index.versions == self.pin_map_versions())
+    }
+
+    /// Whether a pin spatial index is active.
+    pub fn has_pin_index(&self) -> bool {
+        self.pin_index.is_some()
+    }
+
+    /// Drop the pin spatial index; `find_pin_at` falls back to a linear scan.
+    pub fn clear_pin_index(&mut self) {
+        self.pin_index = None;
+    }
+
+    /// Force the next indexed node query to rebuild the quadtree.
+    ///
+    /// Writes to `node_rects` already do this; the method is kept for callers
+    /// that invalidated explicitly.
+    pub fn invalidate_node_index(&mut self) {
+        self.node_index_version = None;
+    }
 }
 
 impl<N> GeometryCache<N>
@@ -54,6 +626,25 @@ where
             })
     }
 
+    /// Absolute world-space position of a single pin
+    ///
+    /// Resolves the pin's node rect and adds the pin offset, e.g. for the
+    /// fixed end of a link preview. Returns `None` if the pin or its node is
+    /// not cached.
+    pub fn absolute_pin(&self, pin_id: i32) -> Option<(f32, f32)> {
+        let pin_pos = self.pin_positions.get(&pin_id)?;
+        let rect = self.node_rects.get(&pin_pos.node_id)?.rect();
+        Some((rect.0 + pin_pos.rel_x, rect.1 + pin_pos.rel_y))
+    }
+
+    /// Absolute world-space `(start, end)` positions of a link's pins
+    ///
+    /// Both pins are resolved as in [`absolute_pin`](Self::absolute_pin).
+    /// Returns `None` if either pin or its node is not cached.
+    pub fn absolute_link(&self, start_pin: i32, end_pin: i32) -> Option<((f32, f32), (f32, f32))> {
+        Some((self.absolute_pin(start_pin)?, self.absolute_pin(end_pin)?))
+    }
+
     /// Iterator over absolute link geometries for hit testing
     pub fn get_absolute_links<'a, I>(
         &'a self,
@@ -63,25 +654,168 @@ where
         I: Iterator<Item = (i32, i32, i32)> + 'a,
     {
         links.filter_map(move |(id, start_pin, end_pin)| {
-            let start_pos = self.pin_positions.get(&start_pin)?;
-            let end_pos = self.pin_positions.get(&end_pin)?;
+            let ((start_x, start_y), (end_x, end_y)) = self.absolute_link(start_pin, end_pin)?;
+            Some(SimpleLinkGeometry { id, start_x, start_y, end_x, end_y })
+        })
+    }
+
+    /// Like [`get_absolute_links`](Self::get_absolute_links), but each link
+    /// carries its [`link_shape`](Self::link_shape) so hit tests follow the
+    /// curves drawn by the `compute_link_path*` methods.
+    pub(crate) fn get_shaped_links<'a, I>(
+        &'a self,
+        links: I,
+        bias: BezierBias,
+    ) -> impl Iterator<Item = ShapedLinkGeometry> + 'a
+    where
+        I: Iterator<Item = (i32, i32, i32)> + 'a,
+    {
+        links.filter_map(move |(id, start_pin, end_pin)| {
+            let ((start_x, start_y), (end_x, end_y)) = self.absolute_link(start_pin, end_pin)?;
+            let shape = self.link_shape(start_pin, end_pin, bias);
+            Some(ShapedLinkGeometry { id, start_x, start_y, end_x, end_y, shape })
+        })
+    }
 
-            let start_rect = self.node_rects.get(&start_pos.node_id)?.rect();
-            let end_rect = self.node_rects.get(&end_pos.node_id)?.rect();
+    /// Build (or rebuild) a uniform-grid spatial index over pin positions.
+    ///
+    /// Once built, [`find_pin_at`](Self::find_pin_at) only tests pins in the
+    /// cells overlapping the hit radius instead of every pin. A `cell_size`
+    /// around the typical hit radius or node height works well.
+    ///
+    /// # Arguments
+    /// * `cell_size` - Side length of a grid cell in world units (must be > 0)
+    pub fn build_pin_index(&mut self, cell_size: f32) {
+        self.rebuild_node_pins();
+        let mut index = PinIndex::new(cell_size.max(f32::EPSILON));
+        for (&pin_id, pin) in &self.pin_positions {
+            if let Some(rect) = self.node_rects.get(&pin.node_id).map(|n| n.rect()) {
+                index.place(pin_id, rect.0 + pin.rel_x, rect.1 + pin.rel_y);
+            }
+        }
+        index.versions = self.pin_map_versions();
+        self.pin_index = Some(index);
+    }
 
-            Some(SimpleLinkGeometry {
-                id,
-                start_x: start_rect.0 + start_pos.rel_x,
-                start_y: start_rect.1 + start_pos.rel_y,
-                end_x: end_rect.0 + end_pos.rel_x,
-                end_y: end_rect.1 + end_pos.rel_y,
-            })
+    /// Rebuild the pin index with its current cell size, if one is active.
+    ///
+    /// The node-to-pins reverse index is always rebuilt. Direct writes to
+    /// `node_rects` or `pin_positions` no longer require this; it only
+    /// moves the rebuild to a point of the caller's choosing.
+    pub fn rebuild_pin_index(&mut self) {
+        match self.pin_index.as_ref().map(|index| index.cell_size) {
+            Some(cell_size) => self.build_pin_index(cell_size),
+            None => self.rebuild_node_pins(),
+        }
+    }
+
+    /// Rebuild the pin index if the maps were written directly since it was updated.
+    fn sync_pin_index(&mut self) {
+        if let Some(index) = &self.pin_index {
+            if index.versions != self.pin_map_versions() {
+                self.build_pin_index(index.cell_size);
+            }
+        }
+    }
+
+    /// Absolute position and hit radius of a cached pin, if its node is cached.
+    fn sized_pin(&self, pin_id: i32, pin: &StoredPin) -> Option<SizedPinGeometry> {
+        let rect = self.node_rects.get(&pin.node_id)?.rect();
+        Some(SizedPinGeometry {
+            id: pin_id,
+            x: rect.0 + pin.rel_x,
+            y: rect.1 + pin.rel_y,
+            hit_radius: self.pin_hit_radius(pin_id),
         })
     }
 
+    /// Every cached pin whose node is cached, as in [`sized_pin`](Self::sized_pin).
+    fn sized_pins(&self) -> impl Iterator<Item = SizedPinGeometry> + '_ {
+        self.pin_positions.iter().filter_map(|(&pin_id, pin)| self.sized_pin(pin_id, pin))
+    }
+
+    /// Pins from the index buckets overlapping the square of `radius` around `(x, y)`.
+    fn indexed_pins_near(
+        &self,
+        index: &PinIndex,
+        x: f32,
+        y: f32,
+        radius: f32,
+    ) -> Vec<SizedPinGeometry> {
+        let (min_cx, min_cy) = index.cell(x - radius, y - radius);
+        let (max_cx, max_cy) = index.cell(x + radius, y + radius);
+        let span = (max_cx as i64 - min_cx as i64 + 1) * (max_cy as i64 - min_cy as i64 + 1);
+
+        let mut ids: Vec<i32> = Vec::new();
+        if span > index.cells.len() as i64 {
+            // Query covers more cells than are occupied; walk the occupied ones
+            for (&(cx, cy), bucket) in &index.cells {
+                if (min_cx..=max_cx).contains(&cx) && (min_cy..=max_cy).contains(&cy) {
+                    ids.extend_from_slice(bucket);
+                }
+            }
+        } else {
+            for cx in min_cx..=max_cx {
+                for cy in min_cy..=max_cy {
+                    if let Some(bucket) = index.cells.get(&(cx, cy)) {
+                        ids.extend_from_slice(bucket);
+                    }
+                }
+            }
+        }
+
+        ids.into_iter()
+            .filter_map(|pin_id| self.sized_pin(pin_id, self.pin_positions.get(&pin_id)?))
+            .collect()
+    }
+
     /// Find pin at position
+    ///
+    /// Pins with their own [`pin_hit_radius`](Self::pin_hit_radius) use it
+    /// instead of `hit_radius`. Uses the pin spatial index when one has been
+    /// built and is up to date, widening the searched cells to the largest
+    /// per-pin radius.
     pub fn find_pin_at(&self, x: f32, y: f32, hit_radius: f32) -> i32 {
-        find_pin_at(x, y, self.get_absolute_pins(), hit_radius)
+        match self.fresh_pin_index() {
+            Some(index) => {
+                let reach = hit_radius.max(self.max_pin_hit_radius);
+                find_pin_at(x, y, self.indexed_pins_near(index, x, y, reach), hit_radius)
+            }
+            None => find_pin_at(x, y, self.sized_pins(), hit_radius),
+        }
+    }
+
+    /// Find the nearest pin within `radius` that passes `filter`, for magnetic
+    /// snapping while dragging a link.
+    ///
+    /// Returns the pin ID and its absolute position so the link preview can
+    /// lock onto the pin's center. Unlike [`find_pin_at`](Self::find_pin_at)
+    /// this picks the closest candidate (lowest ID on ties) rather than the
+    /// first one found, and `filter` can restrict it, e.g. to input pins.
+    /// Uses the pin spatial index when one has been built and is up to date.
+    pub fn snap_to_pin(
+        &self,
+        x: f32,
+        y: f32,
+        radius: f32,
+        filter: impl Fn(&StoredPin) -> bool,
+    ) -> Option<(i32, f32, f32)> {
+        let candidates = match self.fresh_pin_index() {
+            Some(index) => self.indexed_pins_near(index, x, y, radius),
+            None => self.sized_pins().collect(),
+        };
+        let radius_sq = radius * radius;
+
+        candidates
+            .into_iter()
+            .filter(|pin| self.pin_positions.get(&pin.id).is_some_and(&filter))
+            .map(|pin| {
+                let dist_sq = (pin.x - x) * (pin.x - x) + (pin.y - y) * (pin.y - y);
+                (dist_sq, pin)
+            })
+            .filter(|(dist_sq, _)| *dist_sq <= radius_sq)
+            .min_by(|(a, pa), (b, pb)| a.total_cmp(b).then(pa.id.cmp(&pb.id)))
+            .map(|(_, pin)| (pin.id, pin.x, pin.y))
     }
 
     /// Find link at position
@@ -102,7 +836,7 @@ where
         find_link_at(
             x,
             y,
-            self.get_absolute_links(links),
+            self.get_shaped_links(links, BezierBias::Horizontal),
             hover_distance,
             zoom,
             bezier_min_offset,
@@ -127,6 +861,73 @@ where
         )
     }
 
+    /// Like [`nodes_in_selection_box`](Self::nodes_in_selection_box), but
+    /// returns the IDs in ascending order.
+    ///
+    /// The unsorted variant follows `HashMap` iteration order, which changes
+    /// between runs. Use this when the order matters, e.g. when the first
+    /// selected node becomes the primary selection.
+    pub fn nodes_in_selection_box_sorted(
+        &self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) -> Vec<i32> {
+        let mut ids = self.nodes_in_selection_box(x, y, width, height);
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Compute nodes whose center lies inside a lasso polygon.
+    ///
+    /// See [`nodes_in_polygon`](crate::hit_test::nodes_in_polygon).
+    pub fn nodes_in_polygon(&self, polygon: &[(f32, f32)]) -> Vec<i32> {
+        nodes_in_polygon(polygon, self.node_rects.values().copied())
+    }
+
+    /// Compute links whose world-space curve touches a lasso polygon.
+    ///
+    /// Curves are built as in [`compute_link_path_world`](Self::compute_link_path_world);
+    /// see [`links_in_polygon`](crate::hit_test::links_in_polygon).
+    pub fn links_in_polygon<'a, I>(
+        &'a self,
+        polygon: &[(f32, f32)],
+        links: I,
+        bezier_min_offset: f32,
+        samples: usize,
+    ) -> Vec<i32>
+    where
+        I: Iterator<Item = (i32, i32, i32)> + 'a,
+    {
+        links_in_polygon(
+            polygon,
+            self.get_shaped_links(links, BezierBias::Horizontal),
+            1.0,
+            bezier_min_offset,
+            samples,
+        )
+    }
+
+    /// Compute nodes in selection box using the node quadtree.
+    ///
+    /// Returns the same nodes as [`nodes_in_selection_box`](Self::nodes_in_selection_box).
+    /// The quadtree is rebuilt on the first query after any node change, so
+    /// repeated queries during a box-selection drag only visit nearby nodes.
+    pub fn nodes_in_selection_box_indexed(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) -> Vec<i32> {
+        if self.node_index_version != Some(self.node_rects.version()) {
+            self.node_index.rebuild(self.node_rects.values().copied());
+            self.node_index_version = Some(self.node_rects.version());
+        }
+        self.node_index.query_box(x, y, width, height)
+    }
+
     /// Compute links in selection box
     pub fn links_in_selection_box<'a, I>(
         &'a self,
@@ -148,21 +949,29 @@ where
         )
     }
 
+    /// Like [`links_in_selection_box`](Self::links_in_selection_box), but
+    /// returns the IDs in ascending order regardless of the order of `links`.
+    pub fn links_in_selection_box_sorted<'a, I>(
+        &'a self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        links: I,
+    ) -> Vec<i32>
+    where
+        I: Iterator<Item = (i32, i32, i32)> + 'a,
+    {
+        let mut ids = self.links_in_selection_box(x, y, width, height, links);
+        ids.sort_unstable();
+        ids
+    }
+
     /// Resolve absolute world-space positions for a link's start and end pins.
     /// Returns `(start_x, start_y, end_x, end_y)` or `None` if pins/nodes are missing.
-    fn resolve_link_endpoints(&self, start_pin: i32, end_pin: i32) -> Option<(f32, f32, f32, f32)> {
-        let start_pos = self.pin_positions.get(&start_pin)?;
-        let end_pos = self.pin_positions.get(&end_pin)?;
-
-        let start_rect = self.node_rects.get(&start_pos.node_id)?.rect();
-        let end_rect = self.node_rects.get(&end_pos.node_id)?.rect();
-
-        Some((
-            start_rect.0 + start_pos.rel_x,
-            start_rect.1 + start_pos.rel_y,
-            end_rect.0 + end_pos.rel_x,
-            end_rect.1 + end_pos.rel_y,
-        ))
+    pub(crate) fn resolve_link_endpoints(&self, start_pin: i32, end_pin: i32) -> Option<(f32, f32, f32, f32)> {
+        let ((start_x, start_y), (end_x, end_y)) = self.absolute_link(start_pin, end_pin)?;
+        Some((start_x, start_y, end_x, end_y))
     }
 
     /// Compute bezier path in world coordinates with explicit zoom for bezier offset scaling.
@@ -174,7 +983,25 @@ where
         bezier_min_offset: f32,
     ) -> Option<String> {
         let (sx, sy, ex, ey) = self.resolve_link_endpoints(start_pin, end_pin)?;
-        Some(generate_bezier_path(sx, sy, ex, ey, zoom, bezier_min_offset))
+        let shape = self.link_shape(start_pin, end_pin, BezierBias::Horizontal);
+        Some(generate_link_shape_path((sx, sy), (ex, ey), shape, zoom, bezier_min_offset))
+    }
+
+    /// Compute an orthogonal (Manhattan) path in world coordinates.
+    ///
+    /// Orthogonal counterpart to [`compute_link_path`](Self::compute_link_path):
+    /// resolves both pins to absolute positions and routes them with
+    /// [`generate_orthogonal_path`]. Returns `None` if either pin (or its
+    /// node) is not cached.
+    pub fn compute_link_path_orthogonal(
+        &self,
+        start_pin: i32,
+        end_pin: i32,
+        zoom: f32,
+        corner_radius: f32,
+    ) -> Option<String> {
+        let (sx, sy, ex, ey) = self.resolve_link_endpoints(start_pin, end_pin)?;
+        Some(generate_orthogonal_path(sx, sy, ex, ey, zoom, corner_radius))
     }
 
     /// Compute bezier path in screen space from world-space cache data.
@@ -188,15 +1015,74 @@ where
         pan_x: f32,
         pan_y: f32,
         bezier_min_offset: f32,
+    ) -> Option<String> {
+        self.compute_link_path_screen_biased(
+            start_pin,
+            end_pin,
+            zoom,
+            pan_x,
+            pan_y,
+            bezier_min_offset,
+            BezierBias::Horizontal,
+        )
+    }
+
+    /// Same as [`compute_link_path_screen`](Self::compute_link_path_screen),
+    /// with control points along `bias` for links between [`PinSide::Auto`] pins.
+    #[allow(clippy::too_many_arguments)]
+    pub fn compute_link_path_screen_biased(
+        &self,
+        start_pin: i32,
+        end_pin: i32,
+        zoom: f32,
+        pan_x: f32,
+        pan_y: f32,
+        bezier_min_offset: f32,
+        bias: BezierBias,
     ) -> Option<String> {
         let (sx, sy, ex, ey) = self.resolve_link_endpoints(start_pin, end_pin)?;
-        Some(generate_bezier_path(
-            sx * zoom + pan_x, sy * zoom + pan_y,
-            ex * zoom + pan_x, ey * zoom + pan_y,
-            zoom, bezier_min_offset,
+        let shape = self.link_shape(start_pin, end_pin, bias);
+        Some(generate_link_shape_path(
+            (sx * zoom + pan_x, sy * zoom + pan_y),
+            (ex * zoom + pan_x, ey * zoom + pan_y),
+            shape,
+            zoom,
+            bezier_min_offset,
         ))
     }
 
+    /// Nodes whose rect overlaps the world-space `viewport` `(x, y, width, height)`.
+    ///
+    /// Returns IDs in ascending order. Pass only these to the Slint node
+    /// model to skip off-screen nodes in large graphs.
+    pub fn visible_nodes(&self, viewport: (f32, f32, f32, f32)) -> Vec<i32> {
+        let (x, y, width, height) = viewport;
+        self.nodes_in_selection_box_sorted(x, y, width, height)
+    }
+
+    /// Links whose world-space curve bounding box overlaps `viewport`.
+    ///
+    /// Curves are built as in [`compute_link_path_world`](Self::compute_link_path_world)
+    /// and bounded with [`CubicBezier::bounding_box`](crate::path::CubicBezier::bounding_box),
+    /// so a link whose endpoints are both off-screen is still kept when its
+    /// curve crosses the viewport. Links with unknown pins are skipped. IDs
+    /// keep the order of `links`.
+    pub fn visible_links<I>(
+        &self,
+        viewport: (f32, f32, f32, f32),
+        links: I,
+        bezier_min_offset: f32,
+    ) -> Vec<i32>
+    where
+        I: IntoIterator<Item = (i32, i32, i32)>,
+    {
+        visible_link_ids(
+            viewport,
+            self.get_shaped_links(links.into_iter(), BezierBias::Horizontal),
+            bezier_min_offset,
+        )
+    }
+
     /// Compute bezier path in pure world coordinates (zoom=1.0).
     ///
     /// Used when links are rendered inside a transform-scale container
@@ -206,9 +1092,27 @@ where
         start_pin: i32,
         end_pin: i32,
         bezier_min_offset: f32,
+    ) -> Option<String> {
+        self.compute_link_path_world_biased(
+            start_pin,
+            end_pin,
+            bezier_min_offset,
+            BezierBias::Horizontal,
+        )
+    }
+
+    /// Same as [`compute_link_path_world`](Self::compute_link_path_world),
+    /// with control points along `bias` for links between [`PinSide::Auto`] pins.
+    pub fn compute_link_path_world_biased(
+        &self,
+        start_pin: i32,
+        end_pin: i32,
+        bezier_min_offset: f32,
+        bias: BezierBias,
     ) -> Option<String> {
         let (sx, sy, ex, ey) = self.resolve_link_endpoints(start_pin, end_pin)?;
-        Some(generate_bezier_path(sx, sy, ex, ey, 1.0, bezier_min_offset))
+        let shape = self.link_shape(start_pin, end_pin, bias);
+        Some(generate_link_shape_path((sx, sy), (ex, ey), shape, 1.0, bezier_min_offset))
     }
 
     /// Standard handler for pin position reports from Slint
@@ -220,7 +1124,9 @@ where
         rel_x: f32,
         rel_y: f32,
     ) {
-        self.pin_positions.insert(
+        self.sync_node_pins();
+        self.sync_pin_index();
+        let previous = self.pin_positions.insert(
             pin_id,
             StoredPin {
                 node_id,
@@ -229,6 +1135,87 @@ where
                 rel_y,
             },
         );
+
+        match previous {
+            Some(old) if old.node_id == node_id => {}
+            Some(old) => {
+                self.unlink_pin(pin_id, old.node_id);
+                self.link_pin(pin_id, node_id);
+            }
+            None => self.link_pin(pin_id, node_id),
+        }
+        self.node_pins_version = self.pin_positions.version();
+
+        let versions = self.pin_map_versions();
+        if let Some(index) = &mut self.pin_index {
+            match self.node_rects.get(&node_id).map(|n| n.rect()) {
+                Some(rect) => index.place(pin_id, rect.0 + rel_x, rect.1 + rel_y),
+                None => index.unplace(pin_id),
+            }
+            index.versions = versions;
+        }
+    }
+
+    /// World-space bounding box of all cached nodes.
+    ///
+    /// Returns `(min_x, min_y, max_x, max_y)`, or `None` when no node rects
+    /// are cached. Useful for fit-to-view and minimap scaling.
+    pub fn graph_bounds(&self) -> Option<(f32, f32, f32, f32)> {
+        self.node_rects.values().map(|node| node.rect()).fold(None, |bounds, (x, y, w, h)| {
+            let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((x, y, x + w, y + h));
+            Some((min_x.min(x), min_y.min(y), max_x.max(x + w), max_y.max(y + h)))
+        })
+    }
+
+    /// Copy node rects and pin positions into a [`GraphSnapshot`].
+    ///
+    /// Nodes are stored as [`SimpleNodeGeometry`] using their `rect()`.
+    pub fn snapshot(&self) -> GraphSnapshot {
+        let mut nodes: Vec<SimpleNodeGeometry> = self
+            .node_rects
+            .iter()
+            .map(|(&id, node)| {
+                let (x, y, width, height) = node.rect();
+                SimpleNodeGeometry { id, x, y, width, height }
+            })
+            .collect();
+        nodes.sort_by_key(|node| node.id);
+
+        let mut pins: Vec<(i32, StoredPin)> = self
+            .pin_positions
+            .iter()
+            .map(|(&id, &pin)| (id, pin))
+            .collect();
+        pins.sort_by_key(|&(id, _)| id);
+
+        let mut pin_sides: Vec<(i32, PinSide)> =
+            self.pin_sides.iter().map(|(&id, &side)| (id, side)).collect();
+        pin_sides.sort_by_key(|&(id, _)| id);
+
+        GraphSnapshot {
+            nodes,
+            pins,
+            links: Vec::new(),
+            pin_sides,
+        }
+    }
+
+    /// Insert or replace a node's geometry, re-bucketing its indexed pins.
+    pub fn update_node(&mut self, id: i32, node: N) {
+        self.sync_node_pins();
+        self.sync_pin_index();
+        let rect = node.rect();
+        self.node_rects.insert(id, node);
+
+        let versions = self.pin_map_versions();
+        if let Some(index) = &mut self.pin_index {
+            for pin_id in self.node_pins.get(&id).into_iter().flatten() {
+                if let Some(pin) = self.pin_positions.get(pin_id) {
+                    index.place(*pin_id, rect.0 + pin.rel_x, rect.1 + pin.rel_y);
+                }
+            }
+            index.versions = versions;
+        }
     }
 }
 
@@ -236,7 +1223,7 @@ where
 impl GeometryCache<SimpleNodeGeometry> {
     /// Update a node's rectangle (shorthand for SimpleNodeGeometry)
     pub fn update_node_rect(&mut self, id: i32, x: f32, y: f32, width: f32, height: f32) {
-        self.node_rects.insert(
+        self.update_node(
             id,
             SimpleNodeGeometry {
                 id,
@@ -248,15 +1235,87 @@ impl GeometryCache<SimpleNodeGeometry> {
         );
     }
 
+    /// Replace all node rects and pin positions with those from a snapshot.
+    ///
+    /// Links in the snapshot are not stored in the cache; recreate them in
+    /// your link model from `snapshot.links`.
+    pub fn restore(&mut self, snapshot: &GraphSnapshot) {
+        self.node_rects = snapshot.nodes.iter().map(|node| (node.id, *node)).collect();
+        self.pin_positions = snapshot.pins.iter().copied().collect();
+        self.pin_sides = snapshot
+            .pin_sides
+            .iter()
+            .copied()
+            .filter(|(id, _)| self.pin_positions.contains_key(id))
+            .collect();
+        let stale_radii: Vec<i32> = self
+            .pin_hit_radii
+            .keys()
+            .copied()
+            .filter(|id| !self.pin_positions.contains_key(id))
+            .collect();
+        for pin_id in stale_radii {
+            self.forget_hit_radius(pin_id);
+        }
+        self.rebuild_pin_index();
+    }
+
     /// Standard handler for node rect reports from Slint (for SimpleNodeGeometry)
     pub fn handle_node_rect_report(&mut self, id: i32, x: f32, y: f32, w: f32, h: f32) {
         self.update_node_rect(id, x, y, w, h);
     }
+
+    /// Apply many node rect and pin reports at once.
+    ///
+    /// `nodes` are `(id, x, y, width, height)` and `pins` are
+    /// `(pin_id, node_id, pin_type, rel_x, rel_y)`, with the same meaning as
+    /// the arguments of [`handle_node_rect_report`](Self::handle_node_rect_report)
+    /// and [`handle_pin_report`](Self::handle_pin_report). The resulting cache
+    /// state is the same as reporting each item individually, but the spatial
+    /// indexes are rebuilt once instead of updated per report, which makes
+    /// reloading a whole layout cheap.
+    pub fn apply_reports(&mut self, nodes: &[(i32, f32, f32, f32, f32)], pins: &[(i32, i32, i32, f32, f32)]) {
+        self.node_rects.reserve(nodes.len());
+        for &(id, x, y, width, height) in nodes {
+            self.node_rects.insert(id, SimpleNodeGeometry { id, x, y, width, height });
+        }
+        self.pin_positions.reserve(pins.len());
+        for &(pin_id, node_id, pin_type, rel_x, rel_y) in pins {
+            self.pin_positions.insert(pin_id, StoredPin { node_id, pin_type, rel_x, rel_y });
+        }
+        self.rebuild_pin_index();
+    }
+}
+
+/// IDs of the links whose world-space curve (every leg of a waypoint route)
+/// has a bounding box overlapping `viewport`
+pub(crate) fn visible_link_ids<L, I>(
+    viewport: (f32, f32, f32, f32),
+    links: I,
+    bezier_min_offset: f32,
+) -> Vec<i32>
+where
+    L: LinkGeometry,
+    I: IntoIterator<Item = L>,
+{
+    let (vx, vy, vw, vh) = viewport;
+    links
+        .into_iter()
+        .filter(|link| {
+            link_route(link, 1.0, bezier_min_offset).segments.iter().any(|segment| {
+                let (x, y, w, h) = segment.bounding_box();
+                // Inclusive so straight horizontal or vertical links (zero-size box) count
+                x <= vx + vw && x + w >= vx && y <= vy + vh && y + h >= vy
+            })
+        })
+        .map(|link| link.id())
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::path::generate_bezier_path;
 
     /// Helper to create a test cache with two nodes and pins
     fn setup_test_cache() -> GeometryCache<SimpleNodeGeometry> {
@@ -370,6 +1429,195 @@ mod tests {
         assert_eq!(node.y, -200.0);
     }
 
+    // ========================================================================
+    // remove_node() / remove_pin() - Eviction
+    // ========================================================================
+
+    #[test]
+    fn test_remove_node_drops_rect_and_pins() {
+        let mut cache = setup_test_cache();
+        cache.handle_pin_report(1002, 1, 1, 0.0, 25.0);
+
+        let removed = cache.remove_node(1);
+
+        assert_eq!(removed, 2);
+        assert!(!cache.node_rects.contains_key(&1));
+        assert!(!cache.pin_positions.contains_key(&1001));
+        assert!(!cache.pin_positions.contains_key(&1002));
+        // Other node's pins are untouched
+        assert!(cache.pin_positions.contains_key(&2001));
+        assert_eq!(cache.find_pin_at(100.0, 25.0, 10.0), 0);
+    }
+
+    #[test]
+    fn test_remove_node_missing_is_noop() {
+        let mut cache = setup_test_cache();
+        assert_eq!(cache.remove_node(999), 0);
+        assert_eq!(cache.node_rects.len(), 2);
+        assert_eq!(cache.pin_positions.len(), 2);
+    }
+
+    #[test]
+    fn test_remove_node_cleans_orphan_pins() {
+        let mut cache = setup_test_cache();
+        // Pin whose node rect was never reported
+        cache.handle_pin_report(9001, 9, 1, 0.0, 0.0);
+        assert_eq!(cache.remove_node(9), 1);
+        assert!(!cache.pin_positions.contains_key(&9001));
+    }
+
+    #[test]
+    fn test_remove_pin() {
+        let mut cache = setup_test_cache();
+        assert!(cache.remove_pin(1001));
+        assert!(!cache.remove_pin(1001));
+        assert_eq!(cache.find_pin_at(100.0, 25.0, 10.0), 0);
+        // Node rect stays
+        assert!(cache.node_rects.contains_key(&1));
+    }
+
+    // ========================================================================
+    // clear() / node_count() / pin_count()
+    // ========================================================================
+
+    #[test]
+    fn test_counts_reflect_contents() {
+        let mut cache = setup_test_cache();
+        assert_eq!(cache.node_count(), 2);
+        assert_eq!(cache.pin_count(), 2);
+
+        cache.handle_pin_report(1002, 1, 1, 0.0, 25.0);
+        assert_eq!(cache.pin_count(), 3);
+        cache.remove_node(1);
+        assert_eq!(cache.node_count(), 1);
+        assert_eq!(cache.pin_count(), 1);
+    }
+
+    #[test]
+    fn test_clear_empties_both_maps() {
+        let mut cache = setup_test_cache();
+        cache.build_pin_index(50.0);
+
+        cache.clear();
+
+        assert_eq!(cache.node_count(), 0);
+        assert_eq!(cache.pin_count(), 0);
+        assert!(cache.node_rects.is_empty());
+        assert!(cache.pin_positions.is_empty());
+        assert!(cache.node_pins(1).is_empty());
+        assert!(cache.nodes_in_selection_box_indexed(-1000.0, -1000.0, 2000.0, 2000.0).is_empty());
+        assert_eq!(cache.find_pin_at(100.0, 25.0, 10.0), 0);
+
+        // The pin index keeps working for new reports
+        assert!(cache.has_pin_index());
+        cache.update_node_rect(3, 0.0, 0.0, 100.0, 50.0);
+        cache.handle_pin_report(3001, 3, 1, 100.0, 25.0);
+        assert_eq!(cache.find_pin_at(100.0, 25.0, 10.0), 3001);
+    }
+
+    // ========================================================================
+    // pin_connection_count() / connection_counts()
+    // ========================================================================
+
+    fn connection_test_links() -> Vec<SimpleLink> {
+        let color = slint::Color::from_rgb_u8(0, 0, 0);
+        vec![SimpleLink::new(1, 1001, 2001, color), SimpleLink::new(2, 1002, 2001, color)]
+    }
+
+    #[test]
+    fn test_pin_connection_count() {
+        let mut cache = setup_test_cache();
+        cache.handle_pin_report(1002, 1, 2, 100.0, 40.0);
+        cache.handle_pin_report(2002, 2, 1, 0.0, 40.0);
+        let links = connection_test_links();
+
+        assert_eq!(cache.pin_connection_count(2001, links.iter().cloned()), 2);
+        assert_eq!(cache.pin_connection_count(1001, links.iter().cloned()), 1);
+        assert_eq!(cache.pin_connection_count(2002, links.iter().cloned()), 0);
+        assert_eq!(cache.pin_connection_count(9999, links), 0);
+    }
+
+    #[test]
+    fn test_connection_counts_covers_every_cached_pin() {
+        let mut cache = setup_test_cache();
+        cache.handle_pin_report(2002, 2, 1, 0.0, 40.0);
+
+        // Link 2 starts at pin 1002, which is not cached
+        let counts = cache.connection_counts(connection_test_links());
+        assert_eq!(counts, HashMap::from([(1001, 1), (2001, 2), (2002, 0)]));
+    }
+
+    // ========================================================================
+    // node_pins() / pin_node() - Reverse Index
+    // ========================================================================
+
+    #[test]
+    fn test_node_pins_lists_reported_pins() {
+        let mut cache = setup_test_cache();
+        cache.handle_pin_report(1002, 1, 1, 0.0, 25.0);
+
+        assert_eq!(cache.node_pins(1), &[1001, 1002]);
+        assert_eq!(cache.node_pins(2), &[2001]);
+        assert!(cache.node_pins(999).is_empty());
+        assert_eq!(cache.pin_node(1002), Some(1));
+        assert_eq!(cache.pin_node(9999), None);
+    }
+
+    #[test]
+    fn test_node_pins_updated_on_remove_pin() {
+        let mut cache = setup_test_cache();
+        cache.handle_pin_report(1002, 1, 1, 0.0, 25.0);
+
+        cache.remove_pin(1001);
+
+        assert_eq!(cache.node_pins(1), &[1002]);
+        assert_eq!(cache.pin_node(1001), None);
+        cache.remove_node(1);
+        assert!(cache.node_pins(1).is_empty());
+    }
+
+    #[test]
+    fn test_node_pins_follows_pin_moved_to_other_node() {
+        let mut cache = setup_test_cache();
+        cache.handle_pin_report(1001, 2, 1, 0.0, 25.0);
+
+        assert!(cache.node_pins(1).is_empty());
+        assert_eq!(cache.node_pins(2), &[1001, 2001]);
+        assert_eq!(cache.pin_node(1001), Some(2));
+    }
+
+    #[test]
+    fn test_node_pins_rebuilt_after_direct_mutation() {
+        let mut cache = setup_test_cache();
+        cache.pin_positions.clear();
+        assert!(cache.node_pins(1).is_empty());
+
+        cache.apply_reports(&[], &[(3002, 3, 1, 0.0, 0.0), (3001, 3, 2, 0.0, 0.0)]);
+        assert_eq!(cache.node_pins(3), &[3001, 3002]);
+    }
+
+    #[test]
+    fn test_node_pins_follow_direct_writes_without_rebuild() {
+        let mut cache = setup_test_cache();
+        let direct_pin = |node_id| StoredPin { node_id, pin_type: 1, rel_x: 0.0, rel_y: 0.0 };
+        cache.pin_positions.insert(1002, direct_pin(1));
+        cache.pin_positions.insert(2001, direct_pin(1));
+        assert_eq!(cache.node_pins(1), &[1001, 1002, 2001]);
+        assert!(cache.node_pins(2).is_empty());
+
+        // Mutating methods see the direct writes too
+        assert_eq!(cache.prune_pins_for_node(1, &[1001]), vec![1002, 2001]);
+        cache.pin_positions.insert(1003, direct_pin(1));
+        assert_eq!(cache.remove_node(1), 2);
+        assert_eq!(cache.pin_count(), 0);
+
+        // Cache methods and direct writes can be mixed freely
+        cache.handle_pin_report(4001, 4, 1, 0.0, 0.0);
+        cache.pin_positions.insert(4002, direct_pin(4));
+        cache.handle_pin_report(4003, 4, 1, 0.0, 0.0);
+        assert_eq!(cache.node_pins(4), &[4001, 4002, 4003]);
+    }
+
     // ========================================================================
     // get_absolute_pins() - Coordinate Transformation
     // ========================================================================
@@ -411,15 +1659,55 @@ mod tests {
     }
 
     #[test]
-    fn test_get_absolute_pins_node_at_negative_coords() {
-        let mut cache = GeometryCache::new();
-        cache.update_node_rect(1, -100.0, -50.0, 100.0, 50.0);
-        cache.handle_pin_report(1001, 1, 2, 50.0, 25.0);
+    fn test_get_absolute_pins_node_at_negative_coords() {
+        let mut cache = GeometryCache::new();
+        cache.update_node_rect(1, -100.0, -50.0, 100.0, 50.0);
+        cache.handle_pin_report(1001, 1, 2, 50.0, 25.0);
+
+        let pins: Vec<SimplePinGeometry> = cache.get_absolute_pins().collect();
+        let pin = pins.iter().find(|p| p.id == 1001).expect("Pin should exist");
+        assert_eq!(pin.x, -50.0); // -100 + 50
+        assert_eq!(pin.y, -25.0); // -50 + 25
+    }
+
+    // ========================================================================
+    // absolute_pin() - Single Pin Lookup
+    // ========================================================================
+
+    #[test]
+    fn test_absolute_pin_resolves_existing_pin() {
+        let cache = setup_test_cache();
+        assert_eq!(cache.absolute_pin(1001), Some((100.0, 25.0)));
+        assert_eq!(cache.absolute_pin(2001), Some((200.0, 125.0)));
+    }
+
+    #[test]
+    fn test_absolute_pin_missing_node_is_none() {
+        let mut cache = setup_test_cache();
+        cache.handle_pin_report(9999, 999, 1, 50.0, 25.0);
+        assert_eq!(cache.absolute_pin(9999), None);
+    }
+
+    #[test]
+    fn test_absolute_pin_unknown_pin_is_none() {
+        let cache = setup_test_cache();
+        assert_eq!(cache.absolute_pin(4242), None);
+    }
+
+    #[test]
+    fn test_absolute_link_resolves_both_endpoints() {
+        let cache = setup_test_cache();
+        assert_eq!(cache.absolute_link(1001, 2001), Some(((100.0, 25.0), (200.0, 125.0))));
+        assert_eq!(cache.absolute_link(2001, 1001), Some(((200.0, 125.0), (100.0, 25.0))));
+    }
 
-        let pins: Vec<SimplePinGeometry> = cache.get_absolute_pins().collect();
-        let pin = pins.iter().find(|p| p.id == 1001).expect("Pin should exist");
-        assert_eq!(pin.x, -50.0); // -100 + 50
-        assert_eq!(pin.y, -25.0); // -50 + 25
+    #[test]
+    fn test_absolute_link_missing_endpoint_is_none() {
+        let mut cache = setup_test_cache();
+        cache.handle_pin_report(9999, 999, 1, 50.0, 25.0);
+        assert_eq!(cache.absolute_link(1001, 4242), None);
+        assert_eq!(cache.absolute_link(4242, 2001), None);
+        assert_eq!(cache.absolute_link(9999, 2001), None);
     }
 
     // ========================================================================
@@ -565,6 +1853,27 @@ mod tests {
         assert_ne!(path1, path2);
     }
 
+    // ========================================================================
+    // compute_link_path_orthogonal() - Manhattan Path Generation
+    // ========================================================================
+
+    #[test]
+    fn test_compute_link_path_orthogonal_routes_between_pins() {
+        let cache = setup_test_cache();
+        let path = cache.compute_link_path_orthogonal(1001, 2001, 1.0, 0.0).unwrap();
+
+        // Pin 1001 is at (100, 25), pin 2001 at (200, 125); vertical leg halfway
+        assert_eq!(path, "M 100 25 L 150 25 L 150 125 L 200 125");
+        assert_eq!(path, generate_orthogonal_path(100.0, 25.0, 200.0, 125.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn test_compute_link_path_orthogonal_returns_none_for_missing_pins() {
+        let cache = setup_test_cache();
+        assert!(cache.compute_link_path_orthogonal(9999, 2001, 1.0, 0.0).is_none());
+        assert!(cache.compute_link_path_orthogonal(1001, 9999, 1.0, 0.0).is_none());
+    }
+
     // ========================================================================
     // find_pin_at() - Delegated Hit Testing
     // ========================================================================
@@ -605,6 +1914,18 @@ mod tests {
         assert!(selected.is_empty());
     }
 
+    #[test]
+    fn test_nodes_in_selection_box_sorted_is_ascending() {
+        let mut cache: GeometryCache = GeometryCache::new();
+        for id in [7, 3, 42, 1, 19, 5, 100, 2] {
+            cache.update_node_rect(id, id as f32 * 10.0, 0.0, 5.0, 5.0);
+        }
+
+        let selected = cache.nodes_in_selection_box_sorted(0.0, 0.0, 2000.0, 10.0);
+
+        assert_eq!(selected, vec![1, 2, 3, 5, 7, 19, 42, 100]);
+    }
+
     // ========================================================================
     // links_in_selection_box() - Link Selection Query
     // ========================================================================
@@ -630,6 +1951,60 @@ mod tests {
         assert!(selected.is_empty());
     }
 
+    #[test]
+    fn test_links_in_selection_box_sorted_is_ascending() {
+        let cache = setup_test_cache();
+        let links_data = vec![(9, 1001, 2001), (2, 2001, 1001), (5, 1001, 2001)];
+
+        let selected =
+            cache.links_in_selection_box_sorted(90.0, 15.0, 20.0, 20.0, links_data.into_iter());
+
+        assert_eq!(selected, vec![2, 5, 9]);
+    }
+
+    // ========================================================================
+    // visible_nodes() / visible_links() - Viewport Culling
+    // ========================================================================
+
+    #[test]
+    fn test_visible_nodes_inside_and_outside_viewport() {
+        let mut cache = setup_test_cache();
+        cache.update_node_rect(3, 2000.0, 2000.0, 100.0, 50.0);
+        // Node 4 straddles the viewport's right edge
+        cache.update_node_rect(4, 380.0, 0.0, 100.0, 50.0);
+
+        assert_eq!(cache.visible_nodes((0.0, 0.0, 400.0, 300.0)), vec![1, 2, 4]);
+        assert_eq!(cache.visible_nodes((1900.0, 1900.0, 400.0, 300.0)), vec![3]);
+        assert!(cache.visible_nodes((-500.0, -500.0, 100.0, 100.0)).is_empty());
+    }
+
+    #[test]
+    fn test_visible_links_inside_and_outside_viewport() {
+        let mut cache = setup_test_cache();
+        cache.update_node_rect(3, 2000.0, 2000.0, 100.0, 50.0);
+        cache.handle_pin_report(3001, 3, 1, 0.0, 25.0);
+        let links = vec![(1, 1001, 2001), (2, 1001, 3001), (3, 1001, 9999)];
+
+        // Link 1 lies in view, link 2 starts in view, link 3 has an unknown pin
+        assert_eq!(cache.visible_links((0.0, 0.0, 400.0, 300.0), links.clone(), 50.0), vec![1, 2]);
+        // Only the far end of link 2 is near this viewport
+        let far = (1900.0, 1900.0, 400.0, 300.0);
+        assert_eq!(cache.visible_links(far, links.clone(), 50.0), vec![2]);
+        assert!(cache.visible_links((-500.0, -500.0, 100.0, 100.0), links, 50.0).is_empty());
+    }
+
+    #[test]
+    fn test_visible_links_keeps_curve_crossing_viewport() {
+        let mut cache = setup_test_cache();
+        cache.update_node_rect(3, 1000.0, 0.0, 100.0, 50.0);
+        cache.handle_pin_report(3001, 3, 1, 0.0, 25.0);
+        let links = vec![(7, 1001, 3001)];
+
+        // Both endpoints (100, 25) and (1000, 25) are outside this viewport
+        assert_eq!(cache.visible_links((400.0, 0.0, 100.0, 100.0), links.clone(), 50.0), vec![7]);
+        assert!(cache.visible_links((400.0, 200.0, 100.0, 100.0), links, 50.0).is_empty());
+    }
+
     // ========================================================================
     // compute_link_path_screen() - World→Screen Path Generation
     // ========================================================================
@@ -678,4 +2053,506 @@ mod tests {
             .compute_link_path_screen(9999, 2001, 1.0, 0.0, 0.0, 50.0)
             .is_none());
     }
+
+    // ========================================================================
+    // build_pin_index() - Spatial Index
+    // ========================================================================
+
+    #[test]
+    fn test_pin_index_find_pin_matches_linear_scan() {
+        let mut cache = setup_test_cache();
+        cache.build_pin_index(32.0);
+        assert!(cache.has_pin_index());
+        assert_eq!(cache.find_pin_at(100.0, 25.0, 10.0), 1001);
+        assert_eq!(cache.find_pin_at(205.0, 128.0, 10.0), 2001);
+        assert_eq!(cache.find_pin_at(500.0, 500.0, 10.0), 0);
+    }
+
+    #[test]
+    fn test_pin_index_hit_across_cell_boundary() {
+        let mut cache = setup_test_cache();
+        // Pin 1001 at (100, 25) lies in cell (3, 0); query from cell (2, 0)
+        cache.build_pin_index(32.0);
+        assert_eq!(cache.find_pin_at(94.0, 25.0, 10.0), 1001);
+    }
+
+    #[test]
+    fn test_pin_index_follows_pin_report() {
+        let mut cache = setup_test_cache();
+        cache.build_pin_index(32.0);
+        cache.handle_pin_report(1001, 1, 2, 10.0, 10.0);
+        assert_eq!(cache.find_pin_at(100.0, 25.0, 5.0), 0);
+        assert_eq!(cache.find_pin_at(10.0, 10.0, 5.0), 1001);
+
+        // New pin reported after the index was built
+        cache.handle_pin_report(1002, 1, 1, 50.0, 0.0);
+        assert_eq!(cache.find_pin_at(50.0, 0.0, 5.0), 1002);
+    }
+
+    #[test]
+    fn test_pin_index_follows_node_move() {
+        let mut cache = setup_test_cache();
+        cache.build_pin_index(32.0);
+        cache.update_node_rect(2, 1000.0, 1000.0, 100.0, 50.0);
+        assert_eq!(cache.find_pin_at(200.0, 125.0, 5.0), 0);
+        assert_eq!(cache.find_pin_at(1000.0, 1025.0, 5.0), 2001);
+    }
+
+    #[test]
+    fn test_pin_index_pin_reported_before_node_rect() {
+        let mut cache: GeometryCache<SimpleNodeGeometry> = GeometryCache::new();
+        cache.build_pin_index(32.0);
+        cache.handle_pin_report(7, 3, 1, 5.0, 5.0);
+        assert_eq!(cache.find_pin_at(5.0, 5.0, 5.0), 0);
+        cache.update_node_rect(3, 100.0, 100.0, 50.0, 50.0);
+        assert_eq!(cache.find_pin_at(105.0, 105.0, 5.0), 7);
+    }
+
+    #[test]
+    fn test_pin_index_follows_removal() {
+        let mut cache = setup_test_cache();
+        cache.build_pin_index(32.0);
+        cache.remove_pin(1001);
+        assert_eq!(cache.find_pin_at(100.0, 25.0, 10.0), 0);
+        cache.remove_node(2);
+        assert_eq!(cache.find_pin_at(200.0, 125.0, 10.0), 0);
+    }
+
+    #[test]
+    fn test_pin_index_after_direct_mutation() {
+        let mut cache = setup_test_cache();
+        cache.build_pin_index(32.0);
+        cache.pin_positions.insert(
+            3001,
+            StoredPin { node_id: 1, pin_type: 1, rel_x: 0.0, rel_y: 0.0 },
+        );
+        // Stale index is bypassed, not trusted
+        assert!(cache.fresh_pin_index().is_none());
+        assert_eq!(cache.find_pin_at(0.0, 0.0, 5.0), 3001);
+        cache.rebuild_pin_index();
+        assert!(cache.fresh_pin_index().is_some());
+        assert_eq!(cache.find_pin_at(0.0, 0.0, 5.0), 3001);
+
+        cache.clear_pin_index();
+        assert!(!cache.has_pin_index());
+        assert_eq!(cache.find_pin_at(0.0, 0.0, 5.0), 3001);
+    }
+
+    #[test]
+    fn test_pin_index_resyncs_on_next_report() {
+        let mut cache = setup_test_cache();
+        cache.build_pin_index(8.0);
+        cache.set_pin_hit_radius(2001, Some(40.0));
+        // Move node 2 behind the cache's back: pin 2001 now sits at (500, 525)
+        cache.node_rects.insert(
+            2,
+            SimpleNodeGeometry { id: 2, x: 500.0, y: 500.0, width: 100.0, height: 50.0 },
+        );
+        assert_eq!(cache.find_pin_at(200.0, 125.0, 5.0), 0);
+        assert_eq!(cache.find_pin_at(535.0, 525.0, 5.0), 2001);
+
+        // The next report rebuilds the grid, which must still honour the per-pin radius
+        cache.handle_pin_report(1002, 1, 1, 50.0, 0.0);
+        assert!(cache.fresh_pin_index().is_some());
+        assert_eq!(cache.find_pin_at(535.0, 525.0, 5.0), 2001);
+        assert_eq!(cache.find_pin_at(50.0, 0.0, 5.0), 1002);
+
+        // Removals on a stale index leave it stale rather than half-updated
+        cache.pin_positions.remove(&1002);
+        cache.remove_pin(1001);
+        assert!(cache.fresh_pin_index().is_none());
+        assert_eq!(cache.find_pin_at(50.0, 0.0, 5.0), 0);
+        cache.update_node_rect(1, 0.0, 0.0, 100.0, 50.0);
+        assert!(cache.fresh_pin_index().is_some());
+        assert_eq!(cache.find_pin_at(50.0, 0.0, 5.0), 0);
+    }
+
+    #[test]
+    fn test_pin_index_radius_larger_than_grid() {
+        let mut cache = setup_test_cache();
+        cache.build_pin_index(1.0);
+        assert_ne!(cache.find_pin_at(150.0, 75.0, 1.0e6), 0);
+    }
+
+    #[test]
+    fn test_pin_hit_radius_with_and_without_index() {
+        let mut cache = setup_test_cache();
+        assert!(cache.set_pin_hit_radius(1001, Some(40.0)));
+        assert!(!cache.set_pin_hit_radius(9999, Some(40.0)));
+        assert_eq!(cache.pin_hit_radius(1001), Some(40.0));
+
+        // 35 units right of pin 1001 at (100, 25), far outside the caller's radius
+        assert_eq!(cache.find_pin_at(135.0, 25.0, 5.0), 1001);
+        // The index must widen its query to reach the large pin from a far cell
+        cache.build_pin_index(8.0);
+        assert_eq!(cache.find_pin_at(135.0, 25.0, 5.0), 1001);
+
+        cache.set_pin_hit_radius(1001, None);
+        assert_eq!(cache.find_pin_at(135.0, 25.0, 5.0), 0);
+    }
+
+    #[test]
+    fn test_pin_hit_radius_zero_and_removal() {
+        let mut cache = setup_test_cache();
+        cache.set_pin_hit_radius(2001, Some(0.0));
+        assert_eq!(cache.find_pin_at(201.0, 125.0, 5.0), 0);
+        assert_eq!(cache.find_pin_at(200.0, 125.0, 5.0), 2001);
+
+        cache.set_pin_hit_radius(1001, Some(40.0));
+        cache.remove_pin(1001);
+        cache.handle_pin_report(1001, 1, 2, 100.0, 25.0);
+        assert_eq!(cache.pin_hit_radius(1001), None);
+        assert_eq!(cache.max_pin_hit_radius, 0.0);
+    }
+
+    #[test]
+    fn test_pin_index_touches_few_pins_for_10k() {
+        let mut cache: GeometryCache<SimpleNodeGeometry> = GeometryCache::new();
+        // 100x100 nodes on a 100-unit pitch, one pin each
+        for i in 0..100 {
+            for j in 0..100 {
+                let id = i * 100 + j + 1;
+                cache.update_node_rect(id, i as f32 * 100.0, j as f32 * 100.0, 80.0, 40.0);
+                cache.handle_pin_report(id + 100_000, id, 2, 80.0, 20.0);
+            }
+        }
+
+        let (x, y, radius) = (4_380.0, 5_120.0, 10.0);
+        let linear = cache.get_absolute_pins().count();
+        let expected = cache.find_pin_at(x, y, radius);
+
+        cache.build_pin_index(50.0);
+        let index = cache.pin_index.as_ref().unwrap();
+        let touched = cache.indexed_pins_near(index, x, y, radius).len();
+
+        assert_eq!(linear, 10_000);
+        assert!(touched <= 4, "indexed query touched {touched} pins");
+        assert_eq!(cache.find_pin_at(x, y, radius), expected);
+        assert_eq!(expected, 43 * 100 + 51 + 1 + 100_000);
+    }
+
+    // ========================================================================
+    // nodes_in_selection_box_indexed() - Quadtree Selection
+    // ========================================================================
+
+    #[test]
+    fn test_indexed_selection_matches_brute_force() {
+        // Seeded LCG so failures are reproducible
+        let mut seed: u64 = 0x5eed_b0c5;
+        let mut range = |min: f32, max: f32| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            min + ((seed >> 40) as f32 / (1u64 << 24) as f32) * (max - min)
+        };
+
+        let mut cache = setup_test_cache();
+        for id in 3..500 {
+            let (x, y) = (range(-1000.0, 1000.0), range(-1000.0, 1000.0));
+            cache.update_node_rect(id, x, y, range(20.0, 200.0), range(20.0, 120.0));
+        }
+        for _ in 0..300 {
+            let (x, y) = (range(-1200.0, 1200.0), range(-1200.0, 1200.0));
+            let (w, h) = (range(0.0, 800.0), range(0.0, 800.0));
+            let mut expected = cache.nodes_in_selection_box(x, y, w, h);
+            let mut indexed = cache.nodes_in_selection_box_indexed(x, y, w, h);
+            expected.sort();
+            indexed.sort();
+            assert_eq!(indexed, expected, "box ({x}, {y}, {w}, {h})");
+        }
+    }
+
+    #[test]
+    fn test_indexed_selection_sees_node_changes() {
+        let mut cache = setup_test_cache();
+        assert_eq!(cache.nodes_in_selection_box_indexed(190.0, 90.0, 20.0, 20.0), vec![2]);
+
+        cache.update_node_rect(2, 800.0, 800.0, 100.0, 50.0);
+        assert!(cache.nodes_in_selection_box_indexed(190.0, 90.0, 20.0, 20.0).is_empty());
+        assert_eq!(cache.nodes_in_selection_box_indexed(790.0, 790.0, 20.0, 20.0), vec![2]);
+
+        cache.remove_node(2);
+        assert!(cache.nodes_in_selection_box_indexed(790.0, 790.0, 20.0, 20.0).is_empty());
+    }
+
+    #[test]
+    fn test_indexed_selection_after_direct_mutation() {
+        let mut cache = setup_test_cache();
+        assert!(cache.nodes_in_selection_box_indexed(500.0, 500.0, 10.0, 10.0).is_empty());
+        cache.node_rects.insert(
+            3,
+            SimpleNodeGeometry { id: 3, x: 500.0, y: 500.0, width: 10.0, height: 10.0 },
+        );
+        assert_eq!(cache.nodes_in_selection_box_indexed(500.0, 500.0, 10.0, 10.0), vec![3]);
+    }
+
+    // ========================================================================
+    // snapshot() / restore() - Save and Load
+    // ========================================================================
+
+    #[test]
+    fn test_snapshot_is_sorted_and_complete() {
+        let cache = setup_test_cache();
+        let snapshot = cache.snapshot();
+
+        let node_ids: Vec<i32> = snapshot.nodes.iter().map(|n| n.id).collect();
+        let pin_ids: Vec<i32> = snapshot.pins.iter().map(|&(id, _)| id).collect();
+        assert_eq!(node_ids, vec![1, 2]);
+        assert_eq!(pin_ids, vec![1001, 2001]);
+        assert!(snapshot.links.is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_with_links() {
+        let cache = setup_test_cache();
+        let link = SimpleLink::new(5, 1001, 2001, slint::Color::from_rgb_u8(1, 2, 3));
+        let snapshot = cache.snapshot().with_links(vec![link]);
+
+        assert_eq!(snapshot.links.len(), 1);
+        assert_eq!(snapshot.links[0].id, 5);
+        assert_eq!(snapshot.links[0].color, slint::Color::from_rgb_u8(1, 2, 3));
+    }
+
+    #[test]
+    fn test_restore_replaces_contents() {
+        let source = setup_test_cache();
+        let mut target: GeometryCache<SimpleNodeGeometry> = GeometryCache::new();
+        target.update_node_rect(99, 0.0, 0.0, 10.0, 10.0);
+        target.build_pin_index(32.0);
+
+        target.restore(&source.snapshot());
+
+        assert_eq!(target.node_rects, source.node_rects);
+        assert_eq!(target.pin_positions, source.pin_positions);
+        assert_eq!(target.find_pin_at(100.0, 25.0, 5.0), 1001);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_json_round_trip() {
+        let cache = setup_test_cache();
+        let link = SimpleLink::new(5, 1001, 2001, slint::Color::from_argb_u8(200, 10, 20, 30));
+        let snapshot = cache.snapshot().with_links(vec![link]);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let loaded: GraphSnapshot = serde_json::from_str(&json).unwrap();
+
+        let mut restored: GeometryCache<SimpleNodeGeometry> = GeometryCache::new();
+        restored.restore(&loaded);
+
+        assert_eq!(restored.node_rects, cache.node_rects);
+        assert_eq!(restored.pin_positions, cache.pin_positions);
+        assert_eq!(loaded.links.len(), 1);
+        assert_eq!(loaded.links[0].color, slint::Color::from_argb_u8(200, 10, 20, 30));
+        assert_eq!(loaded.links[0].end_pin_id, 2001);
+    }
+
+    // ========================================================================
+    // graph_bounds()
+    // ========================================================================
+
+    #[test]
+    fn test_graph_bounds_empty_cache() {
+        let cache: GeometryCache<SimpleNodeGeometry> = GeometryCache::new();
+        assert_eq!(cache.graph_bounds(), None);
+    }
+
+    #[test]
+    fn test_graph_bounds_single_node() {
+        let mut cache = GeometryCache::new();
+        cache.update_node_rect(1, 10.0, 20.0, 100.0, 50.0);
+        assert_eq!(cache.graph_bounds(), Some((10.0, 20.0, 110.0, 70.0)));
+    }
+
+    #[test]
+    fn test_graph_bounds_negative_coordinates() {
+        let mut cache = GeometryCache::new();
+        cache.update_node_rect(1, -200.0, -150.0, 100.0, 50.0);
+        cache.update_node_rect(2, -50.0, 30.0, 80.0, 40.0);
+        cache.update_node_rect(3, -120.0, -300.0, 60.0, 60.0);
+        assert_eq!(cache.graph_bounds(), Some((-200.0, -300.0, 30.0, 70.0)));
+    }
+
+    // ========================================================================
+    // apply_reports()
+    // ========================================================================
+
+    #[test]
+    fn test_apply_reports_matches_individual_reports() {
+        let nodes: Vec<(i32, f32, f32, f32, f32)> = (0..50)
+            .map(|i| (i, i as f32 * 150.0, (i % 7) as f32 * 80.0, 100.0, 50.0))
+            .collect();
+        // Two pins per node, plus a later report that moves a pin to another node
+        let mut pins: Vec<(i32, i32, i32, f32, f32)> = nodes
+            .iter()
+            .flat_map(|&(id, ..)| [(id * 10, id, 1, 0.0, 25.0), (id * 10 + 1, id, 2, 100.0, 25.0)])
+            .collect();
+        pins.push((11, 3, 2, 100.0, 10.0));
+
+        let mut individual = GeometryCache::new();
+        individual.build_pin_index(32.0);
+        for &(id, x, y, w, h) in &nodes {
+            individual.handle_node_rect_report(id, x, y, w, h);
+        }
+        for &(pin_id, node_id, pin_type, rel_x, rel_y) in &pins {
+            individual.handle_pin_report(pin_id, node_id, pin_type, rel_x, rel_y);
+        }
+
+        let mut bulk = GeometryCache::new();
+        bulk.build_pin_index(32.0);
+        bulk.apply_reports(&nodes, &pins);
+
+        assert_eq!(bulk.node_rects, individual.node_rects);
+        assert_eq!(bulk.pin_positions, individual.pin_positions);
+        for (&pin_id, pin) in &bulk.pin_positions {
+            let rect = bulk.node_rects[&pin.node_id];
+            let (x, y) = (rect.x + pin.rel_x, rect.y + pin.rel_y);
+            assert_eq!(bulk.find_pin_at(x, y, 2.0), individual.find_pin_at(x, y, 2.0));
+            assert_eq!(bulk.find_pin_at(x, y, 2.0), pin_id);
+        }
+        let mut bulk_hits = bulk.nodes_in_selection_box_indexed(0.0, 0.0, 1000.0, 200.0);
+        let mut individual_hits = individual.nodes_in_selection_box_indexed(0.0, 0.0, 1000.0, 200.0);
+        bulk_hits.sort_unstable();
+        individual_hits.sort_unstable();
+        assert_eq!(bulk_hits, individual_hits);
+    }
+
+    #[test]
+    fn test_apply_reports_updates_existing_entries() {
+        let mut cache = setup_test_cache();
+        cache.apply_reports(&[(1, 10.0, 10.0, 100.0, 50.0)], &[(1001, 1, 2, 90.0, 20.0)]);
+        assert_eq!(cache.node_rects.len(), 2);
+        assert_eq!(cache.node_rects[&1].x, 10.0);
+        assert_eq!(cache.pin_positions[&1001].rel_x, 90.0);
+        assert_eq!(cache.find_pin_at(100.0, 30.0, 1.0), 1001);
+    }
+
+    // ========================================================================
+    // nodes_in_polygon()
+    // ========================================================================
+
+    #[test]
+    fn test_nodes_in_polygon_uses_world_rects() {
+        let cache = setup_test_cache();
+        // Triangle around node 2's center (250, 125) but not node 1's (50, 25)
+        let triangle = [(200.0, 80.0), (320.0, 80.0), (260.0, 200.0)];
+        assert_eq!(cache.nodes_in_polygon(&triangle), vec![2]);
+    }
+
+    #[test]
+    fn test_links_in_polygon_resolves_pins() {
+        let cache = setup_test_cache();
+        // Link 1001 (100, 25) -> 2001 (200, 125); lasso around its midpoint only
+        let lasso = [(130.0, 60.0), (170.0, 60.0), (170.0, 90.0), (130.0, 90.0)];
+        let links = vec![(1, 1001, 2001), (2, 1001, 9999)];
+        assert_eq!(cache.links_in_polygon(&lasso, links.into_iter(), 50.0, 20), vec![1]);
+    }
+
+    // ========================================================================
+    // Pin sides
+    // ========================================================================
+
+    fn control_points(path: &str) -> ((f32, f32), (f32, f32)) {
+        let n: Vec<f32> = path.split(' ').filter_map(|t| t.parse().ok()).collect();
+        ((n[2], n[3]), (n[4], n[5]))
+    }
+
+    #[test]
+    fn test_right_side_pin_control_point_to_its_right() {
+        let mut cache = setup_test_cache();
+        assert!(cache.set_pin_side(1001, PinSide::Right));
+        assert!(cache.set_pin_side(2001, PinSide::Top));
+
+        // 1001 at (100, 25), 2001 at (200, 125)
+        let path = cache.compute_link_path(1001, 2001, 1.0, 50.0).unwrap();
+        let (ctrl1, ctrl2) = control_points(&path);
+        assert_eq!(ctrl1, (150.0, 25.0));
+        assert_eq!(ctrl2, (200.0, 75.0));
+    }
+
+    #[test]
+    fn test_left_side_pin_control_point_to_its_left() {
+        let mut cache = setup_test_cache();
+        cache.set_pin_side(1001, PinSide::Left);
+
+        let path = cache.compute_link_path_world(1001, 2001, 50.0).unwrap();
+        let (ctrl1, _) = control_points(&path);
+        assert!(ctrl1.0 < 100.0);
+        assert_eq!(ctrl1.1, 25.0);
+    }
+
+    #[test]
+    fn test_auto_sides_match_default_path() {
+        let cache = setup_test_cache();
+        assert_eq!(cache.pin_side(1001), PinSide::Auto);
+        assert_eq!(
+            cache.compute_link_path(1001, 2001, 1.0, 50.0).unwrap(),
+            generate_bezier_path(100.0, 25.0, 200.0, 125.0, 1.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn test_pin_side_survives_reports() {
+        let mut cache = setup_test_cache();
+        cache.set_pin_side(1001, PinSide::Bottom);
+        cache.handle_pin_report(1001, 1, 2, 90.0, 50.0);
+        cache.apply_reports(&[], &[(1001, 1, 2, 95.0, 50.0)]);
+        assert_eq!(cache.pin_side(1001), PinSide::Bottom);
+        assert!(!cache.set_pin_side(9999, PinSide::Left));
+        assert_eq!(cache.pin_side(9999), PinSide::Auto);
+    }
+
+    #[test]
+    fn test_pin_side_dropped_with_pin_and_kept_in_snapshot() {
+        let mut cache = setup_test_cache();
+        cache.set_pin_side(1001, PinSide::Bottom);
+        cache.set_pin_side(2001, PinSide::Top);
+
+        let snapshot = cache.snapshot();
+        assert_eq!(snapshot.pin_sides, vec![(1001, PinSide::Bottom), (2001, PinSide::Top)]);
+
+        cache.remove_pin(1001);
+        cache.remove_node(2);
+        cache.handle_pin_report(1001, 1, 2, 100.0, 25.0);
+        assert_eq!(cache.pin_side(1001), PinSide::Auto);
+        assert_eq!(cache.pin_side(2001), PinSide::Auto);
+
+        cache.restore(&snapshot);
+        assert_eq!(cache.pin_side(1001), PinSide::Bottom);
+        assert_eq!(cache.pin_side(2001), PinSide::Top);
+    }
+
+    // ========================================================================
+    // snap_to_pin()
+    // ========================================================================
+
+    #[test]
+    fn test_snap_to_pin_returns_nearby_input() {
+        let cache = setup_test_cache();
+        // Input pin 2001 sits at (200, 125)
+        assert_eq!(cache.snap_to_pin(190.0, 120.0, 20.0, |_| true), Some((2001, 200.0, 125.0)));
+        assert_eq!(cache.snap_to_pin(150.0, 75.0, 20.0, |_| true), None);
+    }
+
+    #[test]
+    fn test_snap_to_pin_filter_excludes_outputs() {
+        let mut cache = setup_test_cache();
+        // Output pin 1001 at (100, 25); add an input pin 15px further away
+        cache.handle_pin_report(1002, 1, 1, 100.0, 40.0);
+
+        let any = cache.snap_to_pin(100.0, 20.0, 30.0, |_| true);
+        assert_eq!(any.map(|(id, _, _)| id), Some(1001));
+
+        let inputs_only = cache.snap_to_pin(100.0, 20.0, 30.0, |pin| pin.pin_type == 1);
+        assert_eq!(inputs_only, Some((1002, 100.0, 40.0)));
+        assert_eq!(cache.snap_to_pin(100.0, 20.0, 10.0, |pin| pin.pin_type == 1), None);
+    }
+
+    #[test]
+    fn test_snap_to_pin_picks_closest_with_index() {
+        let mut cache = setup_test_cache();
+        cache.handle_pin_report(1002, 1, 1, 100.0, 40.0);
+        cache.build_pin_index(16.0);
+
+        let snapped = cache.snap_to_pin(100.0, 35.0, 30.0, |_| true);
+        assert_eq!(snapped, Some((1002, 100.0, 40.0)));
+    }
 }
\ No newline at end of file
@@ -16,13 +16,73 @@
 //!
 //! // Get the cache for use elsewhere
 //! let cache = tracker.cache();
+//!
+//! // Refresh links once every node and pin has reported its geometry
+//! tracker.on_complete(&[1, 2], &[3, 4], move || window.invoke_refresh_links());
+//!
+//! // During a drag, recompute only the links touching nodes that moved
+//! for link_id in tracker.take_dirty_links(links.iter()) {
+//!     update_link_path(link_id);
+//! }
 //! ```
 
+use crate::graph::{GraphLogic, LinkModel};
 use crate::hit_test::{NodeGeometry, SimpleNodeGeometry};
 use crate::state::GeometryCache;
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+/// One-shot completion callback waiting for a set of node and pin reports.
+struct PendingCompletion {
+    nodes: Vec<i32>,
+    pins: Vec<i32>,
+    callback: Box<dyn FnOnce()>,
+}
+
+/// Node and pin IDs that have reported geometry since the last reset.
+#[derive(Default)]
+struct ReportLog {
+    nodes: HashSet<i32>,
+    pins: HashSet<i32>,
+    pending: Option<PendingCompletion>,
+    /// Nodes whose geometry changed since the last `take_dirty_links`
+    dirty_nodes: HashSet<i32>,
+    /// The two most recent `record_positions` snapshots, oldest first
+    snapshots: [Option<HashMap<i32, (f32, f32)>>; 2],
+}
+
+impl ReportLog {
+    fn has_all(&self, nodes: &[i32], pins: &[i32]) -> bool {
+        nodes.iter().all(|id| self.nodes.contains(id)) && pins.iter().all(|id| self.pins.contains(id))
+    }
+
+    /// Take the pending callback if its reports have all arrived.
+    fn take_ready(&mut self) -> Option<Box<dyn FnOnce()>> {
+        let ready = matches!(&self.pending, Some(p) if self.has_all(&p.nodes, &p.pins));
+        if ready {
+            self.pending.take().map(|p| p.callback)
+        } else {
+            None
+        }
+    }
+}
+
+/// Record a report and run the completion callback if it became ready.
+///
+/// The callback runs after the log borrow is released so it may call back
+/// into the tracker.
+fn record(log: &RefCell<ReportLog>, update: impl FnOnce(&mut ReportLog)) {
+    let ready = {
+        let mut log = log.borrow_mut();
+        update(&mut log);
+        log.take_ready()
+    };
+    if let Some(callback) = ready {
+        callback();
+    }
+}
+
 /// Convenience wrapper for [`GeometryCache`] that provides ready-to-use Slint callbacks.
 ///
 /// This eliminates the boilerplate of creating a cache, wrapping it in `Rc<RefCell<_>>`,
@@ -50,6 +110,7 @@ use std::rc::Rc;
 /// ```
 pub struct GeometryTracker<N = SimpleNodeGeometry> {
     cache: Rc<RefCell<GeometryCache<N>>>,
+    reports: Rc<RefCell<ReportLog>>,
 }
 
 impl<N> Default for GeometryTracker<N> {
@@ -61,9 +122,7 @@ impl<N> Default for GeometryTracker<N> {
 impl<N> GeometryTracker<N> {
     /// Create a new geometry tracker with an empty cache.
     pub fn new() -> Self {
-        Self {
-            cache: Rc::new(RefCell::new(GeometryCache::new())),
-        }
+        Self::with_cache(Rc::new(RefCell::new(GeometryCache::new())))
     }
 
     /// Create a tracker wrapping an existing cache.
@@ -71,7 +130,10 @@ impl<N> GeometryTracker<N> {
     /// Useful when you need to initialize the cache with data before
     /// connecting callbacks.
     pub fn with_cache(cache: Rc<RefCell<GeometryCache<N>>>) -> Self {
-        Self { cache }
+        Self {
+            cache,
+            reports: Rc::default(),
+        }
     }
 
     /// Get a clone of the internal cache reference.
@@ -81,6 +143,96 @@ impl<N> GeometryTracker<N> {
     pub fn cache(&self) -> Rc<RefCell<GeometryCache<N>>> {
         self.cache.clone()
     }
+
+    /// Whether every listed node and pin has reported through this tracker's
+    /// callbacks since creation or the last [`reset_reports`](Self::reset_reports).
+    pub fn is_complete(&self, expected_nodes: &[i32], expected_pins: &[i32]) -> bool {
+        self.reports.borrow().has_all(expected_nodes, expected_pins)
+    }
+
+    /// Run `callback` once, as soon as every listed node and pin has reported.
+    ///
+    /// Fires immediately if the reports are already complete. Registering a
+    /// new callback replaces one that has not fired yet.
+    pub fn on_complete(
+        &self,
+        expected_nodes: &[i32],
+        expected_pins: &[i32],
+        callback: impl FnOnce() + 'static,
+    ) {
+        record(&self.reports, |log| {
+            log.pending = Some(PendingCompletion {
+                nodes: expected_nodes.to_vec(),
+                pins: expected_pins.to_vec(),
+                callback: Box::new(callback),
+            });
+        });
+    }
+
+    /// Forget which nodes and pins have reported, e.g. after loading a new graph.
+    ///
+    /// A pending [`on_complete`](Self::on_complete) callback stays registered.
+    /// Dirty nodes are kept until [`take_dirty_links`](Self::take_dirty_links).
+    pub fn reset_reports(&self) {
+        let mut log = self.reports.borrow_mut();
+        log.nodes.clear();
+        log.pins.clear();
+    }
+
+    /// Mark a node's links as needing a path update.
+    ///
+    /// The tracker's callbacks already do this for every node rect report and
+    /// for the owning node of every pin report; call it for changes made to
+    /// the cache directly.
+    pub fn mark_node_dirty(&self, node_id: i32) {
+        self.reports.borrow_mut().dirty_nodes.insert(node_id);
+    }
+
+    /// IDs of the links attached to a dirty node, clearing the dirty set.
+    ///
+    /// Pins are resolved to nodes through the tracker's cache as in
+    /// [`GraphLogic::links_orphaned_by_nodes`], so after one node of a chain
+    /// moves only its two adjacent links are returned.
+    pub fn take_dirty_links<I, L>(&self, links: I) -> Vec<i32>
+    where
+        I: IntoIterator<Item = L>,
+        L: LinkModel,
+    {
+        let dirty: Vec<i32> = self.reports.borrow_mut().dirty_nodes.drain().collect();
+        if dirty.is_empty() {
+            return Vec::new();
+        }
+        GraphLogic::links_orphaned_by_nodes(&dirty, links, &self.cache.borrow())
+    }
+
+    /// Record a snapshot of node positions for [`has_settled`](Self::has_settled).
+    ///
+    /// Call once per animation frame with the current `node_id -> (x, y)`
+    /// positions. Only the last two snapshots are kept.
+    pub fn record_positions(&self, positions: &HashMap<i32, (f32, f32)>) {
+        let mut log = self.reports.borrow_mut();
+        log.snapshots.swap(0, 1);
+        log.snapshots[1] = Some(positions.clone());
+    }
+
+    /// Whether no node moved more than `epsilon` along either axis between the
+    /// last two [`record_positions`](Self::record_positions) snapshots.
+    ///
+    /// Use it to stop a layout animation loop. Returns `false` until two
+    /// snapshots have been recorded, and when a node was added or removed
+    /// between them.
+    pub fn has_settled(&self, epsilon: f32) -> bool {
+        let log = self.reports.borrow();
+        let [Some(previous), Some(latest)] = &log.snapshots else {
+            return false;
+        };
+        previous.len() == latest.len()
+            && latest.iter().all(|(id, &(x, y))| {
+                previous
+                    .get(id)
+                    .is_some_and(|&(px, py)| (x - px).abs() <= epsilon && (y - py).abs() <= epsilon)
+            })
+    }
 }
 
 impl<N> GeometryTracker<N>
@@ -99,10 +251,15 @@ where
     /// `(pin_id: i32, node_id: i32, pin_type: i32, rel_x: f32, rel_y: f32)`
     pub fn pin_position_callback(&self) -> impl Fn(i32, i32, i32, f32, f32) + Clone {
         let cache = self.cache.clone();
+        let reports = self.reports.clone();
         move |pin_id, node_id, pin_type, rel_x, rel_y| {
             cache
                 .borrow_mut()
                 .handle_pin_report(pin_id, node_id, pin_type, rel_x, rel_y);
+            record(&reports, |log| {
+                log.pins.insert(pin_id);
+                log.dirty_nodes.insert(node_id);
+            });
         }
     }
 }
@@ -125,10 +282,15 @@ impl GeometryTracker<SimpleNodeGeometry> {
     /// For custom node types, use [`node_rect_callback_with`] instead.
     pub fn node_rect_callback(&self) -> impl Fn(i32, f32, f32, f32, f32) + Clone {
         let cache = self.cache.clone();
+        let reports = self.reports.clone();
         move |id, x, y, width, height| {
             cache
                 .borrow_mut()
                 .handle_node_rect_report(id, x, y, width, height);
+            record(&reports, |log| {
+                log.nodes.insert(id);
+                log.dirty_nodes.insert(id);
+            });
         }
     }
 }
@@ -171,9 +333,14 @@ where
         F: Fn(i32, f32, f32, f32, f32) -> N + Clone + 'static,
     {
         let cache = self.cache.clone();
+        let reports = self.reports.clone();
         move |id, x, y, width, height| {
             let node = convert(id, x, y, width, height);
-            cache.borrow_mut().node_rects.insert(id, node);
+            cache.borrow_mut().update_node(id, node);
+            record(&reports, |log| {
+                log.nodes.insert(id);
+                log.dirty_nodes.insert(id);
+            });
         }
     }
 }
@@ -181,6 +348,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graph::SimpleLink;
     use crate::hit_test::SimpleNodeGeometry;
 
     #[test]
@@ -326,4 +494,176 @@ mod tests {
         assert_eq!(node.id, 1);
         assert!(node.extra);
     }
+
+    // ========================================================================
+    // Report completion
+    // ========================================================================
+
+    #[test]
+    fn test_is_complete_after_all_reports() {
+        let tracker = GeometryTracker::new();
+        let node_cb = tracker.node_rect_callback();
+        let pin_cb = tracker.pin_position_callback();
+
+        assert!(!tracker.is_complete(&[1, 2], &[3, 4]));
+        node_cb(1, 0.0, 0.0, 100.0, 50.0);
+        pin_cb(3, 1, 2, 100.0, 25.0);
+        assert!(!tracker.is_complete(&[1, 2], &[3, 4]));
+
+        node_cb(2, 200.0, 0.0, 100.0, 50.0);
+        pin_cb(4, 2, 1, 0.0, 25.0);
+        assert!(tracker.is_complete(&[1, 2], &[3, 4]));
+        assert!(tracker.is_complete(&[], &[]));
+    }
+
+    #[test]
+    fn test_on_complete_fires_once_when_last_report_arrives() {
+        let tracker = GeometryTracker::new();
+        let node_cb = tracker.node_rect_callback();
+        let pin_cb = tracker.pin_position_callback();
+        let fired = Rc::new(RefCell::new(0));
+
+        tracker.on_complete(&[1, 2], &[3], {
+            let fired = fired.clone();
+            move || *fired.borrow_mut() += 1
+        });
+
+        node_cb(1, 0.0, 0.0, 100.0, 50.0);
+        pin_cb(3, 1, 2, 100.0, 25.0);
+        assert_eq!(*fired.borrow(), 0);
+
+        node_cb(2, 200.0, 0.0, 100.0, 50.0);
+        assert_eq!(*fired.borrow(), 1);
+
+        // Later reports don't fire it again
+        node_cb(2, 210.0, 0.0, 100.0, 50.0);
+        pin_cb(3, 1, 2, 100.0, 25.0);
+        assert_eq!(*fired.borrow(), 1);
+    }
+
+    #[test]
+    fn test_on_complete_fires_immediately_when_already_complete() {
+        let tracker = GeometryTracker::new();
+        tracker.node_rect_callback()(1, 0.0, 0.0, 100.0, 50.0);
+        let fired = Rc::new(RefCell::new(false));
+
+        tracker.on_complete(&[1], &[], {
+            let fired = fired.clone();
+            move || *fired.borrow_mut() = true
+        });
+        assert!(*fired.borrow());
+    }
+
+    #[test]
+    fn test_reset_reports_requires_new_reports() {
+        let tracker = GeometryTracker::new();
+        let node_cb = tracker.node_rect_callback();
+        node_cb(1, 0.0, 0.0, 100.0, 50.0);
+        tracker.reset_reports();
+        assert!(!tracker.is_complete(&[1], &[]));
+
+        let fired = Rc::new(RefCell::new(false));
+        tracker.on_complete(&[1], &[], {
+            let fired = fired.clone();
+            move || *fired.borrow_mut() = true
+        });
+        assert!(!*fired.borrow());
+        node_cb(1, 0.0, 0.0, 100.0, 50.0);
+        assert!(*fired.borrow());
+    }
+
+    #[test]
+    fn test_custom_node_callback_records_reports() {
+        let tracker = GeometryTracker::<SimpleNodeGeometry>::new();
+        let callback = tracker.node_rect_callback_with(|id, x, y, width, height| SimpleNodeGeometry {
+            id,
+            x,
+            y,
+            width,
+            height,
+        });
+        callback(7, 0.0, 0.0, 10.0, 10.0);
+        assert!(tracker.is_complete(&[7], &[]));
+    }
+
+    // ========================================================================
+    // Dirty links
+    // ========================================================================
+
+    /// Chain 1 -> 2 -> 3 -> 4; link N connects node N's output to node N+1's input
+    fn chain_tracker() -> (GeometryTracker, Vec<SimpleLink>) {
+        let tracker = GeometryTracker::new();
+        let node_cb = tracker.node_rect_callback();
+        let pin_cb = tracker.pin_position_callback();
+        for id in 1..=4 {
+            node_cb(id, id as f32 * 200.0, 0.0, 100.0, 50.0);
+            pin_cb(id * 10, id, 1, 0.0, 25.0);
+            pin_cb(id * 10 + 1, id, 2, 100.0, 25.0);
+        }
+        let links = (1..=3)
+            .map(|id| SimpleLink::new(id, id * 10 + 1, (id + 1) * 10, slint::Color::default()))
+            .collect();
+        (tracker, links)
+    }
+
+    #[test]
+    fn test_moving_one_node_marks_adjacent_links_dirty() {
+        let (tracker, links) = chain_tracker();
+        tracker.take_dirty_links(links.iter().cloned());
+
+        tracker.node_rect_callback()(2, 420.0, 30.0, 100.0, 50.0);
+        let mut dirty = tracker.take_dirty_links(links.iter().cloned());
+        dirty.sort_unstable();
+        assert_eq!(dirty, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_take_dirty_links_clears_dirty_set() {
+        let (tracker, links) = chain_tracker();
+        assert_eq!(tracker.take_dirty_links(links.iter().cloned()).len(), 3);
+        assert!(tracker.take_dirty_links(links.iter().cloned()).is_empty());
+
+        tracker.mark_node_dirty(4);
+        assert_eq!(tracker.take_dirty_links(links.iter().cloned()), vec![3]);
+    }
+
+    // ========================================================================
+    // Settling
+    // ========================================================================
+
+    fn snapshot(positions: &[(i32, f32, f32)]) -> HashMap<i32, (f32, f32)> {
+        positions.iter().map(|&(id, x, y)| (id, (x, y))).collect()
+    }
+
+    #[test]
+    fn test_identical_snapshots_have_settled() {
+        let tracker: GeometryTracker = GeometryTracker::new();
+        let frame = snapshot(&[(1, 10.0, 20.0), (2, 300.0, 40.0)]);
+        tracker.record_positions(&frame);
+        assert!(!tracker.has_settled(0.5), "one snapshot is not enough");
+
+        tracker.record_positions(&frame);
+        assert!(tracker.has_settled(0.5));
+    }
+
+    #[test]
+    fn test_differing_snapshots_have_not_settled() {
+        let tracker: GeometryTracker = GeometryTracker::new();
+        tracker.record_positions(&snapshot(&[(1, 10.0, 20.0), (2, 300.0, 40.0)]));
+        tracker.record_positions(&snapshot(&[(1, 10.0, 20.0), (2, 302.0, 40.0)]));
+        assert!(!tracker.has_settled(0.5));
+        assert!(tracker.has_settled(2.0));
+
+        // Only the last two snapshots count
+        tracker.record_positions(&snapshot(&[(1, 10.0, 20.0), (2, 302.2, 40.0)]));
+        assert!(tracker.has_settled(0.5));
+    }
+
+    #[test]
+    fn test_added_node_has_not_settled() {
+        let tracker: GeometryTracker = GeometryTracker::new();
+        tracker.record_positions(&snapshot(&[(1, 0.0, 0.0)]));
+        tracker.record_positions(&snapshot(&[(1, 0.0, 0.0), (2, 0.0, 0.0)]));
+        assert!(!tracker.has_settled(1.0));
+    }
 }
@@ -0,0 +1,341 @@
+//! Undo/redo support for node editor operations.
+//!
+//! The library does not own the application's node and link models, so
+//! commands are plain descriptions of an edit. Undoing a command produces its
+//! [`inverse`](EditCommand::inverse), which the application applies to its own
+//! models (usually through the applier registered with
+//! [`NodeEditorController::set_command_applier`](crate::NodeEditorController::set_command_applier)).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use slint_node_editor::{EditCommand, NodeEditorController};
+//!
+//! let ctrl = NodeEditorController::new();
+//! ctrl.set_command_applier({
+//!     let nodes = nodes.clone();
+//!     move |cmd| match cmd {
+//!         EditCommand::MoveNodes { node_ids, delta_x, delta_y } => {
+//!             // shift the matching rows in `nodes`
+//!         }
+//!         _ => {}
+//!     }
+//! });
+//!
+//! // After a drag has been committed to the model:
+//! ctrl.push_command(EditCommand::MoveNodes { node_ids: vec![1], delta_x: 40.0, delta_y: 0.0 });
+//!
+//! // Ctrl+Z calls the applier with MoveNodes { delta_x: -40.0, .. }
+//! ctrl.undo();
+//! ```
+
+use std::any::Any;
+use std::rc::Rc;
+
+/// A reversible editor operation.
+///
+/// Commands describe edits that have *already been applied* when they are
+/// pushed. Node payloads are application data stored as `Rc<dyn Any>` so a
+/// deleted node can be restored exactly; downcast it in your applier.
+#[derive(Clone, Debug)]
+pub enum EditCommand {
+    /// Nodes were translated by `(delta_x, delta_y)` in world space
+    MoveNodes {
+        node_ids: Vec<i32>,
+        delta_x: f32,
+        delta_y: f32,
+    },
+    /// A node was added
+    CreateNode { node_id: i32, data: Rc<dyn Any> },
+    /// A node was removed
+    DeleteNode { node_id: i32, data: Rc<dyn Any> },
+    /// A link was added
+    CreateLink {
+        link_id: i32,
+        start_pin: i32,
+        end_pin: i32,
+    },
+    /// A link was removed
+    DeleteLink {
+        link_id: i32,
+        start_pin: i32,
+        end_pin: i32,
+    },
+    /// Several commands undone and redone as one step (e.g. a node and its links)
+    Batch(Vec<EditCommand>),
+}
+
+impl EditCommand {
+    /// The command that reverts this one.
+    ///
+    /// Batches are inverted element-wise in reverse order.
+    pub fn inverse(&self) -> EditCommand {
+        match self {
+            Self::MoveNodes {
+                node_ids,
+                delta_x,
+                delta_y,
+            } => Self::MoveNodes {
+                node_ids: node_ids.clone(),
+                delta_x: -delta_x,
+                delta_y: -delta_y,
+            },
+            Self::CreateNode { node_id, data } => Self::DeleteNode {
+                node_id: *node_id,
+                data: data.clone(),
+            },
+            Self::DeleteNode { node_id, data } => Self::CreateNode {
+                node_id: *node_id,
+                data: data.clone(),
+            },
+            Self::CreateLink {
+                link_id,
+                start_pin,
+                end_pin,
+            } => Self::DeleteLink {
+                link_id: *link_id,
+                start_pin: *start_pin,
+                end_pin: *end_pin,
+            },
+            Self::DeleteLink {
+                link_id,
+                start_pin,
+                end_pin,
+            } => Self::CreateLink {
+                link_id: *link_id,
+                start_pin: *start_pin,
+                end_pin: *end_pin,
+            },
+            Self::Batch(commands) => {
+                Self::Batch(commands.iter().rev().map(Self::inverse).collect())
+            }
+        }
+    }
+}
+
+/// Linear undo/redo history of [`EditCommand`]s.
+///
+/// Pushing a new command discards the redo history.
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    undo: Vec<EditCommand>,
+    redo: Vec<EditCommand>,
+    limit: Option<usize>,
+}
+
+impl UndoStack {
+    /// Create an empty, unbounded history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a history that keeps at most `limit` undo steps.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::default()
+        }
+    }
+
+    /// Record a command that has just been applied.
+    pub fn push(&mut self, command: EditCommand) {
+        self.redo.clear();
+        self.undo.push(command);
+        if let Some(limit) = self.limit {
+            if self.undo.len() > limit {
+                let excess = self.undo.len() - limit;
+                self.undo.drain(..excess);
+            }
+        }
+    }
+
+    /// Step back: returns the command to apply to revert the last edit.
+    pub fn undo(&mut self) -> Option<EditCommand> {
+        let command = self.undo.pop()?;
+        let inverse = command.inverse();
+        self.redo.push(command);
+        Some(inverse)
+    }
+
+    /// Step forward: returns the command to apply to redo the last undone edit.
+    pub fn redo(&mut self) -> Option<EditCommand> {
+        let command = self.redo.pop()?;
+        self.undo.push(command.clone());
+        Some(command)
+    }
+
+    /// Whether there is an edit to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Whether there is an undone edit to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Drop all history.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn move_cmd(dx: f32, dy: f32) -> EditCommand {
+        EditCommand::MoveNodes {
+            node_ids: vec![1, 2],
+            delta_x: dx,
+            delta_y: dy,
+        }
+    }
+
+    fn move_delta(cmd: &EditCommand) -> (f32, f32) {
+        match cmd {
+            EditCommand::MoveNodes {
+                delta_x, delta_y, ..
+            } => (*delta_x, *delta_y),
+            other => panic!("expected MoveNodes, got {:?}", other),
+        }
+    }
+
+    // ========================================================================
+    // EditCommand::inverse()
+    // ========================================================================
+
+    #[test]
+    fn test_inverse_move_negates_delta() {
+        let inverse = move_cmd(10.0, -5.0).inverse();
+        assert_eq!(move_delta(&inverse), (-10.0, 5.0));
+    }
+
+    #[test]
+    fn test_inverse_link_create_delete() {
+        let create = EditCommand::CreateLink {
+            link_id: 7,
+            start_pin: 1,
+            end_pin: 2,
+        };
+        match create.inverse() {
+            EditCommand::DeleteLink {
+                link_id,
+                start_pin,
+                end_pin,
+            } => assert_eq!((link_id, start_pin, end_pin), (7, 1, 2)),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            create.inverse().inverse(),
+            EditCommand::CreateLink { link_id: 7, .. }
+        ));
+    }
+
+    #[test]
+    fn test_inverse_node_delete_keeps_payload() {
+        let delete = EditCommand::DeleteNode {
+            node_id: 3,
+            data: Rc::new(String::from("Node C")),
+        };
+        match delete.inverse() {
+            EditCommand::CreateNode { node_id, data } => {
+                assert_eq!(node_id, 3);
+                assert_eq!(data.downcast_ref::<String>().unwrap(), "Node C");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_inverse_batch_reverses_order() {
+        let batch = EditCommand::Batch(vec![
+            EditCommand::DeleteLink {
+                link_id: 1,
+                start_pin: 10,
+                end_pin: 20,
+            },
+            EditCommand::DeleteNode {
+                node_id: 2,
+                data: Rc::new(()),
+            },
+        ]);
+        match batch.inverse() {
+            EditCommand::Batch(items) => {
+                assert!(matches!(items[0], EditCommand::CreateNode { node_id: 2, .. }));
+                assert!(matches!(items[1], EditCommand::CreateLink { link_id: 1, .. }));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    // ========================================================================
+    // UndoStack
+    // ========================================================================
+
+    #[test]
+    fn test_new_stack_cannot_undo_or_redo() {
+        let mut stack = UndoStack::new();
+        assert!(!stack.can_undo());
+        assert!(!stack.can_redo());
+        assert!(stack.undo().is_none());
+        assert!(stack.redo().is_none());
+    }
+
+    #[test]
+    fn test_undo_returns_inverse_and_redo_returns_original() {
+        let mut stack = UndoStack::new();
+        stack.push(move_cmd(10.0, 20.0));
+
+        let undo = stack.undo().unwrap();
+        assert_eq!(move_delta(&undo), (-10.0, -20.0));
+        assert!(stack.can_redo());
+        assert!(!stack.can_undo());
+
+        let redo = stack.redo().unwrap();
+        assert_eq!(move_delta(&redo), (10.0, 20.0));
+        assert!(stack.can_undo());
+        assert!(!stack.can_redo());
+    }
+
+    #[test]
+    fn test_push_clears_redo() {
+        let mut stack = UndoStack::new();
+        stack.push(move_cmd(1.0, 0.0));
+        stack.undo();
+        stack.push(move_cmd(2.0, 0.0));
+        assert!(!stack.can_redo());
+    }
+
+    #[test]
+    fn test_undo_order_is_lifo() {
+        let mut stack = UndoStack::new();
+        stack.push(move_cmd(1.0, 0.0));
+        stack.push(move_cmd(2.0, 0.0));
+        assert_eq!(move_delta(&stack.undo().unwrap()), (-2.0, 0.0));
+        assert_eq!(move_delta(&stack.undo().unwrap()), (-1.0, 0.0));
+    }
+
+    #[test]
+    fn test_limit_drops_oldest() {
+        let mut stack = UndoStack::with_limit(2);
+        stack.push(move_cmd(1.0, 0.0));
+        stack.push(move_cmd(2.0, 0.0));
+        stack.push(move_cmd(3.0, 0.0));
+        assert_eq!(move_delta(&stack.undo().unwrap()), (-3.0, 0.0));
+        assert_eq!(move_delta(&stack.undo().unwrap()), (-2.0, 0.0));
+        assert!(stack.undo().is_none());
+    }
+
+    #[test]
+    fn test_clear_drops_history() {
+        let mut stack = UndoStack::new();
+        stack.push(move_cmd(1.0, 0.0));
+        stack.push(move_cmd(2.0, 0.0));
+        stack.undo();
+        stack.clear();
+        assert!(!stack.can_undo());
+        assert!(!stack.can_redo());
+    }
+}